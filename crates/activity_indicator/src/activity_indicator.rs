@@ -3,9 +3,10 @@ use editor::Editor;
 use extension::ExtensionStore;
 use futures::StreamExt;
 use gpui::{
-    actions, percentage, Animation, AnimationExt as _, AppContext, CursorStyle, EventEmitter,
-    InteractiveElement as _, Model, ParentElement as _, Render, SharedString,
+    actions, percentage, Animation, AnimationExt as _, AppContext, CursorStyle, DismissEvent,
+    EventEmitter, InteractiveElement as _, Model, ParentElement as _, Render, SharedString,
     StatefulInteractiveElement, Styled, Transformation, View, ViewContext, VisualContext as _,
+    WeakView, WindowContext,
 };
 use language::{
     LanguageRegistry, LanguageServerBinaryStatus, LanguageServerId, LanguageServerName,
@@ -14,7 +15,11 @@ use project::{LanguageServerProgress, Project};
 use smallvec::SmallVec;
 use std::{cmp::Reverse, fmt::Write, sync::Arc, time::Duration};
 use ui::{prelude::*, ButtonLike, ContextMenu, PopoverMenu, PopoverMenuHandle};
-use workspace::{item::ItemHandle, StatusItemView, Workspace};
+use workspace::{
+    item::ItemHandle,
+    notifications::{simple_message_notification::MessageNotification, NotificationId},
+    StatusItemView, Workspace,
+};
 
 actions!(activity_indicator, [ShowErrorMessage]);
 
@@ -49,6 +54,50 @@ struct Content {
     on_click: Option<Arc<dyn Fn(&mut ActivityIndicator, &mut ViewContext<ActivityIndicator>)>>,
 }
 
+struct LanguageServerDownloadNotification;
+
+/// Shows a notification while a language server is being downloaded, as the first download
+/// can take a while on slow connections, and offers to cancel it.
+fn update_download_notification(
+    workspace: &WeakView<Workspace>,
+    project: &Model<Project>,
+    languages: &Arc<LanguageRegistry>,
+    name: &LanguageServerName,
+    status: &LanguageServerBinaryStatus,
+    cx: &mut WindowContext,
+) {
+    let notification_id =
+        NotificationId::identified::<LanguageServerDownloadNotification>(name.0.clone());
+    workspace
+        .update(cx, |workspace, cx| {
+            if !matches!(status, LanguageServerBinaryStatus::Downloading) {
+                workspace.dismiss_notification(&notification_id, cx);
+                return;
+            }
+            let project = project.clone();
+            let languages = languages.clone();
+            let name = name.clone();
+            workspace.show_notification(notification_id, cx, |cx| {
+                cx.new_view(|_| {
+                    MessageNotification::new(format!(
+                        "Downloading the {name} language server. This only happens once."
+                    ))
+                    .with_click_message("Cancel")
+                    .on_click(move |cx| {
+                        project.update(cx, |project, cx| {
+                            project.lsp_store().update(cx, |lsp_store, cx| {
+                                lsp_store.stop_language_servers_named(&name, cx)
+                            })
+                        });
+                        languages.update_lsp_status(name.clone(), LanguageServerBinaryStatus::None);
+                        cx.emit(DismissEvent);
+                    })
+                })
+            });
+        })
+        .ok();
+}
+
 impl ActivityIndicator {
     pub fn new(
         workspace: &mut Workspace,
@@ -57,17 +106,30 @@ impl ActivityIndicator {
     ) -> View<ActivityIndicator> {
         let project = workspace.project().clone();
         let auto_updater = AutoUpdater::get(cx);
+        let workspace_handle = cx.view().downgrade();
         let this = cx.new_view(|cx: &mut ViewContext<Self>| {
             let mut status_events = languages.language_server_binary_statuses();
-            cx.spawn(|this, mut cx| async move {
-                while let Some((name, status)) = status_events.next().await {
-                    this.update(&mut cx, |this, cx| {
-                        this.statuses.retain(|s| s.name != name);
-                        this.statuses.push(LspStatus { name, status });
-                        cx.notify();
-                    })?;
+            cx.spawn({
+                let project = project.clone();
+                let languages = languages.clone();
+                |this, mut cx| async move {
+                    while let Some((name, status)) = status_events.next().await {
+                        this.update(&mut cx, |this, cx| {
+                            update_download_notification(
+                                &workspace_handle,
+                                &project,
+                                &languages,
+                                &name,
+                                &status,
+                                cx,
+                            );
+                            this.statuses.retain(|s| s.name != name);
+                            this.statuses.push(LspStatus { name, status });
+                            cx.notify();
+                        })?;
+                    }
+                    anyhow::Ok(())
                 }
-                anyhow::Ok(())
             })
             .detach();
             cx.observe(&project, |_, _, cx| cx.notify()).detach();
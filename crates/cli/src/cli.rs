@@ -15,11 +15,52 @@ pub enum CliRequest {
         urls: Vec<String>,
         wait: bool,
         open_new_workspace: Option<bool>,
+        /// Open each path in its own window, rather than all of them in one workspace.
+        window_per_path: bool,
         dev_server_token: Option<String>,
         env: Option<HashMap<String, String>>,
+        piped_buffer: Option<PipedBuffer>,
+        /// Skip confirmations, like the one for opening many files at once.
+        assume_yes: bool,
+        /// Diff the opened files against their contents at this git ref, like `HEAD~1`.
+        diff_git_ref: Option<String>,
+        /// Open the files read-only, until they're unlocked.
+        read_only: bool,
+        /// Open each file in its own pane, split this way, rather than in tabs.
+        split: Option<SplitLayout>,
+        /// Open and save the files in this encoding, by name like `shift_jis`, rather than UTF-8.
+        encoding: Option<String>,
+        /// Save the files with these line endings, rather than the ones they already use.
+        line_endings: Option<LineEndings>,
     },
 }
 
+/// How to arrange the opened files in pane splits, see `zed --split`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+pub enum SplitLayout {
+    /// Side by side.
+    Vertical,
+    /// Stacked on top of each other.
+    Horizontal,
+}
+
+/// The line endings to save files with, see `zed --line-endings`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+pub enum LineEndings {
+    /// `\n`, as used on Linux and macOS.
+    Lf,
+    /// `\r\n`, as used on Windows.
+    Crlf,
+}
+
+/// Text piped into the CLI, to be opened in an untitled buffer.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PipedBuffer {
+    pub text: String,
+    /// A language name or file extension, as accepted by `LanguageRegistry::language_for_name_or_extension`.
+    pub language: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum CliResponse {
     Ping,
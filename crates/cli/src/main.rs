@@ -2,7 +2,10 @@
 
 use anyhow::{Context, Result};
 use clap::Parser;
-use cli::{ipc::IpcOneShotServer, CliRequest, CliResponse, IpcHandshake};
+use cli::{
+    ipc::IpcOneShotServer, CliRequest, CliResponse, IpcHandshake, LineEndings, PipedBuffer,
+    SplitLayout,
+};
 use collections::HashMap;
 use parking_lot::Mutex;
 use std::{
@@ -13,7 +16,7 @@ use std::{
     thread::{self, JoinHandle},
 };
 use tempfile::NamedTempFile;
-use util::paths::PathWithPosition;
+use util::paths::{FileTarget, PathWithPosition};
 
 struct Detect;
 
@@ -21,6 +24,9 @@ trait InstalledApp {
     fn zed_version_string(&self) -> String;
     fn launch(&self, ipc_url: String) -> anyhow::Result<()>;
     fn run_foreground(&self, ipc_url: String) -> io::Result<ExitStatus>;
+    /// Runs the app binary with the given arguments and waits for it to exit, for the flags it
+    /// handles without opening any windows.
+    fn run_command(&self, args: &[&str]) -> io::Result<ExitStatus>;
 }
 
 #[derive(Parser, Debug)]
@@ -39,14 +45,36 @@ struct Args {
     /// Create a new workspace
     #[arg(short, long, overrides_with = "add")]
     new: bool,
+    /// Open each of the given paths in its own window.
+    ///
+    /// By default, all paths open in one workspace: directories become its project
+    /// folders and files are opened as tabs.
+    #[arg(long, conflicts_with = "add")]
+    window_per_path: bool,
+    /// Open the given paths without asking for confirmation, even if there are many of them.
+    #[arg(short, long)]
+    yes: bool,
     /// A sequence of space-separated paths that you want to open.
     ///
     /// Use `path:line:row` syntax to open a file at a specific location.
     /// Non-existing paths and directories will ignore `:line:row` suffix.
+    ///
+    /// Use `path:/pattern/` to open a file at the first match of a regular expression,
+    /// or `path@offset` to open it at a byte offset.
     paths_with_position: Vec<String>,
     /// Print Zed's version and the app path.
     #[arg(short, long)]
     version: bool,
+    /// Print the directories and files where Zed keeps its configuration and data.
+    #[arg(long)]
+    print_paths: bool,
+    /// Print the log to stdout and keep following it, like `tail -f`, until interrupted.
+    #[arg(long)]
+    tail_log: bool,
+    /// Quit the running instance, e.g. one that keeps running without windows.
+    /// Only supported on Linux.
+    #[arg(long)]
+    quit: bool,
     /// Run zed in the foreground (useful for debugging)
     #[arg(long)]
     foreground: bool,
@@ -56,17 +84,76 @@ struct Args {
     /// Run zed in dev-server mode
     #[arg(long)]
     dev_server_token: Option<String>,
+    /// Open text read from stdin in an untitled buffer with this language (e.g. 'rust' or 'rs'),
+    /// instead of a temporary file.
+    #[arg(long, value_name = "LANGUAGE", requires = "paths_with_position")]
+    stdin_language: Option<String>,
+    /// Diff the given files against their contents at a git ref, e.g. `HEAD~1`, with the
+    /// changes expanded inline.
+    #[arg(long, value_name = "REF", requires = "paths_with_position")]
+    diff_git: Option<String>,
+    /// Open the given files read-only. Trying to edit one offers to unlock it.
+    #[arg(long, requires = "paths_with_position")]
+    read_only: bool,
+    /// Open each of the given files in its own pane, split vertically (side by side) or
+    /// horizontally, rather than in tabs.
+    #[arg(
+        long,
+        value_name = "DIRECTION",
+        value_enum,
+        requires = "paths_with_position"
+    )]
+    split: Option<SplitLayout>,
+    /// Open the given files in this encoding (e.g. `shift_jis`, `windows-1252`) instead of
+    /// UTF-8. Files are saved back in the same encoding.
+    #[arg(
+        long,
+        value_name = "NAME",
+        value_parser = parse_encoding,
+        requires = "paths_with_position"
+    )]
+    encoding: Option<String>,
+    /// Save the given files with these line endings, instead of the ones they already use.
+    #[arg(
+        long,
+        value_name = "STYLE",
+        value_enum,
+        requires = "paths_with_position"
+    )]
+    line_endings: Option<LineEndings>,
+}
+
+/// Checks that `name` is a known encoding, returning its canonical name.
+fn parse_encoding(name: &str) -> Result<String> {
+    encoding_rs::Encoding::for_label(name.trim().as_bytes())
+        .map(|encoding| encoding.name().to_string())
+        .with_context(|| {
+            format!("unknown encoding {name:?}, expected a name like \"windows-1252\"")
+        })
 }
 
 fn parse_path_with_position(argument_str: &str) -> Result<String, std::io::Error> {
+    // A path that exists is opened as is, even if it ends in something like `@123`.
+    let target = FileTarget::parse_str(argument_str).filter(|_| !Path::new(argument_str).exists());
+    if let Some((path, target)) = target {
+        let canonicalized = canonicalize_path(path)?;
+        return Ok(target.to_string(&canonicalized.display().to_string()));
+    }
+
     let path = PathWithPosition::parse_str(argument_str);
-    let curdir = env::current_dir()?;
+    let canonicalized = path.map_path(canonicalize_path)?;
+    Ok(canonicalized.to_string(|path| path.display().to_string()))
+}
 
-    let canonicalized = path.map_path(|path| match fs::canonicalize(&path) {
+/// Canonicalizes a path, or only its parent directory if the path doesn't exist yet.
+fn canonicalize_path(path: PathBuf) -> Result<PathBuf, std::io::Error> {
+    match fs::canonicalize(&path) {
         Ok(path) => Ok(path),
         Err(e) => {
             if let Some(mut parent) = path.parent() {
+                let curdir;
                 if parent == Path::new("") {
+                    curdir = env::current_dir()?;
                     parent = &curdir
                 }
                 match fs::canonicalize(parent) {
@@ -77,8 +164,7 @@ fn parse_path_with_position(argument_str: &str) -> Result<String, std::io::Error
                 Err(e)
             }
         }
-    })?;
-    Ok(canonicalized.to_string(|path| path.display().to_string()))
+    }
 }
 
 fn main() -> Result<()> {
@@ -111,6 +197,28 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    if args.print_paths {
+        for (name, path) in paths::named_paths() {
+            println!("{name:<12}{}", path.display());
+        }
+        for (legacy_dir, dir) in paths::legacy_xdg_dirs() {
+            eprintln!("Using {legacy_dir:?}, since {dir:?} doesn't exist yet");
+        }
+        return Ok(());
+    }
+
+    let app_command = if args.tail_log {
+        Some("--tail-log")
+    } else if args.quit {
+        Some("--quit")
+    } else {
+        None
+    };
+    if let Some(app_command) = app_command {
+        let status = app.run_command(&[app_command])?;
+        std::process::exit(status.code().unwrap_or(1));
+    }
+
     let (server, server_name) =
         IpcOneShotServer::<IpcHandshake>::new().context("Handshake before Zed spawn")?;
     let url = format!("zed-cli://{server_name}");
@@ -128,6 +236,7 @@ fn main() -> Result<()> {
     let mut paths = vec![];
     let mut urls = vec![];
     let mut stdin_tmp_file: Option<fs::File> = None;
+    let mut piped_buffer = None;
     for path in args.paths_with_position.iter() {
         if path.starts_with("zed://")
             || path.starts_with("http://")
@@ -137,6 +246,16 @@ fn main() -> Result<()> {
         {
             urls.push(path.to_string());
         } else if path == "-" && args.paths_with_position.len() == 1 {
+            if let Some(language) = args.stdin_language.clone() {
+                let stdin = io::stdin().lock();
+                let text = if io::IsTerminal::is_terminal(&stdin) {
+                    String::new()
+                } else {
+                    io::read_to_string(stdin).context("Reading stdin")?
+                };
+                piped_buffer = Some(PipedBuffer { text, language });
+                continue;
+            }
             let file = NamedTempFile::new()?;
             paths.push(file.path().to_string_lossy().to_string());
             let (file, _) = file.keep()?;
@@ -157,8 +276,16 @@ fn main() -> Result<()> {
                 urls,
                 wait: args.wait,
                 open_new_workspace,
+                window_per_path: args.window_per_path,
                 dev_server_token: args.dev_server_token,
                 env,
+                piped_buffer,
+                assume_yes: args.yes,
+                diff_git_ref: args.diff_git,
+                read_only: args.read_only,
+                split: args.split,
+                encoding: args.encoding,
+                line_endings: args.line_endings,
             })?;
 
             while let Ok(response) = rx.recv() {
@@ -291,6 +418,12 @@ mod linux {
                 .arg(ipc_url)
                 .status()
         }
+
+        fn run_command(&self, args: &[&str]) -> io::Result<ExitStatus> {
+            std::process::Command::new(self.0.clone())
+                .args(args)
+                .status()
+        }
     }
 
     impl App {
@@ -461,6 +594,9 @@ mod windows {
         fn run_foreground(&self, _ipc_url: String) -> io::Result<ExitStatus> {
             unimplemented!()
         }
+        fn run_command(&self, _args: &[&str]) -> io::Result<ExitStatus> {
+            unimplemented!()
+        }
     }
 
     impl Detect {
@@ -638,16 +774,26 @@ mod mac_os {
         }
 
         fn run_foreground(&self, ipc_url: String) -> io::Result<ExitStatus> {
-            let path = match self {
-                Bundle::App { app_bundle, .. } => app_bundle.join("Contents/MacOS/zed"),
-                Bundle::LocalPath { executable, .. } => executable.clone(),
-            };
+            std::process::Command::new(self.executable())
+                .arg(ipc_url)
+                .status()
+        }
 
-            std::process::Command::new(path).arg(ipc_url).status()
+        fn run_command(&self, args: &[&str]) -> io::Result<ExitStatus> {
+            std::process::Command::new(self.executable())
+                .args(args)
+                .status()
         }
     }
 
     impl Bundle {
+        fn executable(&self) -> PathBuf {
+            match self {
+                Bundle::App { app_bundle, .. } => app_bundle.join("Contents/MacOS/zed"),
+                Bundle::LocalPath { executable, .. } => executable.clone(),
+            }
+        }
+
         fn plist(&self) -> &InfoPlist {
             match self {
                 Self::App { plist, .. } => plist,
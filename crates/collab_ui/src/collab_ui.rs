@@ -14,7 +14,8 @@ use gpui::{
 };
 use panel_settings::MessageEditorSettings;
 pub use panel_settings::{
-    ChatPanelSettings, CollaborationPanelSettings, NotificationPanelSettings,
+    ChatPanelSettings, CollaborationPanelSettings, ConnectionNotificationSettings,
+    NotificationPanelSettings,
 };
 use release_channel::ReleaseChannel;
 use settings::Settings;
@@ -26,6 +27,7 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
     ChatPanelSettings::register(cx);
     NotificationPanelSettings::register(cx);
     MessageEditorSettings::register(cx);
+    ConnectionNotificationSettings::register(cx);
 
     channel_view::init(cx);
     chat_panel::init(cx);
@@ -1,4 +1,5 @@
 mod collab_notification;
+mod connection_status_notification;
 pub mod incoming_call_notification;
 pub mod project_shared_notification;
 
@@ -15,4 +16,5 @@ pub use stories::*;
 pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
     incoming_call_notification::init(app_state, cx);
     project_shared_notification::init(app_state, cx);
+    connection_status_notification::init(app_state, cx);
 }
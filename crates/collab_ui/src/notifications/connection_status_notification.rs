@@ -0,0 +1,100 @@
+use crate::panel_settings::ConnectionNotificationSettings;
+use client::Status;
+use futures::StreamExt;
+use gpui::{AppContext, DismissEvent, VisualContext};
+use settings::Settings;
+use std::sync::Arc;
+use workspace::{
+    notifications::{simple_message_notification::MessageNotification, NotificationId},
+    AppState, Toast,
+};
+
+struct ConnectionLostNotification;
+struct ReconnectedNotification;
+
+/// Tells the user when the connection to the server is lost and when it's back.
+pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
+    let mut status = app_state.client.status();
+    cx.spawn(|mut cx| async move {
+        let mut disconnected = false;
+        while let Some(status) = status.next().await {
+            let result = cx.update(|cx| match status {
+                Status::ConnectionLost => {
+                    disconnected = true;
+                    if ConnectionNotificationSettings::get_global(cx).enabled {
+                        show_connection_lost(cx);
+                    }
+                }
+                Status::Connected { .. } if disconnected => {
+                    disconnected = false;
+                    dismiss_connection_lost(cx);
+                    if ConnectionNotificationSettings::get_global(cx).enabled {
+                        show_reconnected(cx);
+                    }
+                }
+                Status::SignedOut | Status::UpgradeRequired => {
+                    disconnected = false;
+                    dismiss_connection_lost(cx);
+                }
+                _ => {}
+            });
+            if result.is_err() {
+                break;
+            }
+        }
+    })
+    .detach();
+}
+
+fn show_connection_lost(cx: &mut AppContext) {
+    for workspace in workspace::local_workspace_windows(cx) {
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.show_notification(
+                    NotificationId::unique::<ConnectionLostNotification>(),
+                    cx,
+                    |cx| {
+                        cx.new_view(|_| {
+                            MessageNotification::new("Disconnected from the server, retrying…")
+                                .with_click_message("Reconnect now")
+                                .on_click(|cx| {
+                                    cx.dispatch_action(Box::new(client::Reconnect));
+                                    cx.emit(DismissEvent);
+                                })
+                        })
+                    },
+                );
+            })
+            .ok();
+    }
+}
+
+fn dismiss_connection_lost(cx: &mut AppContext) {
+    for workspace in workspace::local_workspace_windows(cx) {
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.dismiss_notification(
+                    &NotificationId::unique::<ConnectionLostNotification>(),
+                    cx,
+                );
+            })
+            .ok();
+    }
+}
+
+fn show_reconnected(cx: &mut AppContext) {
+    for workspace in workspace::local_workspace_windows(cx) {
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<ReconnectedNotification>(),
+                        "Reconnected to the server",
+                    )
+                    .autohide(),
+                    cx,
+                );
+            })
+            .ok();
+    }
+}
@@ -25,6 +25,11 @@ pub struct NotificationPanelSettings {
     pub default_width: Pixels,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct ConnectionNotificationSettings {
+    pub enabled: bool,
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
 pub struct PanelSettingsContent {
     /// Whether to show the panel button in the status bar.
@@ -41,6 +46,15 @@ pub struct PanelSettingsContent {
     pub default_width: Option<f32>,
 }
 
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct ConnectionNotificationSettingsContent {
+    /// Whether to show a notification when the connection to the server is lost,
+    /// and when it's restored.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
 pub struct MessageEditorSettings {
     /// Whether to automatically replace emoji shortcodes with emoji characters.
@@ -89,6 +103,19 @@ impl Settings for NotificationPanelSettings {
     }
 }
 
+impl Settings for ConnectionNotificationSettings {
+    const KEY: Option<&'static str> = Some("connection_notifications");
+
+    type FileContent = ConnectionNotificationSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut gpui::AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
+
 impl Settings for MessageEditorSettings {
     const KEY: Option<&'static str> = Some("message_editor");
 
@@ -1,7 +1,20 @@
+use std::{future::Future, pin::Pin};
+
+use anyhow::{Context as _, Result};
 use sqlez_macros::sql;
 
 use crate::{define_connection, query};
 
+/// The key under which [`KeyValueStore::run_data_migrations`] stores how many migrations were applied.
+const DATA_MIGRATION_VERSION_KEY: &str = "data_migration_version";
+
+/// A one-time migration of the data Zed stores on disk, see [`KeyValueStore::run_data_migrations`].
+#[derive(Clone, Copy)]
+pub struct DataMigration {
+    pub name: &'static str,
+    pub run: for<'a> fn(&'a KeyValueStore) -> Pin<Box<dyn 'a + Future<Output = Result<()>>>>,
+}
+
 define_connection!(pub static ref KEY_VALUE_STORE: KeyValueStore<()> =
     &[sql!(
         CREATE TABLE IF NOT EXISTS kv_store(
@@ -29,11 +42,35 @@ impl KeyValueStore {
             DELETE FROM kv_store WHERE key = (?)
         }
     }
+
+    /// Applies the migrations that haven't run yet, in order.
+    ///
+    /// The number of applied migrations is stored in the store itself, so
+    /// `migrations` must only ever be appended to. Stops at the first failing
+    /// migration, which will be retried on the next launch.
+    pub async fn run_data_migrations(&self, migrations: &[DataMigration]) -> Result<()> {
+        let applied = self
+            .read_kvp(DATA_MIGRATION_VERSION_KEY)?
+            .map(|version| version.parse::<usize>())
+            .transpose()
+            .context("invalid data migration version")?
+            .unwrap_or(0);
+
+        for (ix, migration) in migrations.iter().enumerate().skip(applied) {
+            (migration.run)(self)
+                .await
+                .with_context(|| format!("data migration {:?} failed", migration.name))?;
+            self.write_kvp(DATA_MIGRATION_VERSION_KEY.to_string(), (ix + 1).to_string())
+                .await?;
+            log::info!("applied data migration {:?}", migration.name);
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::kvp::KeyValueStore;
+    use crate::kvp::{DataMigration, KeyValueStore};
 
     #[gpui::test]
     async fn test_kvp() {
@@ -59,6 +96,44 @@ mod tests {
         db.delete_kvp("key-1".to_string()).await.unwrap();
         assert_eq!(db.read_kvp("key-1").unwrap(), None);
     }
+
+    #[gpui::test]
+    async fn test_data_migrations() {
+        let db = KeyValueStore(crate::open_test_db("test_data_migrations").await);
+
+        let first = DataMigration {
+            name: "first",
+            run: |db| {
+                Box::pin(async move {
+                    let count = db.read_kvp("count")?.unwrap_or_default();
+                    db.write_kvp("count".into(), format!("{count}1")).await
+                })
+            },
+        };
+        let second = DataMigration {
+            name: "second",
+            run: |db| {
+                Box::pin(async move {
+                    let count = db.read_kvp("count")?.unwrap_or_default();
+                    db.write_kvp("count".into(), format!("{count}2")).await
+                })
+            },
+        };
+        let failing = DataMigration {
+            name: "failing",
+            run: |_| Box::pin(async move { Err(anyhow::anyhow!("oops")) }),
+        };
+
+        db.run_data_migrations(&[first]).await.unwrap();
+        assert_eq!(db.read_kvp("count").unwrap().as_deref(), Some("1"));
+
+        // Migrations that already ran are skipped, and a failure stops the later ones.
+        let migrations = [first, second, failing, first];
+        db.run_data_migrations(&migrations).await.unwrap_err();
+        assert_eq!(db.read_kvp("count").unwrap().as_deref(), Some("12"));
+        db.run_data_migrations(&migrations[..2]).await.unwrap();
+        assert_eq!(db.read_kvp("count").unwrap().as_deref(), Some("12"));
+    }
 }
 
 define_connection!(pub static ref GLOBAL_KEY_VALUE_STORE: GlobalKeyValueStore<()> =
@@ -215,6 +215,7 @@ gpui::actions!(
         CopyPath,
         CopyPermalinkToLine,
         CopyRelativePath,
+        CopyZedLinkToLine,
         Cut,
         CutToEndOfLine,
         Delete,
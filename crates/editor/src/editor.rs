@@ -121,6 +121,7 @@ use multi_buffer::{
 };
 use ordered_float::OrderedFloat;
 use parking_lot::{Mutex, RwLock};
+use persistence::DB;
 use project::project_settings::{GitGutterSetting, ProjectSettings};
 use project::{
     lsp_store::FormatTrigger, CodeAction, Completion, CompletionIntent, Item, Location, Project,
@@ -162,7 +163,7 @@ use util::{defer, maybe, post_inc, RangeExt, ResultExt, TryFutureExt};
 use workspace::item::{ItemHandle, PreviewTabsSettings};
 use workspace::notifications::{DetachAndPromptErr, NotificationId};
 use workspace::{
-    searchable::SearchEvent, ItemNavHistory, SplitDirection, ViewId, Workspace, WorkspaceId,
+    searchable::SearchEvent, ItemId, ItemNavHistory, SplitDirection, ViewId, Workspace, WorkspaceId,
 };
 use workspace::{OpenInTerminal, OpenTerminal, TabBarSettings, Toast};
 
@@ -185,6 +186,7 @@ pub const DOCUMENT_HIGHLIGHTS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis
 
 pub(crate) const FORMAT_TIMEOUT: Duration = Duration::from_secs(2);
 pub(crate) const SCROLL_CENTER_TOP_BOTTOM_DEBOUNCE_TIMEOUT: Duration = Duration::from_secs(1);
+const SERIALIZE_SELECTIONS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(100);
 
 pub fn render_parsed_markdown(
     element_id: impl Into<ElementId>,
@@ -642,6 +644,7 @@ pub struct Editor {
     focused_block: Option<FocusedBlock>,
     next_scroll_position: NextScrollCursorCenterTopBottom,
     addons: HashMap<TypeId, Box<dyn Addon>>,
+    serialize_selections: Task<()>,
     _scroll_cursor_center_top_bottom_task: Task<()>,
 }
 
@@ -2035,6 +2038,7 @@ impl Editor {
             focused_block: None,
             next_scroll_position: NextScrollCursorCenterTopBottom::default(),
             addons: HashMap::default(),
+            serialize_selections: Task::ready(()),
             _scroll_cursor_center_top_bottom_task: Task::ready(()),
         };
         this.tasks_update_task = Some(this.refresh_runnables(cx));
@@ -2156,6 +2160,69 @@ impl Editor {
         })
     }
 
+    /// Opens a new untitled buffer containing `text` in the active pane.
+    ///
+    /// The buffer is unsaved until the user saves it somewhere. Until then its tab
+    /// shows `title`, if given.
+    pub fn new_scratch_buffer_in_workspace(
+        workspace: &mut Workspace,
+        title: Option<String>,
+        text: String,
+        language: Option<Arc<Language>>,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Task<Result<View<Editor>>> {
+        let project = workspace.project().clone();
+        let create = project.update(cx, |project, cx| project.create_buffer(cx));
+
+        cx.spawn(|workspace, mut cx| async move {
+            let buffer = create.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                if let Some(language) = language {
+                    project.update(cx, |project, cx| {
+                        project.set_language_for_buffer(&buffer, language, cx)
+                    });
+                }
+                // Insert the text as an edit, so that the buffer is dirty until it's saved.
+                buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+
+                let editor = match title {
+                    Some(title) => {
+                        let multibuffer = cx.new_model(|cx| {
+                            MultiBuffer::singleton(buffer.clone(), cx).with_title(title)
+                        });
+                        cx.new_view(|cx| {
+                            let mut editor = Editor::for_multibuffer(
+                                multibuffer,
+                                Some(project.clone()),
+                                true,
+                                cx,
+                            );
+                            // Once saved, show the file name like any other buffer.
+                            editor._subscriptions.push(cx.subscribe(
+                                &buffer,
+                                |editor, buffer, event, cx| {
+                                    if let language::BufferEvent::FileHandleChanged = event {
+                                        if let Some(file) = buffer.read(cx).file() {
+                                            let file_name =
+                                                file.file_name(cx).to_string_lossy().into_owned();
+                                            editor.buffer.update(cx, |multibuffer, cx| {
+                                                multibuffer.set_title(file_name, cx)
+                                            });
+                                        }
+                                    }
+                                },
+                            ));
+                            editor
+                        })
+                    }
+                    None => cx.new_view(|cx| Editor::for_buffer(buffer, Some(project.clone()), cx)),
+                };
+                workspace.add_item_to_active_pane(Box::new(editor.clone()), None, true, cx);
+                editor
+            })
+        })
+    }
+
     fn new_file_vertical(
         workspace: &mut Workspace,
         _: &workspace::NewFileSplitVertical,
@@ -2386,6 +2453,16 @@ impl Editor {
         self.read_only || self.buffer.read(cx).read_only()
     }
 
+    /// Returns whether the editor is read-only, emitting [`EditorEvent::EditRejected`] if it is,
+    /// for the actions that would otherwise edit it.
+    fn reject_edit_if_read_only(&self, cx: &mut ViewContext<Self>) -> bool {
+        let read_only = self.read_only(cx);
+        if read_only {
+            cx.emit(EditorEvent::EditRejected);
+        }
+        read_only
+    }
+
     pub fn set_read_only(&mut self, read_only: bool) {
         self.read_only = read_only;
     }
@@ -2589,6 +2666,7 @@ impl Editor {
             if self.git_blame_inline_enabled {
                 self.start_inline_blame_timer(cx);
             }
+            self.serialize_selections(cx);
         }
 
         self.blink_manager.update(cx, BlinkManager::pause_blinking);
@@ -2600,6 +2678,61 @@ impl Editor {
         cx.notify();
     }
 
+    fn serialize_selections(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(workspace_id) = self.workspace.as_ref().and_then(|workspace| workspace.1) else {
+            return;
+        };
+        if !self.buffer.read(cx).is_singleton() {
+            return;
+        }
+
+        let item_id = cx.view().entity_id().as_u64() as ItemId;
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let selections = self.selections.disjoint_anchors();
+        let background_executor = cx.background_executor().clone();
+        self.serialize_selections = cx.background_executor().spawn(async move {
+            background_executor
+                .timer(SERIALIZE_SELECTIONS_DEBOUNCE_TIMEOUT)
+                .await;
+            let selections = selections
+                .iter()
+                .map(|selection| {
+                    (
+                        selection.start.to_offset(&snapshot),
+                        selection.end.to_offset(&snapshot),
+                    )
+                })
+                .collect();
+            DB.save_editor_selections(item_id, workspace_id, selections)
+                .await
+                .context("failed to save editor selections")
+                .log_err();
+        });
+    }
+
+    pub fn read_selections_from_db(
+        &mut self,
+        item_id: ItemId,
+        workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(selections) = DB.get_editor_selections(item_id, workspace_id).log_err() else {
+            return;
+        };
+        if selections.is_empty() {
+            return;
+        }
+
+        // The file may have changed on disk since the selections were saved.
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let clip = |offset: usize| snapshot.clip_offset(offset.min(snapshot.len()), Bias::Left);
+        let ranges = selections
+            .into_iter()
+            .map(|(start, end)| clip(start)..clip(end))
+            .collect::<Vec<_>>();
+        self.change_selections(None, cx, |s| s.select_ranges(ranges));
+    }
+
     pub fn change_selections<R>(
         &mut self,
         autoscroll: Option<Autoscroll>,
@@ -2646,7 +2779,7 @@ impl Editor {
         S: ToOffset,
         T: Into<Arc<str>>,
     {
-        if self.read_only(cx) {
+        if self.reject_edit_if_read_only(cx) {
             return;
         }
 
@@ -2660,7 +2793,7 @@ impl Editor {
         S: ToOffset,
         T: Into<Arc<str>>,
     {
-        if self.read_only(cx) {
+        if self.reject_edit_if_read_only(cx) {
             return;
         }
 
@@ -2679,7 +2812,7 @@ impl Editor {
         S: ToOffset,
         T: Into<Arc<str>>,
     {
-        if self.read_only(cx) {
+        if self.reject_edit_if_read_only(cx) {
             return;
         }
 
@@ -3177,7 +3310,7 @@ impl Editor {
     pub fn handle_input(&mut self, text: &str, cx: &mut ViewContext<Self>) {
         let text: Arc<str> = text.into();
 
-        if self.read_only(cx) {
+        if self.reject_edit_if_read_only(cx) {
             return;
         }
 
@@ -3802,7 +3935,7 @@ impl Editor {
         autoindent_mode: Option<AutoindentMode>,
         cx: &mut ViewContext<Self>,
     ) {
-        if self.read_only(cx) {
+        if self.reject_edit_if_read_only(cx) {
             return;
         }
 
@@ -5817,7 +5950,7 @@ impl Editor {
     }
 
     pub fn indent(&mut self, _: &Indent, cx: &mut ViewContext<Self>) {
-        if self.read_only(cx) {
+        if self.reject_edit_if_read_only(cx) {
             return;
         }
         let mut selections = self.selections.all::<Point>(cx);
@@ -5919,7 +6052,7 @@ impl Editor {
     }
 
     pub fn outdent(&mut self, _: &Outdent, cx: &mut ViewContext<Self>) {
-        if self.read_only(cx) {
+        if self.reject_edit_if_read_only(cx) {
             return;
         }
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
@@ -6072,7 +6205,7 @@ impl Editor {
     }
 
     pub fn join_lines(&mut self, _: &JoinLines, cx: &mut ViewContext<Self>) {
-        if self.read_only(cx) {
+        if self.reject_edit_if_read_only(cx) {
             return;
         }
         let mut row_ranges = Vec::<Range<MultiBufferRow>>::new();
@@ -7071,7 +7204,7 @@ impl Editor {
         handle_entire_lines: bool,
         cx: &mut ViewContext<Self>,
     ) {
-        if self.read_only(cx) {
+        if self.reject_edit_if_read_only(cx) {
             return;
         }
 
@@ -7163,7 +7296,7 @@ impl Editor {
     }
 
     pub fn undo(&mut self, _: &Undo, cx: &mut ViewContext<Self>) {
-        if self.read_only(cx) {
+        if self.reject_edit_if_read_only(cx) {
             return;
         }
 
@@ -7184,7 +7317,7 @@ impl Editor {
     }
 
     pub fn redo(&mut self, _: &Redo, cx: &mut ViewContext<Self>) {
-        if self.read_only(cx) {
+        if self.reject_edit_if_read_only(cx) {
             return;
         }
 
@@ -11259,7 +11392,11 @@ impl Editor {
     }
 
     pub fn toggle_git_blame(&mut self, _: &ToggleGitBlame, cx: &mut ViewContext<Self>) {
-        self.show_git_blame_gutter = !self.show_git_blame_gutter;
+        self.set_show_git_blame(!self.show_git_blame_gutter, cx);
+    }
+
+    pub fn set_show_git_blame(&mut self, show: bool, cx: &mut ViewContext<Self>) {
+        self.show_git_blame_gutter = show;
 
         if self.show_git_blame_gutter && !self.has_blame_entries(cx) {
             self.start_git_blame(true, cx);
@@ -11474,6 +11611,27 @@ impl Editor {
         }
     }
 
+    /// Copies a `zed://file` link to the cursor's line and column, which opens the file at that
+    /// position when followed.
+    pub fn copy_zed_link_to_line(&mut self, _: &CopyZedLinkToLine, cx: &mut ViewContext<Self>) {
+        let Some(buffer) = self.buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let Some(file) = buffer.read(cx).file().and_then(|f| f.as_local()) else {
+            return;
+        };
+        let Ok(url) = url::Url::from_file_path(file.abs_path(cx)) else {
+            return;
+        };
+        let position = self.selections.newest::<Point>(cx).head();
+        cx.write_to_clipboard(ClipboardItem::new_string(format!(
+            "zed://file{}:{}:{}",
+            url.path(),
+            position.row + 1,
+            position.column + 1
+        )));
+    }
+
     pub fn open_permalink_to_line(&mut self, _: &OpenPermalinkToLine, cx: &mut ViewContext<Self>) {
         let permalink = self.get_permalink_to_line(cx);
 
@@ -12078,6 +12236,7 @@ impl Editor {
                 cx.notify();
             }
             multi_buffer::Event::Closed => cx.emit(EditorEvent::Closed),
+            multi_buffer::Event::EditRejected => cx.emit(EditorEvent::EditRejected),
             multi_buffer::Event::DiagnosticsUpdated => {
                 self.refresh_active_diagnostics(cx);
                 self.scrollbar_marker_state.dirty = true;
@@ -13323,6 +13482,8 @@ pub enum EditorEvent {
         transaction_id: clock::Lamport,
     },
     CursorShapeChanged,
+    /// The user tried to edit an editor that's read-only, e.g. by typing, pasting or undoing.
+    EditRejected,
 }
 
 impl EventEmitter<EditorEvent> for Editor {}
@@ -165,6 +165,48 @@ fn test_edit_events(cx: &mut TestAppContext) {
     assert_eq!(mem::take(&mut *events.borrow_mut()), []);
 }
 
+#[gpui::test]
+fn test_edits_rejected_in_read_only_editor(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let buffer = cx.new_model(|cx| {
+        let mut buffer = language::Buffer::local("one\ntwo", cx);
+        buffer.set_capability(language::Capability::ReadOnly, cx);
+        buffer
+    });
+    let rejected_edits = Rc::new(RefCell::new(0));
+    let editor = cx.add_window({
+        let rejected_edits = rejected_edits.clone();
+        |cx| {
+            cx.subscribe(&cx.view().clone(), move |_, _, event: &EditorEvent, _| {
+                if let EditorEvent::EditRejected = event {
+                    *rejected_edits.borrow_mut() += 1;
+                }
+            })
+            .detach();
+            Editor::for_buffer(buffer.clone(), None, cx)
+        }
+    });
+
+    // Every kind of edit is rejected once, whether it's checked by the editor or the multibuffer.
+    _ = editor.update(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| s.select_ranges([1..1]));
+        editor.handle_input("X", cx);
+        editor.backspace(&Backspace, cx);
+        editor.delete(&Delete, cx);
+        editor.newline(&Newline, cx);
+        editor.delete_line(&DeleteLine, cx);
+        editor.cut(&Cut, cx);
+        editor.undo(&Undo, cx);
+    });
+    assert_eq!(*rejected_edits.borrow(), 7);
+    assert_eq!(buffer.read_with(cx, |buffer, _| buffer.text()), "one\ntwo");
+
+    // Moving the cursor isn't an edit.
+    _ = editor.update(cx, |editor, cx| editor.move_down(&MoveDown, cx));
+    assert_eq!(*rejected_edits.borrow(), 7);
+}
+
 #[gpui::test]
 fn test_undo_redo_with_selection_restoration(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -13970,6 +14012,46 @@ pub(crate) fn update_test_project_settings(
     });
 }
 
+#[gpui::test]
+async fn test_new_scratch_buffer_in_workspace(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    let project = Project::test(fs, [], cx).await;
+    let workspace = cx.add_window(|cx| Workspace::test_new(project, cx));
+
+    let editor = workspace
+        .update(cx, |workspace, cx| {
+            Editor::new_scratch_buffer_in_workspace(
+                workspace,
+                Some("Report".to_string()),
+                "fn main() {}".to_string(),
+                Some(rust_lang()),
+                cx,
+            )
+        })
+        .unwrap()
+        .await
+        .unwrap();
+
+    _ = workspace.update(cx, |workspace, cx| {
+        assert_eq!(
+            workspace.active_item(cx).unwrap().item_id(),
+            editor.entity_id()
+        );
+        let buffer = editor.read(cx).buffer().read(cx);
+        assert_eq!(buffer.title(cx), "Report");
+        let buffer = buffer.as_singleton().unwrap().read(cx);
+        assert_eq!(buffer.text(), "fn main() {}");
+        assert_eq!(
+            buffer.language().map(|language| language.name()),
+            Some("Rust".into())
+        );
+        assert!(buffer.is_dirty());
+        assert!(buffer.file().is_none());
+    });
+}
+
 pub(crate) fn init_test(cx: &mut TestAppContext, f: fn(&mut AllLanguageSettingsContent)) {
     cx.update(|cx| {
         assets::Assets.load_test_fonts(cx);
@@ -1056,6 +1056,7 @@ impl SerializableItem for Editor {
                 cx.new_view(|cx| {
                     let mut editor = Editor::for_buffer(buffer, Some(project), cx);
 
+                    editor.read_selections_from_db(item_id, workspace_id, cx);
                     editor.read_scroll_position_from_db(item_id, workspace_id, cx);
                     editor
                 })
@@ -1063,6 +1064,27 @@ impl SerializableItem for Editor {
         })
     }
 
+    fn serialized_tab_title(
+        workspace_id: WorkspaceId,
+        item_id: ItemId,
+        _: &AppContext,
+    ) -> Option<SharedString> {
+        let serialized_editor = DB.get_serialized_editor(item_id, workspace_id).ok()??;
+        match serialized_editor.path {
+            Some(path) => Some(path.file_name()?.to_string_lossy().into_owned().into()),
+            None => Some("untitled".into()),
+        }
+    }
+
+    fn copy_serialized(
+        workspace_id: WorkspaceId,
+        from_item_id: ItemId,
+        to_item_id: ItemId,
+        cx: &mut WindowContext,
+    ) -> Task<Result<()>> {
+        cx.spawn(|_| DB.copy_serialized_editor(workspace_id, from_item_id, to_item_id))
+    }
+
     fn serialize(
         &mut self,
         workspace: &mut Workspace,
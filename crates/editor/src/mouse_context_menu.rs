@@ -1,10 +1,10 @@
 use std::ops::Range;
 
 use crate::{
-    actions::Format, selections_collection::SelectionsCollection, Copy, CopyPermalinkToLine, Cut,
-    DisplayPoint, DisplaySnapshot, Editor, EditorMode, FindAllReferences, GoToDeclaration,
-    GoToDefinition, GoToImplementation, GoToTypeDefinition, Paste, Rename, RevealInFileManager,
-    SelectMode, ToDisplayPoint, ToggleCodeActions,
+    actions::Format, selections_collection::SelectionsCollection, Copy, CopyPermalinkToLine,
+    CopyZedLinkToLine, Cut, DisplayPoint, DisplaySnapshot, Editor, EditorMode, FindAllReferences,
+    GoToDeclaration, GoToDefinition, GoToImplementation, GoToTypeDefinition, Paste, Rename,
+    RevealInFileManager, SelectMode, ToDisplayPoint, ToggleCodeActions,
 };
 use gpui::prelude::FluentBuilder;
 use gpui::{DismissEvent, Pixels, Point, Subscription, View, ViewContext};
@@ -187,7 +187,8 @@ pub fn deploy_context_menu(
                     builder.action("Reveal in File Manager", Box::new(RevealInFileManager))
                 })
                 .action("Open in Terminal", Box::new(OpenInTerminal))
-                .action("Copy Permalink", Box::new(CopyPermalinkToLine));
+                .action("Copy Permalink", Box::new(CopyPermalinkToLine))
+                .action("Copy Zed Link", Box::new(CopyZedLinkToLine));
             match focus {
                 Some(focus) => builder.context(focus),
                 None => builder,
@@ -89,6 +89,13 @@ define_connection!(
     //   mtime_seconds: Option<i64>,
     //   mtime_nanos: Option<i32>,
     // )
+    //
+    // editor_selections(
+    //   item_id: usize,
+    //   workspace_id: usize,
+    //   start_offset: usize,
+    //   end_offset: usize,
+    // )
     pub static ref DB: EditorDb<WorkspaceDb> =
         &[sql! (
             CREATE TABLE editors(
@@ -136,6 +143,16 @@ define_connection!(
             ALTER TABLE editors ADD COLUMN mtime_seconds INTEGER DEFAULT NULL;
             ALTER TABLE editors ADD COLUMN mtime_nanos INTEGER DEFAULT NULL;
         ),
+        sql! (
+            CREATE TABLE editor_selections (
+                item_id INTEGER NOT NULL,
+                workspace_id INTEGER NOT NULL,
+                start_offset INTEGER NOT NULL,
+                end_offset INTEGER NOT NULL,
+                FOREIGN KEY(item_id, workspace_id) REFERENCES editors(item_id, workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        ),
         ];
 );
 
@@ -190,6 +207,76 @@ impl EditorDb {
         }
     }
 
+    query! {
+        pub fn get_editor_selections(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Vec<(usize, usize)>> {
+            SELECT start_offset, end_offset
+            FROM editor_selections
+            WHERE item_id = ? AND workspace_id = ?
+            ORDER BY start_offset
+        }
+    }
+
+    /// Replaces the stored selections of an editor. Like the scroll position,
+    /// selections are only stored for editors that already have a row in `editors`.
+    pub async fn save_editor_selections(
+        &self,
+        item_id: ItemId,
+        workspace_id: WorkspaceId,
+        selections: Vec<(usize, usize)>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_editor_selections", || {
+                conn.exec_bound(sql!(
+                    DELETE FROM editor_selections WHERE item_id = ?1 AND workspace_id = ?2
+                ))?((item_id, workspace_id))?;
+
+                let mut insert = conn.exec_bound(sql!(
+                    INSERT INTO editor_selections (item_id, workspace_id, start_offset, end_offset)
+                    SELECT ?1, ?2, ?3, ?4
+                    WHERE EXISTS (SELECT 1 FROM editors WHERE item_id = ?1 AND workspace_id = ?2)
+                ))?;
+                for (start, end) in selections {
+                    insert((item_id, workspace_id, start, end))?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+
+    /// Copies an editor's stored state, including its scroll position and selections, to another
+    /// item id in the same workspace.
+    pub async fn copy_serialized_editor(
+        &self,
+        workspace_id: WorkspaceId,
+        from_item_id: ItemId,
+        to_item_id: ItemId,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("copy_serialized_editor", || {
+                conn.exec_bound(sql!(
+                    INSERT OR REPLACE INTO editors (
+                        item_id, workspace_id, path, scroll_top_row, scroll_horizontal_offset,
+                        scroll_vertical_offset, contents, language, mtime_seconds, mtime_nanos
+                    )
+                    SELECT
+                        ?3, workspace_id, path, scroll_top_row, scroll_horizontal_offset,
+                        scroll_vertical_offset, contents, language, mtime_seconds, mtime_nanos
+                    FROM editors
+                    WHERE item_id = ?2 AND workspace_id = ?1
+                ))?((workspace_id, from_item_id, to_item_id))?;
+                conn.exec_bound(sql!(
+                    INSERT INTO editor_selections (item_id, workspace_id, start_offset, end_offset)
+                    SELECT ?3, workspace_id, start_offset, end_offset
+                    FROM editor_selections
+                    WHERE item_id = ?2 AND workspace_id = ?1
+                ))?((workspace_id, from_item_id, to_item_id))?;
+                Ok(())
+            })
+        })
+        .await
+    }
+
     pub async fn delete_unloaded_items(
         &self,
         workspace: WorkspaceId,
@@ -297,4 +384,87 @@ mod tests {
             .unwrap();
         assert_eq!(have, serialized_editor);
     }
+
+    #[gpui::test]
+    async fn test_save_and_get_editor_selections() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+
+        // Selections of editors that were never serialized are not stored
+        DB.save_editor_selections(5678, workspace_id, vec![(1, 2)])
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_editor_selections(5678, workspace_id).unwrap(),
+            Vec::new()
+        );
+
+        DB.save_serialized_editor(5678, workspace_id, SerializedEditor::default())
+            .await
+            .unwrap();
+        DB.save_editor_selections(5678, workspace_id, vec![(10, 12), (0, 4)])
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_editor_selections(5678, workspace_id).unwrap(),
+            vec![(0, 4), (10, 12)]
+        );
+
+        // Saving again replaces the previous selections
+        DB.save_editor_selections(5678, workspace_id, vec![(3, 3)])
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_editor_selections(5678, workspace_id).unwrap(),
+            vec![(3, 3)]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_copy_serialized_editor() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+
+        let serialized_editor = SerializedEditor {
+            path: Some(PathBuf::from("copied.txt")),
+            contents: Some("Copied".to_owned()),
+            language: None,
+            mtime: None,
+        };
+        DB.save_serialized_editor(9012, workspace_id, serialized_editor.clone())
+            .await
+            .unwrap();
+        DB.save_scroll_position(9012, workspace_id, 7, 0.5, 0.)
+            .await
+            .unwrap();
+        DB.save_editor_selections(9012, workspace_id, vec![(1, 5)])
+            .await
+            .unwrap();
+
+        DB.copy_serialized_editor(workspace_id, 9012, 3456)
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_serialized_editor(3456, workspace_id).unwrap(),
+            Some(serialized_editor)
+        );
+        assert_eq!(
+            DB.get_scroll_position(3456, workspace_id).unwrap(),
+            DB.get_scroll_position(9012, workspace_id).unwrap()
+        );
+        assert_eq!(
+            DB.get_scroll_position(3456, workspace_id)
+                .unwrap()
+                .unwrap()
+                .0,
+            7
+        );
+        assert_eq!(
+            DB.get_editor_selections(3456, workspace_id).unwrap(),
+            vec![(1, 5)]
+        );
+        // The original is left alone until the workspace cleans up its unloaded items.
+        assert_eq!(
+            DB.get_editor_selections(9012, workspace_id).unwrap(),
+            vec![(1, 5)]
+        );
+    }
 }
@@ -521,11 +521,10 @@ impl Editor {
     ) {
         let scroll_position = DB.get_scroll_position(item_id, workspace_id);
         if let Ok(Some((top_row, x, y))) = scroll_position {
-            let top_anchor = self
-                .buffer()
-                .read(cx)
-                .snapshot(cx)
-                .anchor_at(Point::new(top_row, 0), Bias::Left);
+            // The file may have shrunk since the scroll position was saved.
+            let snapshot = self.buffer().read(cx).snapshot(cx);
+            let top_row = top_row.min(snapshot.max_point().row);
+            let top_anchor = snapshot.anchor_at(Point::new(top_row, 0), Bias::Left);
             let scroll_anchor = ScrollAnchor {
                 offset: gpui::Point::new(x, y),
                 anchor: top_anchor,
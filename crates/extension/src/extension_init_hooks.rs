@@ -0,0 +1,95 @@
+use anyhow::Result;
+use gpui::{AppContext, Global, SharedString};
+
+type InitHook = Box<dyn FnOnce(&mut AppContext) -> Result<()>>;
+
+/// Initialization hooks registered by extensions, which run once the built-in
+/// subsystems have been initialized.
+#[derive(Default)]
+struct ExtensionInitHooks {
+    pending: Vec<(SharedString, InitHook)>,
+    /// Whether the hooks have run, after which new hooks run as soon as they're registered.
+    ran: bool,
+}
+
+impl Global for ExtensionInitHooks {}
+
+/// Registers a hook that sets up the extension's globals, actions or observers.
+///
+/// Hooks run in the order they were registered, after the built-in subsystems have been
+/// initialized. A hook registered after that point runs immediately.
+pub fn register_extension_init_hook(
+    extension_name: impl Into<SharedString>,
+    hook: impl FnOnce(&mut AppContext) -> Result<()> + 'static,
+    cx: &mut AppContext,
+) {
+    let extension_name = extension_name.into();
+    if cx.default_global::<ExtensionInitHooks>().ran {
+        run_hook(&extension_name, Box::new(hook), cx);
+    } else {
+        cx.global_mut::<ExtensionInitHooks>()
+            .pending
+            .push((extension_name, Box::new(hook)));
+    }
+}
+
+/// Runs the registered hooks. A failing hook is logged and doesn't keep the others from running.
+pub fn run_extension_init_hooks(cx: &mut AppContext) {
+    let hooks = cx.default_global::<ExtensionInitHooks>();
+    hooks.ran = true;
+    let pending = std::mem::take(&mut hooks.pending);
+    for (extension_name, hook) in pending {
+        run_hook(&extension_name, hook, cx);
+    }
+}
+
+fn run_hook(extension_name: &SharedString, hook: InitHook, cx: &mut AppContext) {
+    if let Err(error) = hook(cx) {
+        log::error!("failed to initialize extension {extension_name}: {error:?}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use gpui::TestAppContext;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[gpui::test]
+    fn test_extension_init_hooks(cx: &mut TestAppContext) {
+        let ran = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            for name in ["a", "failing", "b"] {
+                let ran = ran.clone();
+                register_extension_init_hook(
+                    name,
+                    move |_| {
+                        ran.borrow_mut().push(name);
+                        if name == "failing" {
+                            Err(anyhow!("oops"))
+                        } else {
+                            Ok(())
+                        }
+                    },
+                    cx,
+                );
+            }
+            assert!(ran.borrow().is_empty());
+
+            run_extension_init_hooks(cx);
+            assert_eq!(*ran.borrow(), ["a", "failing", "b"]);
+
+            let late = ran.clone();
+            register_extension_init_hook(
+                "late",
+                move |_| {
+                    late.borrow_mut().push("late");
+                    Ok(())
+                },
+                cx,
+            );
+            assert_eq!(*ran.borrow(), ["a", "failing", "b", "late"]);
+        });
+    }
+}
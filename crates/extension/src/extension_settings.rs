@@ -1,5 +1,5 @@
 use anyhow::Result;
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use gpui::AppContext;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -16,6 +16,12 @@ pub struct ExtensionSettings {
     pub auto_install_extensions: HashMap<Arc<str>, bool>,
     #[serde(default)]
     pub auto_update_extensions: HashMap<Arc<str>, bool>,
+    /// The IDs of installed extensions that should not be loaded.
+    ///
+    /// Disabled extensions stay installed, but none of the languages, themes,
+    /// or language servers they provide are registered.
+    #[serde(default)]
+    pub disabled_extensions: HashSet<Arc<str>>,
 }
 
 impl ExtensionSettings {
@@ -33,6 +39,11 @@ impl ExtensionSettings {
             .copied()
             .unwrap_or(true)
     }
+
+    /// Returns whether the given extension has been disabled by the user.
+    pub fn is_disabled(&self, extension_id: &str) -> bool {
+        self.disabled_extensions.contains(extension_id)
+    }
 }
 
 impl Settings for ExtensionSettings {
@@ -1,5 +1,6 @@
 pub mod extension_builder;
 mod extension_indexed_docs_provider;
+mod extension_init_hooks;
 mod extension_lsp_adapter;
 mod extension_manifest;
 mod extension_settings;
@@ -10,6 +11,7 @@ mod wasm_host;
 mod extension_store_test;
 
 use crate::extension_indexed_docs_provider::ExtensionIndexedDocsProvider;
+pub use crate::extension_init_hooks::{register_extension_init_hook, run_extension_init_hooks};
 use crate::extension_manifest::SchemaVersion;
 use crate::extension_slash_command::ExtensionSlashCommand;
 use crate::{extension_lsp_adapter::ExtensionLspAdapter, wasm_host::wit};
@@ -44,7 +46,7 @@ use project::ContextProviderWithTasks;
 use release_channel::ReleaseChannel;
 use semantic_version::SemanticVersion;
 use serde::{Deserialize, Serialize};
-use settings::Settings;
+use settings::{Settings, SettingsStore};
 use snippet_provider::SnippetRegistry;
 use std::ops::RangeInclusive;
 use std::str::FromStr;
@@ -119,6 +121,8 @@ pub struct ExtensionStore {
     indexed_docs_registry: Arc<IndexedDocsRegistry>,
     snippet_registry: Arc<SnippetRegistry>,
     modified_extensions: HashSet<Arc<str>>,
+    disabled_extensions: HashSet<Arc<str>>,
+    disabled_extensions_reload: Option<Task<()>>,
     wasm_host: Arc<WasmHost>,
     wasm_extensions: Vec<(Arc<ExtensionManifest>, WasmExtension)>,
     tasks: Vec<Task<()>>,
@@ -249,6 +253,8 @@ impl ExtensionStore {
             builder: Arc::new(ExtensionBuilder::new(builder_client, build_dir)),
             outstanding_operations: Default::default(),
             modified_extensions: Default::default(),
+            disabled_extensions: Default::default(),
+            disabled_extensions_reload: None,
             reload_complete_senders: Vec::new(),
             wasm_host: WasmHost::new(
                 fs.clone(),
@@ -385,6 +391,25 @@ impl ExtensionStore {
             }
         }));
 
+        // Load or unload extensions as they are enabled or disabled in the settings. The settings
+        // change on every save of the settings file, so wait for them to settle before reloading.
+        cx.observe_global::<SettingsStore>(|this, cx| {
+            let disabled_extensions = &ExtensionSettings::get_global(cx).disabled_extensions;
+            if *disabled_extensions == this.disabled_extensions {
+                this.disabled_extensions_reload.take();
+                return;
+            }
+            this.disabled_extensions_reload = Some(cx.spawn(|this, mut cx| async move {
+                cx.background_executor()
+                    .timer(RELOAD_DEBOUNCE_DURATION)
+                    .await;
+                if let Ok(reload) = this.update(&mut cx, |this, cx| this.reload(None, cx)) {
+                    reload.await;
+                }
+            }));
+        })
+        .detach();
+
         this
     }
 
@@ -652,6 +677,12 @@ impl ExtensionStore {
                 .get(url.as_ref(), Default::default(), true)
                 .await
                 .map_err(|err| anyhow!("error downloading extension: {}", err))?;
+            if !response.status().is_success() {
+                bail!(
+                    "error downloading extension {extension_id}: status {}",
+                    response.status().as_u16()
+                );
+            }
 
             fs.remove_dir(
                 &extension_dir,
@@ -701,28 +732,36 @@ impl ExtensionStore {
         extension_id: Arc<str>,
         cx: &mut ModelContext<Self>,
     ) {
+        self.install_latest_extension_task(extension_id, cx)
+            .detach_and_log_err(cx);
+    }
+
+    /// Installs the latest compatible version of the extension, returning a task
+    /// that completes once it is installed and loaded.
+    pub fn install_latest_extension_task(
+        &mut self,
+        extension_id: Arc<str>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
         log::info!("installing extension {extension_id} latest version");
 
         let schema_versions = schema_version_range();
         let wasm_api_versions = wasm_api_version_range(ReleaseChannel::global(cx));
 
-        let Some(url) = self
-            .http_client
-            .build_zed_api_url(
-                &format!("/extensions/{extension_id}/download"),
-                &[
-                    ("min_schema_version", &schema_versions.start().to_string()),
-                    ("max_schema_version", &schema_versions.end().to_string()),
-                    (
-                        "min_wasm_api_version",
-                        &wasm_api_versions.start().to_string(),
-                    ),
-                    ("max_wasm_api_version", &wasm_api_versions.end().to_string()),
-                ],
-            )
-            .log_err()
-        else {
-            return;
+        let url = match self.http_client.build_zed_api_url(
+            &format!("/extensions/{extension_id}/download"),
+            &[
+                ("min_schema_version", &schema_versions.start().to_string()),
+                ("max_schema_version", &schema_versions.end().to_string()),
+                (
+                    "min_wasm_api_version",
+                    &wasm_api_versions.start().to_string(),
+                ),
+                ("max_wasm_api_version", &wasm_api_versions.end().to_string()),
+            ],
+        ) {
+            Ok(url) => url,
+            Err(error) => return Task::ready(Err(error)),
         };
 
         self.install_or_upgrade_extension_at_endpoint(
@@ -731,7 +770,6 @@ impl ExtensionStore {
             ExtensionOperation::Install,
             cx,
         )
-        .detach_and_log_err(cx);
     }
 
     pub fn upgrade_extension(
@@ -944,6 +982,9 @@ impl ExtensionStore {
         cx: &mut ModelContext<Self>,
     ) -> Task<()> {
         let old_index = &self.extension_index;
+        let disabled_extensions = ExtensionSettings::get_global(cx)
+            .disabled_extensions
+            .clone();
 
         // Determine which extensions need to be loaded and unloaded, based
         // on the changes to the manifest and the extensions that we know have been
@@ -966,7 +1007,10 @@ impl ExtensionStore {
                         Ordering::Equal => {
                             let (old_key, old_value) = old_keys.next().unwrap();
                             let (new_key, new_value) = new_keys.next().unwrap();
-                            if old_value != new_value || self.modified_extensions.contains(old_key)
+                            if old_value != new_value
+                                || self.modified_extensions.contains(old_key)
+                                || self.disabled_extensions.contains(old_key)
+                                    != disabled_extensions.contains(new_key)
                             {
                                 extensions_to_unload.push(old_key.clone());
                                 extensions_to_load.push(new_key.clone());
@@ -984,6 +1028,21 @@ impl ExtensionStore {
             self.modified_extensions.clear();
         }
 
+        let skipped_extensions = new_index
+            .extensions
+            .keys()
+            .filter(|extension_id| disabled_extensions.contains(*extension_id))
+            .map(|extension_id| extension_id.as_ref())
+            .collect::<Vec<_>>();
+        if !skipped_extensions.is_empty() {
+            log::info!(
+                "skipping disabled extensions: {}",
+                skipped_extensions.join(", ")
+            );
+        }
+        extensions_to_load.retain(|extension_id| !disabled_extensions.contains(extension_id));
+        self.disabled_extensions = disabled_extensions;
+
         if extensions_to_load.is_empty() && extensions_to_unload.is_empty() {
             return Task::ready(());
         }
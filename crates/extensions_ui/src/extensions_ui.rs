@@ -11,7 +11,7 @@ use client::telemetry::Telemetry;
 use client::ExtensionMetadata;
 use collections::{BTreeMap, BTreeSet};
 use editor::{Editor, EditorElement, EditorStyle};
-use extension::{ExtensionManifest, ExtensionOperation, ExtensionStore};
+use extension::{ExtensionManifest, ExtensionOperation, ExtensionSettings, ExtensionStore};
 use fuzzy::{match_strings, StringMatchCandidate};
 use gpui::{
     actions, uniform_list, AppContext, EventEmitter, Flatten, FocusableView, InteractiveElement,
@@ -518,6 +518,8 @@ impl ExtensionsPage {
             ExtensionStatus::Installed(installed_version) => Some(installed_version),
             _ => None,
         };
+        let is_installed = installed_version.is_some();
+        let is_disabled = ExtensionSettings::get_global(cx).is_disabled(&extension.id);
 
         ExtensionCard::new()
             .overridden_by_dev_extension(has_dev_extension)
@@ -540,7 +542,10 @@ impl ExtensionsPage {
                                         Headline::new(format!("(v{installed_version} installed)",))
                                             .size(HeadlineSize::XSmall)
                                     }),
-                            ),
+                            )
+                            .when(is_installed && is_disabled, |this| {
+                                this.child(Headline::new("(disabled)").size(HeadlineSize::XSmall))
+                            }),
                     )
                     .child(
                         h_flex()
@@ -624,6 +629,7 @@ impl ExtensionsPage {
                                     Some(Self::render_remote_extension_context_menu(
                                         &this,
                                         extension_id.clone(),
+                                        is_installed,
                                         cx,
                                     ))
                                 }),
@@ -635,21 +641,58 @@ impl ExtensionsPage {
     fn render_remote_extension_context_menu(
         this: &View<Self>,
         extension_id: Arc<str>,
+        is_installed: bool,
         cx: &mut WindowContext,
     ) -> View<ContextMenu> {
+        let is_disabled = ExtensionSettings::get_global(cx).is_disabled(&extension_id);
         let context_menu = ContextMenu::build(cx, |context_menu, cx| {
-            context_menu.entry(
-                "Install Another Version...",
-                None,
-                cx.handler_for(this, move |this, cx| {
-                    this.show_extension_version_list(extension_id.clone(), cx)
-                }),
-            )
+            context_menu
+                .entry(
+                    "Install Another Version...",
+                    None,
+                    cx.handler_for(this, {
+                        let extension_id = extension_id.clone();
+                        move |this, cx| this.show_extension_version_list(extension_id.clone(), cx)
+                    }),
+                )
+                .when(is_installed, |context_menu| {
+                    context_menu.entry(
+                        if is_disabled {
+                            "Enable Extension"
+                        } else {
+                            "Disable Extension"
+                        },
+                        None,
+                        cx.handler_for(this, move |this, cx| {
+                            this.set_extension_disabled(extension_id.clone(), !is_disabled, cx)
+                        }),
+                    )
+                })
         });
 
         context_menu
     }
 
+    fn set_extension_disabled(
+        &mut self,
+        extension_id: Arc<str>,
+        disabled: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        let fs = workspace.read(cx).app_state().fs.clone();
+        settings::update_settings_file::<ExtensionSettings>(fs, cx, move |settings, _| {
+            if disabled {
+                settings.disabled_extensions.insert(extension_id);
+            } else {
+                settings.disabled_extensions.remove(&extension_id);
+            }
+        });
+    }
+
     fn show_extension_version_list(&mut self, extension_id: Arc<str>, cx: &mut ViewContext<Self>) {
         let Some(workspace) = self.workspace.upgrade() else {
             return;
@@ -1,11 +1,11 @@
 use gpui::{actions, AppContext, ClipboardItem, PromptLevel};
-use system_specs::SystemSpecs;
+pub use system_specs::SystemSpecs;
 use util::ResultExt;
 use workspace::Workspace;
 
 pub mod feedback_modal;
 
-actions!(feedback, [GiveFeedback, SubmitFeedback]);
+actions!(feedback, [GiveFeedback, ReportCrash, SubmitFeedback]);
 
 mod system_specs;
 
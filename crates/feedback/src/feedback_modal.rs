@@ -2,7 +2,7 @@ use std::{ops::RangeInclusive, sync::Arc, time::Duration};
 
 use anyhow::{anyhow, bail};
 use bitflags::bitflags;
-use client::Client;
+use client::{Client, TelemetrySettings};
 use db::kvp::KEY_VALUE_STORE;
 use editor::{Editor, EditorEvent};
 use futures::AsyncReadExt;
@@ -15,11 +15,13 @@ use language::Buffer;
 use project::Project;
 use regex::Regex;
 use serde_derive::Serialize;
+use settings::Settings;
+use telemetry_events::Panic;
 use ui::{prelude::*, Button, ButtonStyle, IconPosition, Tooltip};
 use util::ResultExt;
 use workspace::{DismissDecision, ModalView, Workspace};
 
-use crate::{system_specs::SystemSpecs, GiveFeedback, OpenZedRepo};
+use crate::{system_specs::SystemSpecs, GiveFeedback, OpenZedRepo, ReportCrash};
 
 // For UI testing purposes
 const SEND_SUCCESS_IN_DEV_MODE: bool = true;
@@ -118,35 +120,83 @@ impl FeedbackModal {
     pub fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
         let _handle = cx.view().downgrade();
         workspace.register_action(move |workspace, _: &GiveFeedback, cx| {
-            workspace
-                .with_local_workspace(cx, |workspace, cx| {
-                    let markdown = workspace
-                        .app_state()
-                        .languages
-                        .language_for_name("Markdown");
-
-                    let project = workspace.project().clone();
-
-                    let system_specs = SystemSpecs::new(cx);
-                    cx.spawn(|workspace, mut cx| async move {
-                        let markdown = markdown.await.log_err();
-                        let buffer = project.update(&mut cx, |project, cx| {
-                            project.create_local_buffer("", markdown, cx)
-                        })?;
-                        let system_specs = system_specs.await;
-
-                        workspace.update(&mut cx, |workspace, cx| {
-                            workspace.toggle_modal(cx, move |cx| {
-                                FeedbackModal::new(system_specs, project, buffer, cx)
-                            });
-                        })?;
-
-                        anyhow::Ok(())
-                    })
-                    .detach_and_log_err(cx);
+            Self::deploy(workspace, String::new(), cx);
+        });
+        workspace.register_action(move |workspace, _: &ReportCrash, cx| {
+            Self::report_crash(workspace, cx);
+        });
+    }
+
+    /// Opens the feedback modal with details about the most recent panic, and reveals
+    /// the report in the file manager.
+    fn report_crash(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        if !TelemetrySettings::get_global(cx).diagnostics {
+            let prompt = cx.prompt(
+                PromptLevel::Info,
+                "Crash reports are turned off",
+                Some("Enable `telemetry.diagnostics` in your settings so that Zed keeps crash reports to attach to your feedback."),
+                &["OK"],
+            );
+            cx.background_executor()
+                .spawn(async move { prompt.await.ok() })
+                .detach();
+            return;
+        }
+
+        let last_panic = std::fs::read_to_string(paths::last_panic_file())
+            .ok()
+            .and_then(|json| serde_json::from_str::<Panic>(&json).log_err());
+        let Some(last_panic) = last_panic else {
+            let prompt = cx.prompt(PromptLevel::Info, "No crash reports found", None, &["OK"]);
+            cx.background_executor()
+                .spawn(async move { prompt.await.ok() })
+                .detach();
+            return;
+        };
+
+        let mut text = format!(
+            "\n\n---\nCrash: {}\nVersion: {} ({})\nSession: {}",
+            last_panic.payload,
+            last_panic.app_version,
+            last_panic.release_channel,
+            last_panic.session_id
+        );
+        if let Some(location) = last_panic.location_data {
+            text.push_str(&format!("\nLocation: {}:{}", location.file, location.line));
+        }
+        cx.reveal_path(paths::last_panic_file());
+        Self::deploy(workspace, text, cx);
+    }
+
+    fn deploy(workspace: &mut Workspace, initial_text: String, cx: &mut ViewContext<Workspace>) {
+        workspace
+            .with_local_workspace(cx, |workspace, cx| {
+                let markdown = workspace
+                    .app_state()
+                    .languages
+                    .language_for_name("Markdown");
+
+                let project = workspace.project().clone();
+
+                let system_specs = SystemSpecs::new(cx);
+                cx.spawn(|workspace, mut cx| async move {
+                    let markdown = markdown.await.log_err();
+                    let buffer = project.update(&mut cx, |project, cx| {
+                        project.create_local_buffer(&initial_text, markdown, cx)
+                    })?;
+                    let system_specs = system_specs.await;
+
+                    workspace.update(&mut cx, |workspace, cx| {
+                        workspace.toggle_modal(cx, move |cx| {
+                            FeedbackModal::new(system_specs, project, buffer, cx)
+                        });
+                    })?;
+
+                    anyhow::Ok(())
                 })
                 .detach_and_log_err(cx);
-        });
+            })
+            .detach_and_log_err(cx);
     }
 
     pub fn new(
@@ -166,6 +216,7 @@ impl FeedbackModal {
             editor
         });
 
+        let character_count = buffer.read(cx).len() as i32;
         let feedback_editor = cx.new_view(|cx| {
             let mut editor = Editor::for_buffer(buffer, Some(project.clone()), cx);
             editor.set_placeholder_text(
@@ -201,7 +252,7 @@ impl FeedbackModal {
             email_address_editor,
             submission_state: None,
             dismiss_modal: false,
-            character_count: 0,
+            character_count,
         }
     }
 
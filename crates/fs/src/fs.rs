@@ -13,6 +13,8 @@ use std::os::unix::fs::MetadataExt;
 use std::os::unix::fs::FileTypeExt;
 
 use async_tar::Archive;
+use collections::HashMap;
+pub use encoding_rs::{Encoding, UTF_8};
 use futures::{future::BoxFuture, AsyncRead, Stream, StreamExt};
 use git::repository::{GitRepository, RealGitRepository};
 use gpui::{AppContext, Global, ReadGlobal};
@@ -91,11 +93,36 @@ pub trait Fs: Send + Sync {
     }
     async fn open_sync(&self, path: &Path) -> Result<Box<dyn io::Read>>;
     async fn load(&self, path: &Path) -> Result<String> {
-        Ok(String::from_utf8(self.load_bytes(path).await?)?)
+        self.load_with_format(path, FileFormat::default()).await
     }
+    /// Like [`Fs::load`], but decodes the file from the given encoding.
+    async fn load_with_format(&self, path: &Path, file_format: FileFormat) -> Result<String>;
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
+    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+        self.save_with_format(path, text, line_ending, FileFormat::default())
+            .await
+    }
+    /// Like [`Fs::save`], but writes the file in the given encoding and line ending.
+    async fn save_with_format(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        file_format: FileFormat,
+    ) -> Result<()>;
+    /// Sets the encoding to open the file at the given path in next, e.g. for `zed --encoding`,
+    /// see [`Fs::take_file_format_override`].
+    fn set_encoding(&self, path: &Path, encoding: &'static Encoding);
+    /// Sets the line ending to save the file at the given path with once it's opened next, e.g.
+    /// for `zed --line-endings`, see [`Fs::take_file_format_override`].
+    fn set_line_ending(&self, path: &Path, line_ending: LineEnding);
+    /// Removes and returns the encoding and line ending set for the file at the given path, so
+    /// that they only apply to the buffer that's opened for it next.
+    ///
+    /// Paths are compared after resolving symlinks, so this also finds the file when it's
+    /// opened through another path to it.
+    async fn take_file_format_override(&self, path: &Path) -> FileFormat;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
     async fn is_file(&self, path: &Path) -> bool;
     async fn is_dir(&self, path: &Path) -> bool;
@@ -175,10 +202,30 @@ pub struct Metadata {
     pub is_fifo: bool,
 }
 
+/// The encoding and line ending to load and save a file with, where `None` means UTF-8 and the
+/// line ending detected when loading the file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FileFormat {
+    pub encoding: Option<&'static Encoding>,
+    pub line_ending: Option<LineEnding>,
+}
+
+impl FileFormat {
+    /// Takes the encoding and line ending that aren't set here from `other`.
+    pub fn or(self, other: FileFormat) -> FileFormat {
+        FileFormat {
+            encoding: self.encoding.or(other.encoding),
+            line_ending: self.line_ending.or(other.line_ending),
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct RealFs {
     git_hosting_provider_registry: Arc<GitHostingProviderRegistry>,
     git_binary_path: Option<PathBuf>,
+    encodings: parking_lot::Mutex<HashMap<PathBuf, &'static Encoding>>,
+    line_endings: parking_lot::Mutex<HashMap<PathBuf, LineEnding>>,
 }
 
 pub struct RealWatcher {}
@@ -191,8 +238,33 @@ impl RealFs {
         Self {
             git_hosting_provider_registry,
             git_binary_path,
+            encodings: Default::default(),
+            line_endings: Default::default(),
         }
     }
+
+}
+
+/// The path with symlinks resolved, or the path itself if it doesn't exist (yet).
+fn canonical_path_or_self(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn decode(bytes: &[u8], encoding: &'static Encoding) -> Result<String> {
+    encoding
+        .decode_without_bom_handling_and_without_replacement(bytes)
+        .map(|text| text.into_owned())
+        .ok_or_else(|| anyhow!("file is not valid {}", encoding.name()))
+}
+
+fn encode(text: &str, encoding: &'static Encoding) -> Result<Vec<u8>> {
+    let (bytes, _, had_unmappable_characters) = encoding.encode(text);
+    anyhow::ensure!(
+        !had_unmappable_characters,
+        "text contains characters that can't be encoded as {}",
+        encoding.name()
+    );
+    Ok(bytes.into_owned())
 }
 
 #[async_trait::async_trait]
@@ -394,7 +466,10 @@ impl Fs for RealFs {
         Ok(Box::new(std::fs::File::open(path)?))
     }
 
-    async fn load(&self, path: &Path) -> Result<String> {
+    async fn load_with_format(&self, path: &Path, file_format: FileFormat) -> Result<String> {
+        if let Some(encoding) = file_format.encoding {
+            return decode(&self.load_bytes(path).await?, encoding);
+        }
         let path = path.to_path_buf();
         let text = smol::unblock(|| std::fs::read_to_string(path)).await?;
         Ok(text)
@@ -434,11 +509,23 @@ impl Fs for RealFs {
         Ok(())
     }
 
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+    async fn save_with_format(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        file_format: FileFormat,
+    ) -> Result<()> {
         let buffer_size = text.summary().len.min(10 * 1024);
+        let line_ending = file_format.line_ending.unwrap_or(line_ending);
         if let Some(path) = path.parent() {
             self.create_dir(path).await?;
         }
+        if let Some(encoding) = file_format.encoding {
+            let bytes = encode(&chunks(text, line_ending).collect::<String>(), encoding)?;
+            smol::fs::write(path, bytes).await?;
+            return Ok(());
+        }
         let file = smol::fs::File::create(path).await?;
         let mut writer = smol::io::BufWriter::with_capacity(buffer_size, file);
         for chunk in chunks(text, line_ending) {
@@ -448,6 +535,32 @@ impl Fs for RealFs {
         Ok(())
     }
 
+    fn set_encoding(&self, path: &Path, encoding: &'static Encoding) {
+        self.encodings
+            .lock()
+            .insert(canonical_path_or_self(path), encoding);
+    }
+
+    fn set_line_ending(&self, path: &Path, line_ending: LineEnding) {
+        self.line_endings
+            .lock()
+            .insert(canonical_path_or_self(path), line_ending);
+    }
+
+    async fn take_file_format_override(&self, path: &Path) -> FileFormat {
+        if self.encodings.lock().is_empty() && self.line_endings.lock().is_empty() {
+            return FileFormat::default();
+        }
+        let path = self
+            .canonicalize(path)
+            .await
+            .unwrap_or_else(|_| path.to_path_buf());
+        FileFormat {
+            encoding: self.encodings.lock().remove(&path),
+            line_ending: self.line_endings.lock().remove(&path),
+        }
+    }
+
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
         Ok(smol::fs::canonicalize(path).await?)
     }
@@ -794,6 +907,8 @@ struct FakeFsState {
     buffered_events: Vec<PathEvent>,
     metadata_call_count: usize,
     read_dir_call_count: usize,
+    encodings: HashMap<PathBuf, &'static Encoding>,
+    line_endings: HashMap<PathBuf, LineEnding>,
 }
 
 #[cfg(any(test, feature = "test-support"))]
@@ -819,6 +934,14 @@ enum FakeFsEntry {
 
 #[cfg(any(test, feature = "test-support"))]
 impl FakeFsState {
+    fn canonical_path_or_self(&self, path: &Path) -> PathBuf {
+        let path = normalize_path(path);
+        match self.try_read_path(&path, true) {
+            Some((_, canonical_path)) => canonical_path,
+            None => path,
+        }
+    }
+
     fn read_path(&self, target: &Path) -> Result<Arc<Mutex<FakeFsEntry>>> {
         Ok(self
             .try_read_path(target, true)
@@ -949,6 +1072,8 @@ impl FakeFs {
                 buffered_events: Vec::new(),
                 events_paused: false,
                 read_dir_call_count: 0,
+                encodings: Default::default(),
+                line_endings: Default::default(),
                 metadata_call_count: 0,
             }),
         })
@@ -1175,6 +1300,21 @@ impl FakeFs {
         });
     }
 
+    pub fn set_ref_contents_for_repo(
+        &self,
+        dot_git: &Path,
+        git_ref: &str,
+        ref_state: &[(&Path, String)],
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state
+                .ref_contents
+                .extend(ref_state.iter().map(|(path, content)| {
+                    ((git_ref.to_string(), path.to_path_buf()), content.clone())
+                }));
+        });
+    }
+
     pub fn set_blame_for_repo(&self, dot_git: &Path, blames: Vec<(&Path, git::blame::Blame)>) {
         self.with_git_state(dot_git, true, |state| {
             state.blames.clear();
@@ -1632,8 +1772,11 @@ impl Fs for FakeFs {
         Ok(Box::new(io::Cursor::new(bytes)))
     }
 
-    async fn load(&self, path: &Path) -> Result<String> {
+    async fn load_with_format(&self, path: &Path, file_format: FileFormat) -> Result<String> {
         let content = self.load_internal(path).await?;
+        if let Some(encoding) = file_format.encoding {
+            return decode(&content, encoding);
+        }
         Ok(String::from_utf8(content.clone())?)
     }
 
@@ -1648,17 +1791,50 @@ impl Fs for FakeFs {
         Ok(())
     }
 
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+    async fn save_with_format(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        file_format: FileFormat,
+    ) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path);
+        let encoding = file_format.encoding;
+        let line_ending = file_format.line_ending.unwrap_or(line_ending);
         let content = chunks(text, line_ending).collect::<String>();
+        let content = match encoding {
+            Some(encoding) => encode(&content, encoding)?,
+            None => content.into_bytes(),
+        };
         if let Some(path) = path.parent() {
             self.create_dir(path).await?;
         }
-        self.write_file_internal(path, content.into_bytes())?;
+        self.write_file_internal(path, content)?;
         Ok(())
     }
 
+    fn set_encoding(&self, path: &Path, encoding: &'static Encoding) {
+        let mut state = self.state.lock();
+        let path = state.canonical_path_or_self(path);
+        state.encodings.insert(path, encoding);
+    }
+
+    fn set_line_ending(&self, path: &Path, line_ending: LineEnding) {
+        let mut state = self.state.lock();
+        let path = state.canonical_path_or_self(path);
+        state.line_endings.insert(path, line_ending);
+    }
+
+    async fn take_file_format_override(&self, path: &Path) -> FileFormat {
+        let mut state = self.state.lock();
+        let path = state.canonical_path_or_self(path);
+        FileFormat {
+            encoding: state.encodings.remove(&path),
+            line_ending: state.line_endings.remove(&path),
+        }
+    }
+
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
         let path = normalize_path(path);
         self.simulate_random_delay().await;
@@ -2002,6 +2178,125 @@ mod tests {
             "D",
         );
     }
+
+    #[gpui::test]
+    async fn test_fake_fs_encodings(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor.clone());
+        let shift_jis = Encoding::for_label(b"shift_jis").unwrap();
+        let (bytes, _, _) = shift_jis.encode("こんにちは");
+        fs.insert_file("/root/a.txt", bytes.to_vec()).await;
+        fs.set_encoding(Path::new("/root/a.txt"), shift_jis);
+
+        // The encoding only applies to the next time the file is opened.
+        let file_format = fs.take_file_format_override(Path::new("/root/a.txt")).await;
+        assert_eq!(file_format.encoding, Some(shift_jis));
+        assert_eq!(
+            fs.take_file_format_override(Path::new("/root/a.txt")).await,
+            FileFormat::default()
+        );
+
+        assert_eq!(
+            fs.load_with_format(Path::new("/root/a.txt"), file_format)
+                .await
+                .unwrap(),
+            "こんにちは"
+        );
+
+        fs.save_with_format(
+            Path::new("/root/a.txt"),
+            &"さようなら".into(),
+            LineEnding::Unix,
+            file_format,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            fs.load_bytes(Path::new("/root/a.txt")).await.unwrap(),
+            shift_jis.encode("さようなら").0.to_vec()
+        );
+
+        // Characters missing from the encoding are not silently replaced.
+        fs.save_with_format(
+            Path::new("/root/a.txt"),
+            &"🙂".into(),
+            LineEnding::Unix,
+            file_format,
+        )
+        .await
+        .unwrap_err();
+    }
+
+    #[gpui::test]
+    async fn test_fake_fs_line_endings(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor.clone());
+        fs.insert_file("/root/a.txt", b"one\r\ntwo\r\n".to_vec())
+            .await;
+        fs.set_line_ending(Path::new("/root/a.txt"), LineEnding::Unix);
+
+        let file_format = fs.take_file_format_override(Path::new("/root/a.txt")).await;
+        assert_eq!(file_format.line_ending, Some(LineEnding::Unix));
+        fs.save_with_format(
+            Path::new("/root/a.txt"),
+            &"one\ntwo\nthree\n".into(),
+            LineEnding::Windows,
+            file_format,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            fs.load(Path::new("/root/a.txt")).await.unwrap(),
+            "one\ntwo\nthree\n"
+        );
+
+        // Line endings set through a symlink apply to the file it points to, and vice versa.
+        fs.insert_file("/root/b.txt", b"one\r\n".to_vec()).await;
+        fs.insert_symlink("/root/link", "/root".into()).await;
+        fs.set_line_ending(Path::new("/root/link/b.txt"), LineEnding::Unix);
+        fs.set_line_ending(Path::new("/root/a.txt"), LineEnding::Windows);
+        assert_eq!(
+            fs.take_file_format_override(Path::new("/root/b.txt"))
+                .await
+                .line_ending,
+            Some(LineEnding::Unix)
+        );
+        assert_eq!(
+            fs.take_file_format_override(Path::new("/root/link/a.txt"))
+                .await
+                .line_ending,
+            Some(LineEnding::Windows)
+        );
+    }
+
+    #[gpui::test]
+    async fn test_fake_fs_file_format(executor: BackgroundExecutor) {
+        let fs = FakeFs::new(executor.clone());
+        let windows_1252 = Encoding::for_label(b"windows-1252").unwrap();
+        fs.insert_file("/root/a.bat", windows_1252.encode("echo café").0.to_vec())
+            .await;
+        let file_format = FileFormat {
+            encoding: Some(windows_1252),
+            line_ending: Some(LineEnding::Windows),
+        };
+
+        assert_eq!(
+            fs.load_with_format(Path::new("/root/a.bat"), file_format)
+                .await
+                .unwrap(),
+            "echo café"
+        );
+        fs.save_with_format(
+            Path::new("/root/a.bat"),
+            &"echo\ncafé\n".into(),
+            LineEnding::Unix,
+            file_format,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            fs.load_bytes(Path::new("/root/a.bat")).await.unwrap(),
+            windows_1252.encode("echo\r\ncafé\r\n").0.to_vec()
+        );
+    }
 }
 
 #[cfg(target_os = "linux")]
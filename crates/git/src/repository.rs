@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
     path::{Component, Path, PathBuf},
+    process::{Command, Output, Stdio},
     sync::Arc,
 };
 use sum_tree::MapSeekTarget;
@@ -29,6 +30,10 @@ pub trait GitRepository: Send + Sync {
     /// Note that for symlink entries, this will return the contents of the symlink, not the target.
     fn load_index_text(&self, relative_file_path: &Path) -> Option<String>;
 
+    /// Loads a file's contents at the given ref, e.g. `HEAD~1` or a branch name. Fails if the
+    /// ref doesn't name a commit, or if the file didn't exist in it.
+    fn load_text_at_ref(&self, git_ref: &str, relative_file_path: &Path) -> Result<String>;
+
     /// Returns the URL of the remote with the given name.
     fn remote_url(&self, name: &str) -> Option<String>;
     fn branch_name(&self) -> Option<String>;
@@ -105,6 +110,44 @@ impl GitRepository for RealGitRepository {
         None
     }
 
+    fn load_text_at_ref(&self, git_ref: &str, relative_file_path: &Path) -> Result<String> {
+        check_path_to_repo_path_errors(relative_file_path)?;
+        let working_directory = self
+            .repository
+            .lock()
+            .workdir()
+            .context("failed to read git work directory")?
+            .to_path_buf();
+
+        let commit = run_git(
+            &self.git_binary_path,
+            &working_directory,
+            &[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{git_ref}^{{commit}}"),
+            ],
+        )?;
+        if !commit.status.success() {
+            anyhow::bail!("{git_ref:?} doesn't name a commit in this repository");
+        }
+        let commit = String::from_utf8(commit.stdout)?;
+
+        // Git expects forward slashes in paths within a commit, even on Windows.
+        let path = relative_file_path.to_string_lossy().replace('\\', "/");
+        let output = run_git(
+            &self.git_binary_path,
+            &working_directory,
+            &["show", &format!("{}:{path}", commit.trim())],
+        )?;
+        if !output.status.success() {
+            anyhow::bail!("{path} doesn't exist at {git_ref}");
+        }
+        String::from_utf8(output.stdout)
+            .with_context(|| format!("{path} isn't valid UTF-8 at {git_ref}"))
+    }
+
     fn remote_url(&self, name: &str) -> Option<String> {
         let repo = self.repository.lock();
         let remote = repo.find_remote(name).ok()?;
@@ -209,6 +252,8 @@ pub struct FakeGitRepository {
 #[derive(Debug, Clone, Default)]
 pub struct FakeGitRepositoryState {
     pub index_contents: HashMap<PathBuf, String>,
+    /// File contents by ref, for [`GitRepository::load_text_at_ref`].
+    pub ref_contents: HashMap<(String, PathBuf), String>,
     pub blames: HashMap<PathBuf, Blame>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
     pub branch_name: Option<String>,
@@ -228,6 +273,15 @@ impl GitRepository for FakeGitRepository {
         state.index_contents.get(path).cloned()
     }
 
+    fn load_text_at_ref(&self, git_ref: &str, path: &Path) -> Result<String> {
+        let state = self.state.lock();
+        state
+            .ref_contents
+            .get(&(git_ref.to_string(), path.to_path_buf()))
+            .cloned()
+            .with_context(|| format!("{} doesn't exist at {git_ref}", path.display()))
+    }
+
     fn remote_url(&self, _name: &str) -> Option<String> {
         None
     }
@@ -289,6 +343,24 @@ impl GitRepository for FakeGitRepository {
     }
 }
 
+fn run_git(git_binary: &Path, working_directory: &Path, args: &[&str]) -> Result<Output> {
+    let mut command = Command::new(git_binary);
+    command
+        .current_dir(working_directory)
+        .args(args)
+        .stdin(Stdio::null());
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(windows::Win32::System::Threading::CREATE_NO_WINDOW.0);
+    }
+
+    command
+        .output()
+        .with_context(|| format!("failed to run git {}", args.join(" ")))
+}
+
 fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
     match relative_file_path.components().next() {
         None => anyhow::bail!("repo path should not be empty"),
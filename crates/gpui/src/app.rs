@@ -1,6 +1,6 @@
 use std::{
     any::{type_name, TypeId},
-    cell::{Ref, RefCell, RefMut},
+    cell::{Cell, Ref, RefCell, RefMut},
     marker::PhantomData,
     ops::{Deref, DerefMut},
     path::{Path, PathBuf},
@@ -256,6 +256,7 @@ pub struct AppContext {
     pub(crate) layout_id_buffer: Vec<LayoutId>, // We recycle this memory across layout requests.
     pub(crate) propagate_event: bool,
     pub(crate) prompt_builder: Option<PromptBuilder>,
+    pub(crate) window_activation_suppressed: Cell<bool>,
 }
 
 impl AppContext {
@@ -309,6 +310,7 @@ impl AppContext {
                 layout_id_buffer: Default::default(),
                 propagate_event: true,
                 prompt_builder: Some(PromptBuilder::Default),
+                window_activation_suppressed: Cell::new(false),
             }),
         });
 
@@ -523,9 +525,22 @@ impl AppContext {
 
     /// Instructs the platform to activate the application by bringing it to the foreground.
     pub fn activate(&self, ignoring_other_apps: bool) {
+        self.window_activation_suppressed.set(false);
         self.platform.activate(ignoring_other_apps);
     }
 
+    /// Keeps windows from taking focus when they ask to be activated, so the application can
+    /// start in the background. This lasts until [`AppContext::activate`] is called, or until
+    /// the user activates one of the windows.
+    pub fn suppress_window_activation(&self) {
+        self.window_activation_suppressed.set(true);
+    }
+
+    /// Whether windows are currently kept from taking focus, see [`AppContext::suppress_window_activation`].
+    pub fn window_activation_suppressed(&self) -> bool {
+        self.window_activation_suppressed.get()
+    }
+
     /// Hide the application at the platform level.
     pub fn hide(&self) {
         self.platform.hide();
@@ -681,6 +696,13 @@ impl AppContext {
         self.platform.restart(binary_path)
     }
 
+    /// Sets whether the application quits once its last window is closed, which it does by default
+    /// on Linux. Has no effect on other platforms: applications keep running without windows on
+    /// macOS, and always quit with their last window on Windows.
+    pub fn set_quit_when_last_window_closes(&self, quit: bool) {
+        self.platform.set_quit_when_last_window_closes(quit)
+    }
+
     /// Updates the http client assigned to GPUI
     pub fn set_http_client(&mut self, new_client: Arc<dyn HttpClient>) {
         self.http_client = new_client;
@@ -1085,6 +1107,11 @@ impl AppContext {
         self.pending_effects.push_back(Effect::Refresh);
     }
 
+    /// Get the keymap holding every key binding registered with the app.
+    pub fn key_bindings(&self) -> Rc<RefCell<Keymap>> {
+        self.keymap.clone()
+    }
+
     /// Clear all key bindings in the app.
     pub fn clear_key_bindings(&mut self) {
         self.keymap.borrow_mut().clear();
@@ -705,6 +705,20 @@ pub struct Bounds<T: Clone + Default + Debug> {
 }
 
 impl Bounds<Pixels> {
+    /// Returns true if these bounds have no area or aren't finite, as reported
+    /// for displays by some headless X servers and VNC/RDP sessions.
+    pub fn is_degenerate(&self) -> bool {
+        let values = [
+            self.origin.x.0,
+            self.origin.y.0,
+            self.size.width.0,
+            self.size.height.0,
+        ];
+        values.iter().any(|value| !value.is_finite())
+            || self.size.width.0 <= 0.
+            || self.size.height.0 <= 0.
+    }
+
     /// Generate a centered bounds for the given display or primary display if none is provided
     pub fn centered(
         display_id: Option<DisplayId>,
@@ -713,7 +727,8 @@ impl Bounds<Pixels> {
     ) -> Self {
         let display = display_id
             .and_then(|id| cx.find_display(id))
-            .or_else(|| cx.primary_display());
+            .or_else(|| cx.primary_display())
+            .filter(|display| !display.bounds().is_degenerate());
 
         display
             .map(|display| {
@@ -733,7 +748,8 @@ impl Bounds<Pixels> {
     pub fn maximized(display_id: Option<DisplayId>, cx: &mut AppContext) -> Self {
         let display = display_id
             .and_then(|id| cx.find_display(id))
-            .or_else(|| cx.primary_display());
+            .or_else(|| cx.primary_display())
+            .filter(|display| !display.bounds().is_degenerate());
 
         display
             .map(|display| display.bounds())
@@ -3115,6 +3131,17 @@ where
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_bounds_is_degenerate() {
+        assert!(!Bounds::new(point(px(0.), px(0.)), size(px(800.), px(600.))).is_degenerate());
+        assert!(Bounds::new(point(px(0.), px(0.)), size(px(0.), px(600.))).is_degenerate());
+        assert!(Bounds::new(point(px(0.), px(0.)), size(px(800.), px(-1.))).is_degenerate());
+        assert!(Bounds::new(point(px(f32::NAN), px(0.)), size(px(800.), px(600.))).is_degenerate());
+        assert!(
+            Bounds::new(point(px(0.), px(0.)), size(px(f32::INFINITY), px(600.))).is_degenerate()
+        );
+    }
+
     #[test]
     fn test_bounds_intersects() {
         let bounds1 = Bounds {
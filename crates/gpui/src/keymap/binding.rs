@@ -7,6 +7,7 @@ pub struct KeyBinding {
     pub(crate) action: Box<dyn Action>,
     pub(crate) keystrokes: SmallVec<[Keystroke; 2]>,
     pub(crate) context_predicate: Option<KeyBindingContextPredicate>,
+    pub(crate) action_input: Option<serde_json::Value>,
 }
 
 impl Clone for KeyBinding {
@@ -15,6 +16,7 @@ impl Clone for KeyBinding {
             action: self.action.boxed_clone(),
             keystrokes: self.keystrokes.clone(),
             context_predicate: self.context_predicate.clone(),
+            action_input: self.action_input.clone(),
         }
     }
 }
@@ -42,9 +44,17 @@ impl KeyBinding {
             keystrokes,
             action,
             context_predicate: context,
+            action_input: None,
         })
     }
 
+    /// Record the arguments the action was built from, so that the binding can be written back
+    /// to a keymap file.
+    pub fn with_action_input(mut self, action_input: serde_json::Value) -> Self {
+        self.action_input = Some(action_input);
+        self
+    }
+
     /// Check if the given keystrokes match this binding.
     pub fn match_keystrokes(&self, typed: &[Keystroke]) -> Option<bool> {
         if self.keystrokes.len() < typed.len() {
@@ -69,6 +79,16 @@ impl KeyBinding {
     pub fn action(&self) -> &dyn Action {
         self.action.as_ref()
     }
+
+    /// Get the arguments the action was built from, if it was loaded from a keymap file with any
+    pub fn action_input(&self) -> Option<&serde_json::Value> {
+        self.action_input.as_ref()
+    }
+
+    /// Get the context predicate that must match for this binding to apply, if any
+    pub fn predicate(&self) -> Option<&KeyBindingContextPredicate> {
+        self.context_predicate.as_ref()
+    }
 }
 
 impl std::fmt::Debug for KeyBinding {
@@ -178,6 +178,48 @@ pub enum KeyBindingContextPredicate {
     ),
 }
 
+impl fmt::Display for KeyBindingContextPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn operand(
+            predicate: &KeyBindingContextPredicate,
+            f: &mut fmt::Formatter<'_>,
+        ) -> fmt::Result {
+            match predicate {
+                KeyBindingContextPredicate::Identifier(_)
+                | KeyBindingContextPredicate::Equal(_, _)
+                | KeyBindingContextPredicate::NotEqual(_, _)
+                | KeyBindingContextPredicate::Not(_) => write!(f, "{predicate}"),
+                _ => write!(f, "({predicate})"),
+            }
+        }
+
+        match self {
+            Self::Identifier(name) => write!(f, "{name}"),
+            Self::Equal(left, right) => write!(f, "{left} == {right}"),
+            Self::NotEqual(left, right) => write!(f, "{left} != {right}"),
+            Self::Not(predicate) => match predicate.as_ref() {
+                Self::Identifier(_) | Self::Not(_) => write!(f, "!{predicate}"),
+                _ => write!(f, "!({predicate})"),
+            },
+            Self::Child(parent, child) => {
+                operand(parent, f)?;
+                write!(f, " > ")?;
+                operand(child, f)
+            }
+            Self::And(left, right) => {
+                operand(left, f)?;
+                write!(f, " && ")?;
+                operand(right, f)
+            }
+            Self::Or(left, right) => {
+                operand(left, f)?;
+                write!(f, " || ")?;
+                operand(right, f)
+            }
+        }
+    }
+}
+
 impl KeyBindingContextPredicate {
     /// Parse a string in the same format as the keymap's context field.
     ///
@@ -531,4 +573,24 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_display_round_trips() {
+        for source in [
+            "Editor",
+            "Editor && mode == full",
+            "!Terminal",
+            "Workspace > (Pane || Dock) && !(a != b)",
+            "!!Terminal || !(x == y)",
+            "vim_mode == normal && (vim_operator == none || vim_operator == n)",
+        ] {
+            let predicate = KeyBindingContextPredicate::parse(source).unwrap();
+            let displayed = predicate.to_string();
+            assert_eq!(
+                KeyBindingContextPredicate::parse(&displayed).unwrap(),
+                predicate,
+                "{source:?} displayed as {displayed:?}"
+            );
+        }
+    }
 }
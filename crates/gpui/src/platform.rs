@@ -117,6 +117,8 @@ pub(crate) trait Platform: 'static {
 
     fn run(&self, on_finish_launching: Box<dyn 'static + FnOnce()>);
     fn quit(&self);
+    /// Only honored on Linux. Applications keep running without windows on macOS.
+    fn set_quit_when_last_window_closes(&self, _quit: bool) {}
     fn restart(&self, binary_path: Option<PathBuf>);
     fn activate(&self, ignoring_other_apps: bool);
     fn hide(&self);
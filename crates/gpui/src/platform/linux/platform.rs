@@ -99,6 +99,7 @@ pub(crate) struct LinuxCommon {
     pub(crate) callbacks: PlatformHandlers,
     pub(crate) signal: LoopSignal,
     pub(crate) menus: Vec<OwnedMenu>,
+    pub(crate) quit_when_last_window_closes: bool,
 }
 
 impl LinuxCommon {
@@ -120,6 +121,7 @@ impl LinuxCommon {
             callbacks,
             signal,
             menus: Vec::new(),
+            quit_when_last_window_closes: true,
         };
 
         (common, main_receiver)
@@ -154,6 +156,10 @@ impl<P: LinuxClient + 'static> Platform for P {
         self.with_common(|common| common.signal.stop());
     }
 
+    fn set_quit_when_last_window_closes(&self, quit: bool) {
+        self.with_common(|common| common.quit_when_last_window_closes = quit);
+    }
+
     fn compositor_name(&self) -> &'static str {
         self.compositor_name()
     }
@@ -344,7 +344,7 @@ impl WaylandClientStatePtr {
                 state.keyboard_focused_window = Some(window);
             }
         }
-        if state.windows.is_empty() {
+        if state.windows.is_empty() && state.common.quit_when_last_window_closes {
             state.common.signal.stop();
         }
     }
@@ -183,7 +183,7 @@ impl X11ClientStatePtr {
         }
         state.cursor_styles.remove(&x_window);
 
-        if state.windows.is_empty() {
+        if state.windows.is_empty() && state.common.quit_when_last_window_closes {
             state.common.signal.stop();
         }
     }
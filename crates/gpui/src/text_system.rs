@@ -51,6 +51,9 @@ pub struct TextSystem {
     raster_bounds: RwLock<FxHashMap<RenderGlyphParams, Bounds<DevicePixels>>>,
     wrapper_pool: Mutex<FxHashMap<FontIdWithSize, Vec<LineWrapper>>>,
     font_runs_pool: Mutex<Vec<Vec<FontRun>>>,
+    /// Fonts registered with [`TextSystem::add_preferred_fallback_font`], tried in order
+    /// before the `fallback_font_stack`.
+    preferred_fallback_fonts: RwLock<SmallVec<[Font; 2]>>,
     fallback_font_stack: SmallVec<[Font; 2]>,
 }
 
@@ -63,6 +66,7 @@ impl TextSystem {
             font_ids_by_font: RwLock::default(),
             wrapper_pool: Mutex::default(),
             font_runs_pool: Mutex::default(),
+            preferred_fallback_fonts: RwLock::default(),
             fallback_font_stack: smallvec![
                 // TODO: Remove this when Linux have implemented setting fallbacks.
                 font("Zed Plex Mono"),
@@ -80,8 +84,10 @@ impl TextSystem {
     pub fn all_font_names(&self) -> Vec<String> {
         let mut names = self.platform_text_system.all_font_names();
         names.extend(
-            self.fallback_font_stack
+            self.preferred_fallback_fonts
+                .read()
                 .iter()
+                .chain(&self.fallback_font_stack)
                 .map(|font| font.family.to_string()),
         );
         names.push(".SystemUIFont".to_string());
@@ -95,6 +101,17 @@ impl TextSystem {
         self.platform_text_system.add_fonts(fonts)
     }
 
+    /// Registers a font, usually one added with [`TextSystem::add_fonts`], to be used when
+    /// a requested font can't be loaded. Fonts registered this way are tried in the order they
+    /// were added, before the platform's default fallbacks, so that missing fonts resolve to
+    /// the same font regardless of which fonts are installed on the system.
+    pub fn add_preferred_fallback_font(&self, font: Font) {
+        let mut preferred_fallback_fonts = self.preferred_fallback_fonts.write();
+        if !preferred_fallback_fonts.contains(&font) {
+            preferred_fallback_fonts.push(font);
+        }
+    }
+
     /// Get the FontId for the configure font family and style.
     pub fn font_id(&self, font: &Font) -> Result<FontId> {
         fn clone_font_id_result(font_id: &Result<FontId>) -> Result<FontId> {
@@ -141,7 +158,11 @@ impl TextSystem {
         if let Ok(font_id) = self.font_id(font) {
             return font_id;
         }
-        for fallback in &self.fallback_font_stack {
+        let preferred_fallback_fonts = self.preferred_fallback_fonts.read();
+        for fallback in preferred_fallback_fonts
+            .iter()
+            .chain(&self.fallback_font_stack)
+        {
             if let Ok(font_id) = self.font_id(fallback) {
                 return font_id;
             }
@@ -150,8 +171,9 @@ impl TextSystem {
         panic!(
             "failed to resolve font '{}' or any of the fallbacks: {}",
             font.family,
-            self.fallback_font_stack
+            preferred_fallback_fonts
                 .iter()
+                .chain(&self.fallback_font_stack)
                 .map(|fallback| &fallback.family)
                 .join(", ")
         );
@@ -600,6 +600,7 @@ fn default_bounds(display_id: Option<DisplayId>, cx: &mut AppContext) -> Bounds<
                 .unwrap_or_else(|| cx.primary_display());
 
             display
+                .filter(|display| !display.bounds().is_degenerate())
                 .map(|display| display.default_bounds())
                 .unwrap_or_else(|| Bounds::new(point(px(0.), px(0.)), DEFAULT_WINDOW_SIZE))
         })
@@ -625,9 +626,13 @@ impl Window {
             window_decorations,
         } = options;
 
-        let bounds = window_bounds
+        let mut bounds = window_bounds
             .map(|bounds| bounds.get_bounds())
             .unwrap_or_else(|| default_bounds(display_id, cx));
+        if bounds.is_degenerate() {
+            log::warn!("invalid window bounds {bounds:?}, falling back to the default size");
+            bounds = Bounds::new(point(px(0.), px(0.)), DEFAULT_WINDOW_SIZE);
+        }
         let mut platform_window = cx.platform.open_window(
             handle,
             WindowParams {
@@ -748,6 +753,9 @@ impl Window {
             move |active| {
                 handle
                     .update(&mut cx, |_, cx| {
+                        if active {
+                            cx.app.window_activation_suppressed.set(false);
+                        }
                         cx.window.active.set(active);
                         cx.window
                             .activation_observers
@@ -3592,6 +3600,9 @@ impl<'a> WindowContext<'a> {
 
     /// Focus the current window and bring it to the foreground at the platform level.
     pub fn activate_window(&self) {
+        if self.app.window_activation_suppressed() {
+            return;
+        }
         self.window.platform_window.activate();
     }
 
@@ -4,7 +4,11 @@ use futures::future::BoxFuture;
 use util::maybe;
 
 pub use isahc::config::Configurable;
-pub struct IsahcHttpClient(isahc::HttpClient);
+pub struct IsahcHttpClient {
+    client: isahc::HttpClient,
+    /// A host whose TLS certificate isn't verified, see [`IsahcHttpClient::new_insecure_for_host`].
+    insecure_host: Option<String>,
+}
 
 pub use http_client::*;
 
@@ -17,8 +21,29 @@ impl IsahcHttpClient {
         if let Some(agent) = user_agent {
             builder = builder.default_header("User-Agent", agent);
         }
-        Arc::new(IsahcHttpClient(builder.build().unwrap()))
+        Arc::new(IsahcHttpClient {
+            client: builder.build().unwrap(),
+            insecure_host: None,
+        })
+    }
+
+    /// Like [`IsahcHttpClient::new`], except that invalid TLS certificates, e.g. self-signed
+    /// ones, are accepted for requests to `insecure_host`. This must only be used for a host
+    /// that the user explicitly chose to trust, as it allows anyone on the network path to
+    /// impersonate it.
+    pub fn new_insecure_for_host(
+        proxy: Option<Uri>,
+        user_agent: Option<String>,
+        insecure_host: String,
+    ) -> Arc<IsahcHttpClient> {
+        let client = Self::new(proxy, user_agent);
+        let client = Arc::into_inner(client).unwrap();
+        Arc::new(IsahcHttpClient {
+            insecure_host: Some(insecure_host),
+            ..client
+        })
     }
+
     pub fn builder() -> isahc::HttpClientBuilder {
         isahc::HttpClientBuilder::new()
     }
@@ -26,7 +51,10 @@ impl IsahcHttpClient {
 
 impl From<isahc::HttpClient> for IsahcHttpClient {
     fn from(client: isahc::HttpClient) -> Self {
-        Self(client)
+        Self {
+            client,
+            insecure_host: None,
+        }
     }
 }
 
@@ -51,6 +79,10 @@ impl HttpClient for IsahcHttpClient {
             .map(|t| t.0);
         let req = maybe!({
             let (mut parts, body) = req.into_parts();
+            let is_insecure_host = self
+                .insecure_host
+                .as_deref()
+                .map_or(false, |host| parts.uri.host() == Some(host));
             let mut builder = isahc::Request::builder()
                 .method(parts.method)
                 .uri(parts.uri)
@@ -58,6 +90,10 @@ impl HttpClient for IsahcHttpClient {
             if let Some(read_timeout) = read_timeout {
                 builder = builder.low_speed_timeout(100, read_timeout);
             }
+            if is_insecure_host {
+                builder =
+                    builder.ssl_options(isahc::config::SslOption::DANGER_ACCEPT_INVALID_CERTS);
+            }
 
             let headers = builder.headers_mut()?;
             mem::swap(headers, &mut parts.headers);
@@ -85,7 +121,7 @@ impl HttpClient for IsahcHttpClient {
                 .ok()
         });
 
-        let client = self.0.clone();
+        let client = self.client.clone();
 
         Box::pin(async move {
             match req {
@@ -260,6 +260,12 @@ impl CachedLspAdapter {
             .await
     }
 
+    /// Forgets the binary found when the server was last started, so that it's looked up or
+    /// downloaded again on the next start.
+    pub async fn forget_cached_binary(&self) {
+        self.cached_binary.lock().await.take();
+    }
+
     pub fn code_action_kinds(&self) -> Option<Vec<CodeActionKind>> {
         self.adapter.code_action_kinds()
     }
@@ -629,7 +629,7 @@ impl LanguageRegistry {
         }
     }
 
-    fn language_for_file_internal(
+    pub(crate) fn language_for_file_internal(
         self: &Arc<Self>,
         path: &Path,
         content: Option<&Rope>,
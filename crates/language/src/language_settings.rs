@@ -1,6 +1,6 @@
 //! Provides `language`-related settings.
 
-use crate::{File, Language, LanguageName, LanguageServerName};
+use crate::{File, Language, LanguageName, LanguageRegistry, LanguageServerName, LineEnding};
 use anyhow::Result;
 use collections::{HashMap, HashSet};
 use core::slice;
@@ -87,6 +87,10 @@ pub struct LanguageSettings {
     /// Whether or not to ensure there's a single newline at the end of a buffer
     /// when saving it.
     pub ensure_final_newline_on_save: bool,
+    /// The encoding to open and save files in, e.g. `utf-8` or `windows-1252`.
+    pub encoding: String,
+    /// The line endings to save files with, instead of the ones they already use.
+    pub line_endings: LineEndingSetting,
     /// How to perform a buffer format.
     pub formatter: SelectedFormatter,
     /// Zed's Prettier integration settings.
@@ -274,6 +278,17 @@ pub struct LanguageSettingsContent {
     /// Default: true
     #[serde(default)]
     pub ensure_final_newline_on_save: Option<bool>,
+    /// The encoding to open and save files in, e.g. `utf-8` or `windows-1252`.
+    /// Overridden by `zed --encoding`.
+    ///
+    /// Default: utf-8
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// The line endings to save files with. Overridden by `zed --line-endings`.
+    ///
+    /// Default: keep
+    #[serde(default)]
+    pub line_endings: Option<LineEndingSetting>,
     /// How to perform a buffer format.
     ///
     /// Default: auto
@@ -375,6 +390,29 @@ pub struct FeaturesContent {
     pub inline_completion_provider: Option<InlineCompletionProvider>,
 }
 
+/// The line endings to save files with.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LineEndingSetting {
+    /// The line endings the file already uses.
+    Keep,
+    /// `\n`, as used on Linux and macOS.
+    Lf,
+    /// `\r\n`, as used on Windows.
+    Crlf,
+}
+
+impl LineEndingSetting {
+    /// Returns the line ending to save files with, if it isn't the one they already use.
+    pub fn line_ending(self) -> Option<LineEnding> {
+        match self {
+            LineEndingSetting::Keep => None,
+            LineEndingSetting::Lf => Some(LineEnding::Unix),
+            LineEndingSetting::Crlf => Some(LineEnding::Windows),
+        }
+    }
+}
+
 /// Controls the soft-wrapping behavior in the editor.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -819,6 +857,17 @@ impl AllLanguageSettings {
         &self.defaults
     }
 
+    /// Returns the settings for the language of the file at `path`, detected from the path alone
+    /// (including the `file_types` setting), as the file's contents may not have been loaded yet.
+    pub fn language_for_path(
+        &self,
+        path: &Path,
+        languages: &Arc<LanguageRegistry>,
+    ) -> &LanguageSettings {
+        let language = languages.language_for_file_internal(path, None, Some(&self.file_types));
+        self.language(language.map(|language| language.name()).as_ref())
+    }
+
     /// Returns whether inline completions are enabled for the given path.
     pub fn inline_completions_enabled_for_path(&self, path: &Path) -> bool {
         !self
@@ -1084,6 +1133,8 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         &mut settings.ensure_final_newline_on_save,
         src.ensure_final_newline_on_save,
     );
+    merge(&mut settings.encoding, src.encoding.clone());
+    merge(&mut settings.line_endings, src.line_endings);
     merge(
         &mut settings.enable_language_server,
         src.enable_language_server,
@@ -1,3 +1,4 @@
+mod lsp_binary_check;
 mod lsp_log;
 mod syntax_tree_view;
 
@@ -6,10 +7,12 @@ mod lsp_log_tests;
 
 use gpui::AppContext;
 
+pub use lsp_binary_check::CheckLanguageServerBinaries;
 pub use lsp_log::{LogStore, LspLogToolbarItemView, LspLogView};
 pub use syntax_tree_view::{SyntaxTreeToolbarItemView, SyntaxTreeView};
 
 pub fn init(cx: &mut AppContext) {
+    lsp_binary_check::init(cx);
     lsp_log::init(cx);
     syntax_tree_view::init(cx);
 }
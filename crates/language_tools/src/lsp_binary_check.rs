@@ -0,0 +1,134 @@
+use gpui::{actions, AppContext, ViewContext, VisualContext as _};
+use project::{LanguageServerBinaryCheck, LanguageServerBinaryCheckStatus};
+use workspace::{
+    notifications::{simple_message_notification::MessageNotification, NotificationId},
+    Workspace,
+};
+
+actions!(debug, [CheckLanguageServerBinaries]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(check_language_server_binaries);
+    })
+    .detach();
+}
+
+/// Checks the binaries of the language servers used by the open buffers, and reports the ones
+/// that are missing or broken, offering to download them again.
+fn check_language_server_binaries(
+    workspace: &mut Workspace,
+    _: &CheckLanguageServerBinaries,
+    cx: &mut ViewContext<Workspace>,
+) {
+    struct LanguageServerBinariesReport;
+    struct LanguageServerBinaryProblem;
+
+    let project = workspace.project().clone();
+    if !project.read(cx).is_local() {
+        workspace.show_notification(
+            NotificationId::unique::<LanguageServerBinariesReport>(),
+            cx,
+            |cx| {
+                cx.new_view(|_| {
+                    MessageNotification::new(
+                        "Language server binaries can only be checked in local projects.",
+                    )
+                })
+            },
+        );
+        return;
+    }
+
+    let checks = project.update(cx, |project, cx| project.check_language_server_binaries(cx));
+    cx.spawn(|workspace, mut cx| async move {
+        let checks = checks.await;
+        workspace.update(&mut cx, |workspace, cx| {
+            workspace.show_notification(
+                NotificationId::unique::<LanguageServerBinariesReport>(),
+                cx,
+                |cx| cx.new_view(|_| MessageNotification::new(report(&checks))),
+            );
+
+            for check in checks {
+                let (message, downloadable) = match &check.status {
+                    LanguageServerBinaryCheckStatus::Installed { .. } => continue,
+                    LanguageServerBinaryCheckStatus::NotDownloaded => (
+                        format!(
+                            "{} for {} hasn't been downloaded.",
+                            check.name.0, check.language.0
+                        ),
+                        true,
+                    ),
+                    LanguageServerBinaryCheckStatus::Broken {
+                        path,
+                        downloaded,
+                        error,
+                    } => (
+                        format!(
+                            "{} for {} is broken: {path:?}: {error}",
+                            check.name.0, check.language.0
+                        ),
+                        *downloaded,
+                    ),
+                };
+                let id = NotificationId::identified::<LanguageServerBinaryProblem>(
+                    check.name.0.to_string(),
+                );
+                let project = project.clone();
+                workspace.show_notification(id, cx, |cx| {
+                    cx.new_view(|_| {
+                        let notification = MessageNotification::new(message);
+                        if !downloadable {
+                            return notification;
+                        }
+                        notification
+                            .with_click_message("Download Again")
+                            .on_click(move |cx| {
+                                project
+                                    .update(cx, |project, cx| {
+                                        project.reinstall_language_server(
+                                            check.language.clone(),
+                                            check.name.clone(),
+                                            cx,
+                                        )
+                                    })
+                                    .detach();
+                            })
+                    })
+                });
+            }
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn report(checks: &[LanguageServerBinaryCheck]) -> String {
+    if checks.is_empty() {
+        return "No language servers are configured for the open files.".to_string();
+    }
+    let lines = checks
+        .iter()
+        .map(|check| {
+            let state = if check.running {
+                "running"
+            } else {
+                "not running"
+            };
+            let status = match &check.status {
+                LanguageServerBinaryCheckStatus::Installed { path, downloaded } => {
+                    let source = if *downloaded {
+                        "downloaded"
+                    } else {
+                        "installed"
+                    };
+                    format!("{source} at {path:?}")
+                }
+                LanguageServerBinaryCheckStatus::NotDownloaded => "not downloaded".to_string(),
+                LanguageServerBinaryCheckStatus::Broken { .. } => "broken".to_string(),
+            };
+            format!("{} ({}): {state}, {status}", check.name.0, check.language.0)
+        })
+        .collect::<Vec<_>>();
+    format!("Language servers for the open files:\n{}", lines.join("\n"))
+}
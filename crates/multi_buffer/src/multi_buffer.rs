@@ -107,6 +107,8 @@ pub enum Event {
     Discarded,
     DirtyChanged,
     DiagnosticsUpdated,
+    /// An edit was dropped because the multibuffer is read-only.
+    EditRejected,
 }
 
 /// A diff hunk, representing a range of consequent lines in a multibuffer.
@@ -525,6 +527,7 @@ impl MultiBuffer {
         T: Into<Arc<str>>,
     {
         if self.read_only() {
+            cx.emit(Event::EditRejected);
             return;
         }
         if self.buffers.borrow().is_empty() {
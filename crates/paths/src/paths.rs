@@ -15,19 +15,128 @@ pub fn config_dir() -> &'static PathBuf {
                 .join("Zed");
         }
 
-        if cfg!(target_os = "linux") {
+        if cfg!(any(target_os = "linux", target_os = "freebsd")) {
             return if let Ok(flatpak_xdg_config) = std::env::var("FLATPAK_XDG_CONFIG_HOME") {
-                flatpak_xdg_config.into()
+                PathBuf::from(flatpak_xdg_config).join("zed")
             } else {
-                dirs::config_dir().expect("failed to determine XDG_CONFIG_HOME directory")
-            }
-            .join("zed");
+                xdg_zed_dir("XDG_CONFIG_HOME", ".config")
+            };
         }
 
         home_dir().join(".config").join("zed")
     })
 }
 
+/// Returns the base directory named by an XDG environment variable, or `default_relative_to_home`
+/// inside the home directory. As the XDG base directory spec requires, the variable is ignored
+/// when it is empty or not an absolute path.
+fn xdg_dir(var: &str, default_relative_to_home: &str) -> PathBuf {
+    std::env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .unwrap_or_else(|| home_dir().join(default_relative_to_home))
+}
+
+/// Returns the `zed` directory inside the XDG base directory named by `var`. Until that
+/// directory exists, an existing one at the default location inside the home directory keeps
+/// being used instead, so that setting the variable doesn't hide the user's data. It is copied
+/// over by [`migrate_legacy_xdg_dirs`].
+fn xdg_zed_dir(var: &str, default_relative_to_home: &str) -> PathBuf {
+    let legacy_dir = home_dir().join(default_relative_to_home).join("zed");
+    let dir = xdg_dir(var, default_relative_to_home).join("zed");
+    if uses_legacy_dir(&legacy_dir, &dir) {
+        legacy_dir
+    } else {
+        dir
+    }
+}
+
+fn uses_legacy_dir(legacy_dir: &Path, dir: &Path) -> bool {
+    dir != legacy_dir && !dir.exists() && legacy_dir.is_dir()
+}
+
+/// The XDG base directories Zed keeps its directories in on Linux, as the Flatpak variable
+/// overriding it, the variable naming it and its default location inside the home directory.
+const XDG_ZED_DIRS: [(&str, &str, &str); 2] = [
+    ("FLATPAK_XDG_CONFIG_HOME", "XDG_CONFIG_HOME", ".config"),
+    ("FLATPAK_XDG_DATA_HOME", "XDG_DATA_HOME", ".local/share"),
+];
+
+/// Returns the directories that are still used at their legacy location inside the home
+/// directory, as `(legacy, xdg)` pairs, because the XDG base directory variables point
+/// elsewhere and the directories there don't exist yet.
+pub fn legacy_xdg_dirs() -> Vec<(PathBuf, PathBuf)> {
+    if !cfg!(any(target_os = "linux", target_os = "freebsd")) {
+        return Vec::new();
+    }
+    XDG_ZED_DIRS
+        .into_iter()
+        .filter(|(flatpak_var, _, _)| std::env::var_os(flatpak_var).is_none())
+        .filter_map(|(_, var, default_relative_to_home)| {
+            let legacy_dir = home_dir().join(default_relative_to_home).join("zed");
+            let dir = xdg_dir(var, default_relative_to_home).join("zed");
+            uses_legacy_dir(&legacy_dir, &dir).then_some((legacy_dir, dir))
+        })
+        .collect()
+}
+
+/// Copies the directories returned by [`legacy_xdg_dirs`] to their XDG locations, which are
+/// used from then on. Each copy is made next to its destination and only renamed into place
+/// once it is complete, so an interrupted migration starts over on the next launch. The legacy
+/// directories are left as they are.
+///
+/// Nothing is copied while `is_support_dir_in_use` reports that another instance uses one of the
+/// support directories, since it may still write to the legacy directories.
+/// Must be called before anything resolves the paths in this module.
+/// Returns the directories that were copied, as `(from, to)` pairs.
+pub fn migrate_legacy_xdg_dirs(
+    is_support_dir_in_use: impl Fn(&Path) -> bool,
+) -> std::io::Result<Vec<(PathBuf, PathBuf)>> {
+    let legacy_dirs = legacy_xdg_dirs();
+    if legacy_dirs.is_empty() {
+        return Ok(legacy_dirs);
+    }
+    let (_, data_var, default_data_dir) = XDG_ZED_DIRS[1];
+    let support_dirs = [
+        home_dir().join(default_data_dir).join("zed"),
+        xdg_dir(data_var, default_data_dir).join("zed"),
+    ];
+    if support_dirs.iter().any(|dir| is_support_dir_in_use(dir)) {
+        return Ok(Vec::new());
+    }
+
+    for (legacy_dir, dir) in &legacy_dirs {
+        let mut staging_name = dir.file_name().unwrap_or_default().to_owned();
+        staging_name.push(".migrating");
+        let staging_dir = dir.with_file_name(staging_name);
+        if staging_dir.exists() {
+            std::fs::remove_dir_all(&staging_dir)?;
+        }
+        copy_dir_all(legacy_dir, &staging_dir)?;
+        std::fs::rename(&staging_dir, dir)?;
+    }
+    Ok(legacy_dirs)
+}
+
+fn copy_dir_all(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = to.join(entry.file_name());
+        if file_type.is_dir() {
+            copy_dir_all(&entry.path(), &target)?;
+        } else if file_type.is_file() {
+            std::fs::copy(entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(std::fs::read_link(entry.path())?, &target)?;
+        }
+        // Anything else, like the sockets of previous instances, isn't worth copying.
+    }
+    Ok(())
+}
+
 /// Returns the path to the support directory used by Zed.
 pub fn support_dir() -> &'static PathBuf {
     static SUPPORT_DIR: OnceLock<PathBuf> = OnceLock::new();
@@ -36,13 +145,12 @@ pub fn support_dir() -> &'static PathBuf {
             return home_dir().join("Library/Application Support/Zed");
         }
 
-        if cfg!(target_os = "linux") {
+        if cfg!(any(target_os = "linux", target_os = "freebsd")) {
             return if let Ok(flatpak_xdg_data) = std::env::var("FLATPAK_XDG_DATA_HOME") {
-                flatpak_xdg_data.into()
+                PathBuf::from(flatpak_xdg_data).join("zed")
             } else {
-                dirs::data_local_dir().expect("failed to determine XDG_DATA_HOME directory")
-            }
-            .join("zed");
+                xdg_zed_dir("XDG_DATA_HOME", ".local/share")
+            };
         }
 
         if cfg!(target_os = "windows") {
@@ -71,11 +179,11 @@ pub fn temp_dir() -> &'static PathBuf {
                 .join("Zed");
         }
 
-        if cfg!(target_os = "linux") {
+        if cfg!(any(target_os = "linux", target_os = "freebsd")) {
             return if let Ok(flatpak_xdg_cache) = std::env::var("FLATPAK_XDG_CACHE_HOME") {
                 flatpak_xdg_cache.into()
             } else {
-                dirs::cache_dir().expect("failed to determine XDG_CACHE_HOME directory")
+                xdg_dir("XDG_CACHE_HOME", ".cache")
             }
             .join("zed");
         }
@@ -108,6 +216,13 @@ pub fn old_log_file() -> &'static PathBuf {
     OLD_LOG_FILE.get_or_init(|| logs_dir().join("Zed.log.old"))
 }
 
+/// Returns the path to the copy of the most recent panic report, which is kept after the
+/// report itself has been uploaded.
+pub fn last_panic_file() -> &'static PathBuf {
+    static LAST_PANIC_FILE: OnceLock<PathBuf> = OnceLock::new();
+    LAST_PANIC_FILE.get_or_init(|| logs_dir().join("last_panic.json"))
+}
+
 /// Returns the path to the database directory.
 pub fn database_dir() -> &'static PathBuf {
     static DATABASE_DIR: OnceLock<PathBuf> = OnceLock::new();
@@ -140,6 +255,19 @@ pub fn keymap_file() -> &'static PathBuf {
     KEYMAP_FILE.get_or_init(|| config_dir().join("keymap.json"))
 }
 
+/// Returns the path to the file the effective key bindings are exported to.
+pub fn effective_keymap_file() -> &'static PathBuf {
+    static EFFECTIVE_KEYMAP_FILE: OnceLock<PathBuf> = OnceLock::new();
+    EFFECTIVE_KEYMAP_FILE.get_or_init(|| temp_dir().join("effective-keymap.json"))
+}
+
+/// Returns the path to the directory that unsaved changes are snapshotted to, so that they can
+/// be recovered after a crash.
+pub fn unsaved_changes_dir() -> &'static PathBuf {
+    static UNSAVED_CHANGES_DIR: OnceLock<PathBuf> = OnceLock::new();
+    UNSAVED_CHANGES_DIR.get_or_init(|| temp_dir().join("unsaved_changes"))
+}
+
 /// Returns the path to the `tasks.json` file.
 pub fn tasks_file() -> &'static PathBuf {
     static TASKS_FILE: OnceLock<PathBuf> = OnceLock::new();
@@ -273,6 +401,12 @@ pub fn local_settings_file_relative_path() -> &'static Path {
     Path::new(".zed/settings.json")
 }
 
+/// Returns the relative path to an `env` file within a project, whose variables are set
+/// in the project's terminals and tasks.
+pub fn local_env_file_relative_path() -> &'static Path {
+    Path::new(".zed/env")
+}
+
 /// Returns the relative path to a `tasks.json` file within a project.
 pub fn local_tasks_file_relative_path() -> &'static Path {
     Path::new(".zed/tasks.json")
@@ -282,3 +416,21 @@ pub fn local_tasks_file_relative_path() -> &'static Path {
 pub fn local_vscode_tasks_file_relative_path() -> &'static Path {
     Path::new(".vscode/tasks.json")
 }
+
+/// Returns the main directories and files Zed uses, with a short name for each,
+/// e.g. for `--print-paths`.
+pub fn named_paths() -> Vec<(&'static str, &'static PathBuf)> {
+    vec![
+        ("config", config_dir()),
+        ("settings", settings_file()),
+        ("keymap", keymap_file()),
+        ("themes", themes_dir()),
+        ("support", support_dir()),
+        ("extensions", extensions_dir()),
+        ("languages", languages_dir()),
+        ("database", database_dir()),
+        ("logs", logs_dir()),
+        ("log file", log_file()),
+        ("temp", temp_dir()),
+    ]
+}
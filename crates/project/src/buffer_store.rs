@@ -6,7 +6,7 @@ use crate::{
 use anyhow::{anyhow, Context as _, Result};
 use client::Client;
 use collections::{hash_map, HashMap, HashSet};
-use fs::Fs;
+use fs::{Encoding, FileFormat, Fs, UTF_8};
 use futures::{channel::oneshot, stream::FuturesUnordered, StreamExt};
 use git::blame::Blame;
 use gpui::{
@@ -15,13 +15,15 @@ use gpui::{
 };
 use http_client::Url;
 use language::{
+    language_settings::AllLanguageSettings,
     proto::{
         deserialize_line_ending, deserialize_version, serialize_line_ending, serialize_version,
         split_operations,
     },
-    Buffer, BufferEvent, Capability, File as _, Language, Operation,
+    Buffer, BufferEvent, Capability, File as _, Language, LanguageRegistry, Operation,
 };
 use rpc::{proto, AnyProtoClient, ErrorExt as _, TypedEnvelope};
+use settings::{Settings as _, SettingsLocation};
 use smol::channel::Receiver;
 use std::{io, path::Path, str::FromStr as _, sync::Arc, time::Instant};
 use text::BufferId;
@@ -78,6 +80,12 @@ struct LocalBufferStore {
     local_buffer_ids_by_entry_id: HashMap<ProjectEntryId, BufferId>,
     buffer_store: WeakModel<BufferStore>,
     worktree_store: Model<WorktreeStore>,
+    languages: Arc<LanguageRegistry>,
+    /// The unknown encodings from the settings that were already logged, to log each only once.
+    reported_unknown_encodings: HashSet<String>,
+    /// The encodings and line endings that buffers were opened with instead of the ones from
+    /// the settings, e.g. with `zed --encoding`, see [`Fs::take_file_format_override`].
+    file_format_overrides: HashMap<BufferId, FileFormat>,
     _subscription: Subscription,
 }
 
@@ -93,6 +101,8 @@ pub struct BufferStore {
     opened_buffers: HashMap<BufferId, OpenBuffer>,
     downstream_client: Option<(AnyProtoClient, u64)>,
     shared_buffers: HashMap<proto::PeerId, HashSet<Model<Buffer>>>,
+    /// Buffers whose diff base was set to a git ref, which is kept when the index changes.
+    diff_bases_at_ref: HashSet<BufferId>,
 }
 
 enum OpenBuffer {
@@ -419,8 +429,41 @@ impl BufferStoreImpl for Model<RemoteBufferStore> {
 }
 
 impl LocalBufferStore {
+    /// Returns the encoding and line ending to load and save the file with, from the settings
+    /// of its language. These only apply to the buffers opened in the editor, so that they
+    /// can't break the loading of Zed's own configuration files. Files are loaded as UTF-8 if
+    /// the encoding is unknown.
+    fn file_format(
+        &mut self,
+        worktree: &Model<Worktree>,
+        path: &Path,
+        cx: &AppContext,
+    ) -> FileFormat {
+        let worktree = worktree.read(cx);
+        let settings = AllLanguageSettings::get(
+            Some(SettingsLocation {
+                worktree_id: worktree.id(),
+                path,
+            }),
+            cx,
+        )
+        .language_for_path(&Path::new(worktree.root_name()).join(path), &self.languages);
+        let encoding = Encoding::for_label(settings.encoding.trim().as_bytes());
+        if encoding.is_none() && self.reported_unknown_encodings.insert(settings.encoding.clone())
+        {
+            log::error!(
+                "unknown encoding {:?} in the settings, using UTF-8",
+                settings.encoding
+            );
+        }
+        FileFormat {
+            encoding: encoding.filter(|encoding| *encoding != UTF_8),
+            line_ending: settings.line_endings.line_ending(),
+        }
+    }
+
     fn save_local_buffer(
-        &self,
+        &mut self,
         buffer_handle: Model<Buffer>,
         worktree: Model<Worktree>,
         path: Arc<Path>,
@@ -437,8 +480,14 @@ impl LocalBufferStore {
             has_changed_file = true;
         }
 
+        let file_format = self
+            .file_format_overrides
+            .get(&buffer_id)
+            .copied()
+            .unwrap_or_default()
+            .or(self.file_format(&worktree, &path, cx));
         let save = worktree.update(cx, |worktree, cx| {
-            worktree.write_file(path.as_ref(), text, line_ending, cx)
+            worktree.write_file(path.as_ref(), text, line_ending, file_format, cx)
         });
 
         cx.spawn(move |this, mut cx| async move {
@@ -541,7 +590,11 @@ impl LocalBufferStore {
                 .buffers()
                 .filter_map(|buffer| {
                     let file = File::from_dyn(buffer.read(cx).file())?;
-                    if file.worktree != worktree_handle {
+                    if file.worktree != worktree_handle
+                        || buffer_store
+                            .diff_bases_at_ref
+                            .contains(&buffer.read(cx).remote_id())
+                    {
                         return None;
                     }
                     changed_repos
@@ -832,29 +885,47 @@ impl BufferStoreImpl for Model<LocalBufferStore> {
         cx: &mut ModelContext<BufferStore>,
     ) -> Task<Result<Model<Buffer>>> {
         let buffer_store = cx.weak_model();
-        self.update(cx, |_, cx| {
-            let load_buffer = worktree.update(cx, |worktree, cx| {
-                let load_file = worktree.load_file(path.as_ref(), cx);
-                let reservation = cx.reserve_model();
-                let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
-                cx.spawn(move |_, mut cx| async move {
-                    let loaded = load_file.await?;
-                    let text_buffer = cx
-                        .background_executor()
-                        .spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
-                        .await;
-                    cx.insert_model(reservation, |_| {
-                        Buffer::build(
-                            text_buffer,
-                            loaded.diff_base,
-                            Some(loaded.file),
-                            Capability::ReadWrite,
-                        )
-                    })
-                })
-            });
+        self.update(cx, |this, cx| {
+            let file_format = this.file_format(&worktree, &path, cx);
+            let take_file_format_override = {
+                let worktree = worktree.read(cx);
+                let fs = worktree.as_local().map(|worktree| worktree.fs().clone());
+                let abs_path = worktree.absolutize(&path).ok();
+                async move {
+                    match (fs, abs_path) {
+                        (Some(fs), Some(abs_path)) => fs.take_file_format_override(&abs_path).await,
+                        _ => FileFormat::default(),
+                    }
+                }
+            };
 
             cx.spawn(move |this, mut cx| async move {
+                let file_format_override = take_file_format_override.await;
+                let load_buffer = worktree.update(&mut cx, |worktree, cx| {
+                    let load_file = worktree.load_file(
+                        path.as_ref(),
+                        file_format_override.or(file_format),
+                        cx,
+                    );
+                    let reservation = cx.reserve_model();
+                    let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
+                    cx.spawn(move |_, mut cx| async move {
+                        let loaded = load_file.await?;
+                        let text_buffer = cx
+                            .background_executor()
+                            .spawn(async move { text::Buffer::new(0, buffer_id, loaded.text) })
+                            .await;
+                        cx.insert_model(reservation, |_| {
+                            Buffer::build(
+                                text_buffer,
+                                loaded.diff_base,
+                                Some(loaded.file),
+                                Capability::ReadWrite,
+                            )
+                        })
+                    })
+                })?;
+
                 let buffer = match load_buffer.await {
                     Ok(buffer) => Ok(buffer),
                     Err(error) if is_not_found_error(&error) => cx.new_model(|cx| {
@@ -897,6 +968,14 @@ impl BufferStoreImpl for Model<LocalBufferStore> {
                         }
                     }
 
+                    if file_format_override != FileFormat::default() {
+                        this.file_format_overrides.insert(buffer_id, file_format_override);
+                        cx.observe_release(&buffer, |this, buffer, _| {
+                            this.file_format_overrides.remove(&buffer.remote_id());
+                        })
+                        .detach();
+                    }
+
                     anyhow::Ok(())
                 })??;
 
@@ -974,7 +1053,11 @@ impl BufferStore {
     }
 
     /// Creates a buffer store, optionally retaining its buffers.
-    pub fn local(worktree_store: Model<WorktreeStore>, cx: &mut ModelContext<Self>) -> Self {
+    pub fn local(
+        worktree_store: Model<WorktreeStore>,
+        languages: Arc<LanguageRegistry>,
+        cx: &mut ModelContext<Self>,
+    ) -> Self {
         let this = cx.weak_model();
         Self {
             state: Box::new(cx.new_model(|cx| {
@@ -992,12 +1075,16 @@ impl BufferStore {
                     local_buffer_ids_by_entry_id: Default::default(),
                     buffer_store: this,
                     worktree_store: worktree_store.clone(),
+                    languages,
+                    reported_unknown_encodings: Default::default(),
+                    file_format_overrides: Default::default(),
                     _subscription: subscription,
                 }
             })),
             downstream_client: None,
             opened_buffers: Default::default(),
             shared_buffers: Default::default(),
+            diff_bases_at_ref: Default::default(),
             loading_buffers_by_path: Default::default(),
             worktree_store,
         }
@@ -1024,6 +1111,7 @@ impl BufferStore {
             opened_buffers: Default::default(),
             loading_buffers_by_path: Default::default(),
             shared_buffers: Default::default(),
+            diff_bases_at_ref: Default::default(),
             worktree_store,
         }
     }
@@ -1179,7 +1267,8 @@ impl BufferStore {
         buffer.update(cx, move |_, cx| {
             cx.on_release(move |buffer, cx| {
                 handle
-                    .update(cx, |_, cx| {
+                    .update(cx, |this, cx| {
+                        this.diff_bases_at_ref.remove(&buffer.remote_id());
                         cx.emit(BufferStoreEvent::BufferDropped(buffer.remote_id()))
                     })
                     .ok();
@@ -1211,6 +1300,59 @@ impl BufferStore {
         Ok(())
     }
 
+    /// Diffs the buffer against its file's contents at the given git ref, like `HEAD~1`,
+    /// instead of against the git index. This lasts for as long as the buffer is open.
+    ///
+    /// The returned task resolves once the buffer's diff against the ref has been computed.
+    pub fn set_diff_base_to_git_ref(
+        &mut self,
+        buffer: Model<Buffer>,
+        git_ref: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(file) = File::from_dyn(buffer.read(cx).file()) else {
+            return Task::ready(Err(anyhow!("the buffer isn't saved in a file")));
+        };
+        let Some(snapshot) = file
+            .worktree
+            .read(cx)
+            .as_local()
+            .map(|worktree| worktree.snapshot())
+        else {
+            return Task::ready(Err(anyhow!("only local files can be diffed against git")));
+        };
+        let Some((repo_entry, local_repo_entry)) = snapshot.repo_for_path(&file.path) else {
+            return Task::ready(Err(anyhow!(
+                "{} isn't in a git repository",
+                file.path.display()
+            )));
+        };
+        let relative_path = match repo_entry.relativize(&snapshot, &file.path) {
+            Ok(relative_path) => relative_path,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let repo = local_repo_entry.repo().clone();
+        let buffer_id = buffer.read(cx).remote_id();
+
+        cx.spawn(|this, mut cx| async move {
+            let base_text = cx
+                .background_executor()
+                .spawn(async move { repo.load_text_at_ref(&git_ref, &relative_path) })
+                .await?;
+            let recalculate_diff = this.update(&mut cx, |this, cx| {
+                this.diff_bases_at_ref.insert(buffer_id);
+                buffer.update(cx, |buffer, cx| {
+                    buffer.set_diff_base(Some(base_text), cx);
+                    buffer.recalculate_diff(cx)
+                })
+            })?;
+            if let Some(recalculate_diff) = recalculate_diff {
+                recalculate_diff.await;
+            }
+            Ok(())
+        })
+    }
+
     pub fn buffers(&self) -> impl '_ + Iterator<Item = Model<Buffer>> {
         self.opened_buffers
             .values()
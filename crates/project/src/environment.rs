@@ -1,12 +1,14 @@
 use anyhow::Result;
+use fs::Fs;
 use futures::{future::Shared, FutureExt};
+use paths::local_env_file_relative_path;
 use std::{path::Path, sync::Arc};
-use util::ResultExt;
+use util::{parse_env_file, ResultExt};
 
 use collections::HashMap;
 use gpui::{AppContext, Context, Model, ModelContext, Task};
 use settings::Settings as _;
-use worktree::WorktreeId;
+use worktree::{PathChange, UpdatedEntriesSet, Worktree, WorktreeId};
 
 use crate::{
     project_settings::{DirenvSettings, ProjectSettings},
@@ -17,26 +19,47 @@ pub struct ProjectEnvironment {
     cli_environment: Option<HashMap<String, String>>,
     get_environment_task: Option<Shared<Task<Option<HashMap<String, String>>>>>,
     cached_shell_environments: HashMap<WorktreeId, HashMap<String, String>>,
+    env_file_variables: HashMap<WorktreeId, HashMap<String, String>>,
+    fs: Option<Arc<dyn Fs>>,
 }
 
 impl ProjectEnvironment {
+    /// When `fs` is given, the variables in each worktree's `.zed/env` file are loaded from it.
+    /// They are only applied when the user opted into `load_env_file`.
     pub fn new(
         worktree_store: &Model<WorktreeStore>,
         cli_environment: Option<HashMap<String, String>>,
+        fs: Option<Arc<dyn Fs>>,
         cx: &mut AppContext,
     ) -> Model<Self> {
         cx.new_model(|cx| {
-            cx.subscribe(worktree_store, |this: &mut Self, _, event, _| {
-                if let WorktreeStoreEvent::WorktreeRemoved(_, id) = event {
-                    this.remove_worktree_environment(*id);
-                }
-            })
+            cx.subscribe(
+                worktree_store,
+                move |this: &mut Self, _, event, cx| match event {
+                    WorktreeStoreEvent::WorktreeAdded(worktree) => {
+                        if this.fs.is_some() {
+                            cx.subscribe(worktree, |this, worktree, event, cx| {
+                                if let worktree::Event::UpdatedEntries(changes) = event {
+                                    this.update_env_file(&worktree, changes, cx);
+                                }
+                            })
+                            .detach();
+                        }
+                    }
+                    WorktreeStoreEvent::WorktreeRemoved(_, id) => {
+                        this.remove_worktree_environment(*id);
+                    }
+                    _ => {}
+                },
+            )
             .detach();
 
             Self {
                 cli_environment,
                 get_environment_task: None,
                 cached_shell_environments: Default::default(),
+                env_file_variables: Default::default(),
+                fs,
             }
         })
     }
@@ -54,6 +77,101 @@ impl ProjectEnvironment {
 
     pub(crate) fn remove_worktree_environment(&mut self, worktree_id: WorktreeId) {
         self.cached_shell_environments.remove(&worktree_id);
+        self.env_file_variables.remove(&worktree_id);
+    }
+
+    /// Returns the variables from the worktree's `.zed/env` file, which take precedence over
+    /// the shell environment in terminals and tasks.
+    ///
+    /// Since the file comes with the project, these are only returned when the user opted into
+    /// `load_env_file` in their own settings.
+    pub fn env_file_variables(
+        &self,
+        worktree_id: WorktreeId,
+        cx: &AppContext,
+    ) -> Option<&HashMap<String, String>> {
+        if !ProjectSettings::get_global(cx).load_env_file {
+            return None;
+        }
+        self.env_file_variables.get(&worktree_id)
+    }
+
+    /// Reads the worktree's `.zed/env` file from disk, instead of waiting for the worktree
+    /// scan to pick it up, and returns its variables.
+    pub fn load_env_file(
+        &mut self,
+        worktree: &Model<Worktree>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Option<HashMap<String, String>>> {
+        let Some(fs) = self.fs.clone() else {
+            return Task::ready(None);
+        };
+        if !ProjectSettings::get_global(cx).load_env_file {
+            return Task::ready(None);
+        }
+        let worktree = worktree.read(cx);
+        let worktree_id = worktree.id();
+        let Some(abs_path) = worktree
+            .absolutize(local_env_file_relative_path())
+            .log_err()
+        else {
+            return Task::ready(None);
+        };
+        cx.spawn(|this, mut cx| async move {
+            let variables = if fs.is_file(&abs_path).await {
+                Some(read_env_file(fs.as_ref(), &abs_path).await.log_err()?)
+            } else {
+                None
+            };
+            this.update(&mut cx, |this, _| match &variables {
+                Some(variables) => {
+                    this.env_file_variables
+                        .insert(worktree_id, variables.clone());
+                }
+                None => {
+                    this.env_file_variables.remove(&worktree_id);
+                }
+            })
+            .ok()?;
+            variables
+        })
+    }
+
+    fn update_env_file(
+        &mut self,
+        worktree: &Model<Worktree>,
+        changes: &UpdatedEntriesSet,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(fs) = self.fs.clone() else {
+            return;
+        };
+        let Some((_, _, change)) = changes
+            .iter()
+            .find(|(path, _, _)| &**path == local_env_file_relative_path())
+        else {
+            return;
+        };
+
+        let worktree = worktree.read(cx);
+        let worktree_id = worktree.id();
+        if *change == PathChange::Removed {
+            self.env_file_variables.remove(&worktree_id);
+            return;
+        }
+        let Some(abs_path) = worktree
+            .absolutize(local_env_file_relative_path())
+            .log_err()
+        else {
+            return;
+        };
+        cx.spawn(|this, mut cx| async move {
+            let variables = read_env_file(fs.as_ref(), &abs_path).await?;
+            this.update(&mut cx, |this, _| {
+                this.env_file_variables.insert(worktree_id, variables);
+            })
+        })
+        .detach_and_log_err(cx);
     }
 
     /// Returns the inherited CLI environment, if this project was opened from the Zed CLI.
@@ -145,6 +263,15 @@ impl ProjectEnvironment {
     }
 }
 
+async fn read_env_file(fs: &dyn Fs, abs_path: &Path) -> Result<HashMap<String, String>> {
+    let content = fs.load(abs_path).await?;
+    let mut variables = HashMap::default();
+    parse_env_file(&content, |key, value| {
+        variables.insert(key, value);
+    });
+    Ok(variables)
+}
+
 fn set_origin_marker(env: &mut HashMap<String, String>, origin: EnvironmentOrigin) {
     env.insert(ZED_ENVIRONMENT_ORIGIN_MARKER.to_string(), origin.into());
 }
@@ -672,6 +672,7 @@ pub struct LspStore {
     buffer_store: Model<BufferStore>,
     worktree_store: Model<WorktreeStore>,
     buffer_snapshots: HashMap<BufferId, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
+    plain_text_paths: HashSet<ProjectPath>,
     pub languages: Arc<LanguageRegistry>,
     language_server_ids: HashMap<(WorktreeId, LanguageServerName), LanguageServerId>,
     pub language_server_statuses: BTreeMap<LanguageServerId, LanguageServerStatus>,
@@ -733,6 +734,31 @@ pub struct LanguageServerStatus {
     progress_tokens: HashSet<String>,
 }
 
+/// The outcome of checking the binary of a language server used by the open buffers, see
+/// [`LspStore::check_language_server_binaries`].
+#[derive(Clone, Debug)]
+pub struct LanguageServerBinaryCheck {
+    pub name: LanguageServerName,
+    pub language: LanguageName,
+    pub running: bool,
+    pub status: LanguageServerBinaryCheckStatus,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LanguageServerBinaryCheckStatus {
+    /// The binary exists and is executable. It was either downloaded by Zed, or configured in
+    /// the settings or found on the system.
+    Installed { path: PathBuf, downloaded: bool },
+    /// The binary hasn't been downloaded yet.
+    NotDownloaded,
+    /// The binary, or a file it needs, is missing or isn't executable.
+    Broken {
+        path: PathBuf,
+        downloaded: bool,
+        error: String,
+    },
+}
+
 #[derive(Clone, Debug)]
 struct CoreSymbol {
     pub language_server_name: LanguageServerName,
@@ -872,6 +898,7 @@ impl LspStore {
             language_server_statuses: Default::default(),
             nonce: StdRng::from_entropy().gen(),
             buffer_snapshots: Default::default(),
+            plain_text_paths: Default::default(),
             next_diagnostic_group_id: Default::default(),
             diagnostic_summaries: Default::default(),
             diagnostics: Default::default(),
@@ -926,6 +953,7 @@ impl LspStore {
             language_server_statuses: Default::default(),
             nonce: StdRng::from_entropy().gen(),
             buffer_snapshots: Default::default(),
+            plain_text_paths: Default::default(),
             next_diagnostic_group_id: Default::default(),
             diagnostic_summaries: Default::default(),
             diagnostics: Default::default(),
@@ -1060,6 +1088,13 @@ impl LspStore {
 
         self.register_buffer_with_language_servers(buffer, cx);
         cx.observe_release(buffer, |this, buffer, cx| {
+            // A file that was too large before may be opened as usual next time.
+            if let Some(file) = buffer.file() {
+                this.plain_text_paths.remove(&ProjectPath {
+                    worktree_id: file.worktree_id(cx),
+                    path: file.path().clone(),
+                });
+            }
             if let Some(file) = File::from_dyn(buffer.file()) {
                 if file.is_local() {
                     let uri = lsp::Url::from_file_path(file.abs_path(cx)).unwrap();
@@ -1147,6 +1182,12 @@ impl LspStore {
         // If the buffer has a language, set it and start the language server if we haven't already.
         let buffer = buffer_handle.read(cx);
         let file = buffer.file()?;
+        if self.plain_text_paths.contains(&ProjectPath {
+            worktree_id: file.worktree_id(cx),
+            path: file.path().clone(),
+        }) {
+            return None;
+        }
 
         let content = buffer.as_rope();
         let available_language = self.languages.language_for_file(file, Some(content), cx);
@@ -1168,6 +1209,13 @@ impl LspStore {
         available_language
     }
 
+    /// Keeps the buffer for the path from getting a language, so that it isn't parsed or sent to
+    /// language servers. Used for files that are too large for that, so it has to be called
+    /// before the buffer is opened. Applies until the buffer is released.
+    pub fn open_as_plain_text(&mut self, project_path: ProjectPath) {
+        self.plain_text_paths.insert(project_path);
+    }
+
     pub fn set_language_for_buffer(
         &mut self,
         buffer: &Model<Buffer>,
@@ -5570,6 +5618,50 @@ impl LspStore {
         }
     }
 
+    /// Stops the language servers with the given name in all worktrees. Servers that are
+    /// still being downloaded or started are cancelled.
+    pub fn stop_language_servers_named(
+        &mut self,
+        name: &LanguageServerName,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let worktree_ids = self
+            .language_server_ids
+            .keys()
+            .filter(|(_, server_name)| server_name == name)
+            .map(|(worktree_id, _)| *worktree_id)
+            .collect::<Vec<_>>();
+        for worktree_id in worktree_ids {
+            self.stop_local_language_server(worktree_id, name.clone(), cx)
+                .detach();
+        }
+    }
+
+    /// Stops the language servers that no open buffer is using. They're started again when a
+    /// buffer that needs them is opened.
+    pub fn stop_unused_language_servers(&mut self, cx: &mut ModelContext<Self>) {
+        if self.as_local().is_none() {
+            return;
+        }
+        let used_server_ids = self
+            .buffer_store
+            .read(cx)
+            .buffers()
+            .flat_map(|buffer| self.language_server_ids_for_buffer(buffer.read(cx), cx))
+            .collect::<HashSet<_>>();
+        let unused_servers = self
+            .language_server_ids
+            .iter()
+            .filter(|(_, server_id)| !used_server_ids.contains(server_id))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for (worktree_id, name) in unused_servers {
+            log::info!("stopping unused language server {name}");
+            self.stop_local_language_server(worktree_id, name, cx)
+                .detach();
+        }
+    }
+
     pub fn restart_language_servers_for_buffers(
         &mut self,
         buffers: impl IntoIterator<Item = Model<Buffer>>,
@@ -5607,6 +5699,136 @@ impl LspStore {
         }
     }
 
+    /// Checks the binaries of the language servers that the open buffers use, without
+    /// downloading or starting any of them.
+    pub fn check_language_server_binaries(
+        &mut self,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Vec<LanguageServerBinaryCheck>> {
+        if self.mode.is_remote() {
+            return Task::ready(Vec::new());
+        }
+
+        let mut worktree_languages = Vec::new();
+        for buffer in self.buffer_store.read(cx).buffers() {
+            let buffer = buffer.read(cx);
+            if let (Some(language), Some(file)) = (buffer.language(), File::from_dyn(buffer.file()))
+            {
+                let worktree_language = (file.worktree.clone(), language.name());
+                if !worktree_languages.contains(&worktree_language) {
+                    worktree_languages.push(worktree_language);
+                }
+            }
+        }
+
+        let mut checked_servers = HashSet::default();
+        let mut checks = Vec::new();
+        for (worktree, language) in worktree_languages {
+            let available_lsp_adapters = self.languages.lsp_adapters(&language);
+            let available_language_servers = available_lsp_adapters
+                .iter()
+                .map(|adapter| adapter.name.clone())
+                .collect::<Vec<_>>();
+            let settings = self.language_settings(&worktree, &language, cx);
+            if !settings.enable_language_server {
+                continue;
+            }
+            let desired_language_servers =
+                settings.customized_language_servers(&available_language_servers);
+
+            let worktree_id = worktree.read(cx).id();
+            for name in desired_language_servers {
+                let Some(adapter) = available_lsp_adapters
+                    .iter()
+                    .find(|adapter| adapter.name == name)
+                    .cloned()
+                else {
+                    continue;
+                };
+                if !checked_servers.insert(name.clone()) {
+                    continue;
+                }
+                let running = self
+                    .language_server_ids
+                    .get(&(worktree_id, name.clone()))
+                    .and_then(|id| self.as_local()?.language_servers.get(id))
+                    .map_or(false, |state| {
+                        matches!(state, LanguageServerState::Running { .. })
+                    });
+                let delegate = LocalLspAdapterDelegate::for_local(self, &worktree, cx)
+                    as Arc<dyn LspAdapterDelegate>;
+                let binary =
+                    self.get_language_server_binary(adapter.clone(), delegate.clone(), false, cx);
+                let container_dir = self.languages.language_server_download_dir(&name);
+                let language = language.clone();
+                checks.push(async move {
+                    let status = match binary.await {
+                        Ok(binary) => check_language_server_binary(&binary, false),
+                        Err(_) => match container_dir {
+                            Some(container_dir) => match adapter
+                                .adapter
+                                .cached_server_binary(
+                                    container_dir.to_path_buf(),
+                                    delegate.as_ref(),
+                                )
+                                .await
+                            {
+                                Some(binary) => check_language_server_binary(&binary, true),
+                                None => LanguageServerBinaryCheckStatus::NotDownloaded,
+                            },
+                            None => LanguageServerBinaryCheckStatus::NotDownloaded,
+                        },
+                    };
+                    LanguageServerBinaryCheck {
+                        name,
+                        language,
+                        running,
+                        status,
+                    }
+                });
+            }
+        }
+
+        cx.spawn(|_, _| join_all(checks))
+    }
+
+    /// Deletes the downloaded binary of a language server, and restarts it for the open buffers
+    /// of the given language so that it's downloaded again.
+    pub fn reinstall_language_server(
+        &mut self,
+        language: LanguageName,
+        name: LanguageServerName,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<()> {
+        let adapter = self
+            .languages
+            .lsp_adapters(&language)
+            .into_iter()
+            .find(|adapter| adapter.name == name);
+        let buffers = self
+            .buffer_store
+            .read(cx)
+            .buffers()
+            .filter(|buffer| {
+                buffer
+                    .read(cx)
+                    .language()
+                    .map_or(false, |buffer_language| buffer_language.name() == language)
+            })
+            .collect::<Vec<_>>();
+        let languages = self.languages.clone();
+        cx.spawn(|this, mut cx| async move {
+            if let Some(adapter) = adapter {
+                adapter.forget_cached_binary().await;
+            }
+            languages.delete_server_container(name).await;
+            this.update(&mut cx, |this, cx| {
+                this.restart_language_servers_for_buffers(buffers, cx)
+            })
+            .ok();
+        })
+    }
+
     fn restart_local_language_servers(
         &mut self,
         worktree: Model<Worktree>,
@@ -7428,6 +7650,51 @@ pub fn language_server_settings<'a, 'b: 'a>(
     .get(language)
 }
 
+fn check_language_server_binary(
+    binary: &LanguageServerBinary,
+    downloaded: bool,
+) -> LanguageServerBinaryCheckStatus {
+    let broken = |error: String| LanguageServerBinaryCheckStatus::Broken {
+        path: binary.path.clone(),
+        downloaded,
+        error,
+    };
+    // Commands without a directory are looked up on the PATH when the server starts.
+    if binary.path.is_absolute() {
+        match std::fs::metadata(&binary.path) {
+            Ok(metadata) if !is_executable(&metadata) => {
+                return broken("it isn't an executable file".to_string())
+            }
+            Ok(_) => {}
+            Err(error) => return broken(error.to_string()),
+        }
+    }
+    // Servers that run on Node are started with the path of their script.
+    if downloaded {
+        for argument in &binary.arguments {
+            let path = Path::new(argument);
+            if path.is_absolute() && !path.exists() {
+                return broken(format!("{path:?} is missing"));
+            }
+        }
+    }
+    LanguageServerBinaryCheckStatus::Installed {
+        path: binary.path.clone(),
+        downloaded,
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt as _;
+    metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(metadata: &std::fs::Metadata) -> bool {
+    metadata.is_file()
+}
+
 pub struct LocalLspAdapterDelegate {
     lsp_store: WeakModel<LspStore>,
     worktree: worktree::Snapshot,
@@ -44,8 +44,8 @@ use language::{
     language_settings::InlayHintKind,
     proto::{deserialize_anchor, serialize_anchor, split_operations},
     Buffer, BufferEvent, CachedLspAdapter, Capability, CodeLabel, ContextProvider, DiagnosticEntry,
-    Documentation, File as _, Language, LanguageRegistry, LanguageServerName, PointUtf16, ToOffset,
-    ToPointUtf16, Transaction, Unclipped,
+    Documentation, File as _, Language, LanguageName, LanguageRegistry, LanguageServerName,
+    PointUtf16, ToOffset, ToPointUtf16, Transaction, Unclipped,
 };
 use lsp::{CompletionContext, DocumentHighlightKind, LanguageServer, LanguageServerId};
 use lsp_command::*;
@@ -97,7 +97,8 @@ pub use worktree::{
 
 pub use buffer_store::ProjectTransaction;
 pub use lsp_store::{
-    DiagnosticSummary, LanguageServerLogType, LanguageServerProgress, LanguageServerPromptRequest,
+    DiagnosticSummary, LanguageServerBinaryCheck, LanguageServerBinaryCheckStatus,
+    LanguageServerLogType, LanguageServerProgress, LanguageServerPromptRequest,
     LanguageServerStatus, LanguageServerToQuery, LspStore, LspStoreEvent,
     SERVER_PROGRESS_THROTTLE_TIMEOUT,
 };
@@ -591,7 +592,8 @@ impl Project {
             cx.subscribe(&worktree_store, Self::on_worktree_store_event)
                 .detach();
 
-            let buffer_store = cx.new_model(|cx| BufferStore::local(worktree_store.clone(), cx));
+            let buffer_store = cx
+                .new_model(|cx| BufferStore::local(worktree_store.clone(), languages.clone(), cx));
             cx.subscribe(&buffer_store, Self::on_buffer_store_event)
                 .detach();
 
@@ -611,7 +613,7 @@ impl Project {
             cx.subscribe(&settings_observer, Self::on_settings_observer_event)
                 .detach();
 
-            let environment = ProjectEnvironment::new(&worktree_store, env, cx);
+            let environment = ProjectEnvironment::new(&worktree_store, env, Some(fs.clone()), cx);
             let lsp_store = cx.new_model(|cx| {
                 LspStore::new_local(
                     buffer_store.clone(),
@@ -703,7 +705,7 @@ impl Project {
             cx.subscribe(&settings_observer, Self::on_settings_observer_event)
                 .detach();
 
-            let environment = ProjectEnvironment::new(&worktree_store, None, cx);
+            let environment = ProjectEnvironment::new(&worktree_store, None, None, cx);
             let lsp_store = cx.new_model(|cx| {
                 LspStore::new_remote(
                     buffer_store.clone(),
@@ -934,7 +936,7 @@ impl Project {
                 search_history: Self::new_search_history(),
                 search_included_history: Self::new_search_history(),
                 search_excluded_history: Self::new_search_history(),
-                environment: ProjectEnvironment::new(&worktree_store, None, cx),
+                environment: ProjectEnvironment::new(&worktree_store, None, None, cx),
                 remotely_created_models: Arc::new(Mutex::new(RemotelyCreatedModels::default())),
             };
             this.set_role(role, cx);
@@ -2239,6 +2241,12 @@ impl Project {
         })
     }
 
+    pub fn open_as_plain_text(&mut self, project_path: ProjectPath, cx: &mut ModelContext<Self>) {
+        self.lsp_store.update(cx, |lsp_store, _| {
+            lsp_store.open_as_plain_text(project_path)
+        })
+    }
+
     pub fn restart_language_servers_for_buffers(
         &mut self,
         buffers: impl IntoIterator<Item = Model<Buffer>>,
@@ -2249,6 +2257,26 @@ impl Project {
         })
     }
 
+    pub fn check_language_server_binaries(
+        &mut self,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Vec<LanguageServerBinaryCheck>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.check_language_server_binaries(cx)
+        })
+    }
+
+    pub fn reinstall_language_server(
+        &mut self,
+        language: LanguageName,
+        name: LanguageServerName,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<()> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.reinstall_language_server(language, name, cx)
+        })
+    }
+
     pub fn cancel_language_server_work_for_buffers(
         &mut self,
         buffers: impl IntoIterator<Item = Model<Buffer>>,
@@ -3314,6 +3342,19 @@ impl Project {
         self.buffer_store.read(cx).blame_buffer(buffer, version, cx)
     }
 
+    /// Diffs the buffer against its file's contents at the given git ref, instead of against
+    /// the git index.
+    pub fn set_diff_base_to_git_ref(
+        &mut self,
+        buffer: Model<Buffer>,
+        git_ref: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.buffer_store.update(cx, |buffer_store, cx| {
+            buffer_store.set_diff_base_to_git_ref(buffer, git_ref, cx)
+        })
+    }
+
     // RPC message handlers
 
     async fn handle_unshare_project(
@@ -3946,6 +3987,68 @@ impl Project {
             .language_server_for_buffer(buffer, server_id, cx)
     }
 
+    /// Returns the environment that tasks of the given local worktree run with: the project
+    /// environment, with the worktree's `.zed/env` file taking precedence over it when the user
+    /// opted into `load_env_file`.
+    pub fn task_environment(
+        &self,
+        worktree_id: Option<WorktreeId>,
+        cx: &mut ModelContext<'_, Project>,
+    ) -> Task<Option<HashMap<String, String>>> {
+        let project_env = self.project_environment(worktree_id, cx);
+        cx.spawn(|project, mut cx| async move {
+            let mut project_env = project_env.await;
+            if let Some(worktree_id) = worktree_id {
+                let env_file_variables = project
+                    .update(&mut cx, |project, cx| {
+                        project
+                            .environment
+                            .read(cx)
+                            .env_file_variables(worktree_id, cx)
+                            .cloned()
+                    })
+                    .ok()
+                    .flatten();
+                if let Some(env_file_variables) = env_file_variables {
+                    project_env
+                        .get_or_insert_with(Default::default)
+                        .extend(env_file_variables);
+                }
+            }
+            project_env
+        })
+    }
+
+    /// Returns the project environment of the given local worktree, without the variables from
+    /// its `.zed/env` file.
+    pub fn project_environment(
+        &self,
+        worktree_id: Option<WorktreeId>,
+        cx: &mut ModelContext<'_, Project>,
+    ) -> Task<Option<HashMap<String, String>>> {
+        let worktree_abs_path = worktree_id
+            .and_then(|worktree_id| self.worktree_for_id(worktree_id, cx))
+            .map(|worktree| worktree.read(cx).abs_path());
+        let project_env = self.environment.update(cx, |environment, cx| {
+            environment.get_environment(worktree_id, worktree_abs_path, cx)
+        });
+        cx.spawn(|_, _| project_env)
+    }
+
+    /// Reads the `.zed/env` file of the given local worktree from disk and returns its
+    /// variables, if there is one and the user opted into `load_env_file`.
+    pub fn load_env_file(
+        &self,
+        worktree_id: WorktreeId,
+        cx: &mut ModelContext<'_, Project>,
+    ) -> Task<Option<HashMap<String, String>>> {
+        let Some(worktree) = self.worktree_for_id(worktree_id, cx) else {
+            return Task::ready(None);
+        };
+        self.environment
+            .update(cx, |environment, cx| environment.load_env_file(&worktree, cx))
+    }
+
     pub fn task_context_for_location(
         &self,
         captured_variables: TaskVariables,
@@ -3962,16 +4065,9 @@ impl Project {
                 (None, None)
             };
 
+            let project_env = self.task_environment(worktree_id, cx);
             cx.spawn(|project, mut cx| async move {
-                let project_env = project
-                    .update(&mut cx, |project, cx| {
-                        let worktree_abs_path = worktree_abs_path.clone();
-                        project.environment.update(cx, |environment, cx| {
-                            environment.get_environment(worktree_id, worktree_abs_path, cx)
-                        })
-                    })
-                    .ok()?
-                    .await;
+                let project_env = project_env.await;
 
                 let mut task_variables = cx
                     .update(|cx| {
@@ -4148,6 +4244,7 @@ impl Project {
                         hide,
                         shell,
                         tags: proto_template.tags,
+                        run_on_open: false,
                     };
                     Some((task_source_kind, task_template))
                 })
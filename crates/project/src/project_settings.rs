@@ -42,6 +42,12 @@ pub struct ProjectSettings {
     #[serde(default)]
     pub load_direnv: DirenvSettings,
 
+    /// Whether to apply the variables from a project's `.zed/env` file to its terminals and
+    /// tasks. Only read from the user settings, since a project must not opt itself in.
+    /// Default: false
+    #[serde(default)]
+    pub load_env_file: bool,
+
     /// Configuration for session-related features
     #[serde(default)]
     pub session: SessionSettings,
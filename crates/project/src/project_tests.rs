@@ -1,10 +1,12 @@
 use crate::{Event, *};
-use fs::FakeFs;
+use fs::{Encoding, FakeFs};
 use futures::{future, StreamExt};
 use gpui::{AppContext, SemanticVersion, UpdateGlobal};
 use http_client::Url;
 use language::{
-    language_settings::{language_settings, AllLanguageSettings, LanguageSettingsContent},
+    language_settings::{
+        language_settings, AllLanguageSettings, LanguageSettingsContent, LineEndingSetting,
+    },
     tree_sitter_rust, tree_sitter_typescript, Diagnostic, DiagnosticSet, FakeLspAdapter,
     LanguageConfig, LanguageMatcher, LanguageName, LineEnding, OffsetRangeExt, Point, ToPoint,
 };
@@ -718,6 +720,222 @@ async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_file_format_settings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let windows_1252 = Encoding::for_label(b"windows-1252").unwrap();
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_file(
+        "/the-root/a.rs",
+        windows_1252.encode("// café\r\n").0.to_vec(),
+    )
+    .await;
+    fs.insert_file("/the-root/b.txt", "café\r\n".as_bytes().to_vec())
+        .await;
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.languages.insert(
+                    "Rust".into(),
+                    LanguageSettingsContent {
+                        encoding: Some("windows-1252".into()),
+                        line_endings: Some(LineEndingSetting::Lf),
+                        ..Default::default()
+                    },
+                );
+            });
+        })
+    });
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    project.read_with(cx, |project, _| project.languages().add(rust_lang()));
+
+    // Files of the language are loaded and saved in its encoding and line endings.
+    let rust_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/the-root/a.rs", cx)
+        })
+        .await
+        .unwrap();
+    rust_buffer.update(cx, |buffer, _| assert_eq!(buffer.text(), "// café\n"));
+    project
+        .update(cx, |project, cx| project.save_buffer(rust_buffer, cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes(Path::new("/the-root/a.rs")).await.unwrap(),
+        windows_1252.encode("// café\n").0.to_vec()
+    );
+
+    // Other files are still UTF-8, and keep their line endings.
+    let text_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/the-root/b.txt", cx)
+        })
+        .await
+        .unwrap();
+    project
+        .update(cx, |project, cx| project.save_buffer(text_buffer, cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes(Path::new("/the-root/b.txt")).await.unwrap(),
+        "café\r\n".as_bytes().to_vec()
+    );
+}
+
+#[gpui::test]
+async fn test_file_format_overrides(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let shift_jis = Encoding::for_label(b"shift_jis").unwrap();
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_file("/the-root/a.txt", shift_jis.encode("こんにちは\r\n").0.to_vec())
+        .await;
+    fs.set_encoding(Path::new("/the-root/a.txt"), shift_jis);
+    fs.set_line_ending(Path::new("/the-root/a.txt"), LineEnding::Unix);
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+
+    // The buffer opened next for the file is loaded and saved with the overrides.
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/the-root/a.txt", cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| assert_eq!(buffer.text(), "こんにちは\n"));
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes(Path::new("/the-root/a.txt")).await.unwrap(),
+        shift_jis.encode("こんにちは\n").0.to_vec()
+    );
+
+    // Once the buffer is released, the file is opened as usual again.
+    fs.insert_file("/the-root/a.txt", "café\r\n".as_bytes().to_vec())
+        .await;
+    cx.update(|_| drop(buffer));
+    cx.executor().run_until_parked();
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/the-root/a.txt", cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| assert_eq!(buffer.text(), "café\n"));
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer, cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes(Path::new("/the-root/a.txt")).await.unwrap(),
+        "café\r\n".as_bytes().to_vec()
+    );
+}
+
+#[gpui::test]
+async fn test_opening_paths_as_plain_text(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            "small.rs": "const A: i32 = 1;",
+            "large.rs": "const B: i32 = 2;",
+            "other.rs": "const C: i32 = 3;",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp("Rust", FakeLspAdapter::default());
+
+    let small_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/the-root/small.rs", cx)
+        })
+        .await
+        .unwrap();
+    let mut fake_server = fake_servers.next().await.unwrap();
+    assert_eq!(
+        fake_server
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await
+            .text_document
+            .uri,
+        lsp::Url::from_file_path("/the-root/small.rs").unwrap(),
+    );
+
+    // The path is marked before the buffer is opened, so the server never hears about it.
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let large_buffer = project
+        .update(cx, |project, cx| {
+            project.open_as_plain_text(
+                ProjectPath {
+                    worktree_id,
+                    path: Path::new("large.rs").into(),
+                },
+                cx,
+            );
+            project.open_local_buffer("/the-root/large.rs", cx)
+        })
+        .await
+        .unwrap();
+    let other_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/the-root/other.rs", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(
+        fake_server
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await
+            .text_document
+            .uri,
+        lsp::Url::from_file_path("/the-root/other.rs").unwrap(),
+    );
+    for (buffer, language) in [
+        (&small_buffer, Some("Rust")),
+        (&large_buffer, None),
+        (&other_buffer, Some("Rust")),
+    ] {
+        buffer.update(cx, |buffer, _| {
+            assert_eq!(
+                buffer.language().map(|language| language.name()),
+                language.map(Into::into)
+            );
+        });
+    }
+
+    // Once the buffer is released, the file is opened as usual again.
+    cx.update(|_| drop(large_buffer));
+    cx.executor().run_until_parked();
+    let large_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/the-root/large.rs", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    large_buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            buffer.language().map(|language| language.name()),
+            Some("Rust".into())
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_reporting_fs_changes_to_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3299,6 +3517,73 @@ async fn test_buffer_deduping(cx: &mut gpui::TestAppContext) {
     assert_eq!(buffer_a_3.entity_id(), buffer_a_id);
 }
 
+#[gpui::test]
+async fn test_diff_base_at_git_ref(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "src": {
+                "lib.rs": "fn one() -> usize { 3 }",
+            },
+        }),
+    )
+    .await;
+    fs.set_ref_contents_for_repo(
+        Path::new("/dir/.git"),
+        "HEAD~1",
+        &[(Path::new("src/lib.rs"), "fn one() -> usize { 1 }".into())],
+    );
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/src/lib.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    let error = project
+        .update(cx, |project, cx| {
+            project.set_diff_base_to_git_ref(buffer.clone(), "missing".into(), cx)
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(error.to_string(), "src/lib.rs doesn't exist at missing");
+
+    project
+        .update(cx, |project, cx| {
+            project.set_diff_base_to_git_ref(buffer.clone(), "HEAD~1".into(), cx)
+        })
+        .await
+        .unwrap();
+    // The diff against the ref is ready as soon as the task resolves.
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            buffer.diff_base().unwrap().to_string(),
+            "fn one() -> usize { 1 }"
+        );
+        assert!(buffer.snapshot().has_git_diff());
+    });
+
+    // Changes to the index don't replace the ref's contents.
+    fs.set_index_for_repo(
+        Path::new("/dir/.git"),
+        &[(Path::new("src/lib.rs"), "fn one() -> usize { 2 }".into())],
+    );
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            buffer.diff_base().unwrap().to_string(),
+            "fn one() -> usize { 1 }"
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_buffer_is_dirty(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -118,6 +118,14 @@ impl Project {
             .read(cx)
             .get_cli_environment()
             .unwrap_or_default();
+        // Then the variables from the project's `.zed/env` file.
+        if let Some(env_file_variables) = settings_location.and_then(|location| {
+            self.environment
+                .read(cx)
+                .env_file_variables(location.worktree_id, cx)
+        }) {
+            env.extend(env_file_variables.clone());
+        }
         // Then extend it with the explicit env variables from the settings, so they take
         // precedence.
         env.extend(settings.env.clone());
@@ -327,6 +335,26 @@ impl Project {
     pub fn local_terminal_handles(&self) -> &Vec<WeakModel<terminal::Terminal>> {
         &self.terminals.local_handles
     }
+
+    /// Whether any task spawned in a local terminal is still running.
+    pub fn has_running_tasks(&self, cx: &AppContext) -> bool {
+        self.running_task_count(cx) > 0
+    }
+
+    pub fn running_task_count(&self, cx: &AppContext) -> usize {
+        self.terminals
+            .local_handles
+            .iter()
+            .filter(|terminal| {
+                terminal.upgrade().map_or(false, |terminal| {
+                    terminal
+                        .read(cx)
+                        .task()
+                        .map_or(false, |task| task.status == TaskStatus::Running)
+                })
+            })
+            .count()
+    }
 }
 
 pub fn wrap_for_ssh(
@@ -420,19 +420,36 @@ pub async fn open_ssh_project(
         })?
     };
 
-    let session = window
-        .update(cx, |workspace, cx| {
-            cx.activate_window();
-            workspace.toggle_modal(cx, |cx| SshConnectionModal::new(&connection_options, cx));
-            let ui = workspace
-                .active_modal::<SshConnectionModal>(cx)
-                .unwrap()
-                .read(cx)
-                .prompt
-                .clone();
-            connect_over_ssh(connection_options.clone(), ui, cx)
-        })?
-        .await?;
+    let (ui, connect) = window.update(cx, |workspace, cx| {
+        cx.activate_window();
+        workspace.toggle_modal(cx, |cx| SshConnectionModal::new(&connection_options, cx));
+        let ui = workspace
+            .active_modal::<SshConnectionModal>(cx)
+            .unwrap()
+            .read(cx)
+            .prompt
+            .clone();
+        (
+            ui.clone(),
+            connect_over_ssh(connection_options.clone(), ui, cx),
+        )
+    })?;
+    let session = match connect.await {
+        Ok(session) => session,
+        Err(error) => {
+            // Keep the modal open with the reason, e.g. when the host is unreachable.
+            let message = format!(
+                "Failed to connect to {}: {error}",
+                connection_options.connection_string()
+            );
+            window
+                .update(cx, |_, cx| {
+                    ui.update(cx, |ui, cx| ui.set_error(message, cx))
+                })
+                .ok();
+            return Err(error);
+        }
+    };
 
     cx.update(|cx| {
         workspace::open_ssh_project(window, connection_options, session, app_state, paths, cx)
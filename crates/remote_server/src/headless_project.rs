@@ -54,7 +54,8 @@ impl HeadlessProject {
             store
         });
         let buffer_store = cx.new_model(|cx| {
-            let mut buffer_store = BufferStore::local(worktree_store.clone(), cx);
+            let mut buffer_store =
+                BufferStore::local(worktree_store.clone(), languages.clone(), cx);
             buffer_store.shared(SSH_PROJECT_ID, session.clone().into(), cx);
             buffer_store
         });
@@ -73,7 +74,8 @@ impl HeadlessProject {
             observer.shared(SSH_PROJECT_ID, session.clone().into(), cx);
             observer
         });
-        let environment = project::ProjectEnvironment::new(&worktree_store, None, cx);
+        let environment =
+            project::ProjectEnvironment::new(&worktree_store, None, Some(fs.clone()), cx);
         let lsp_store = cx.new_model(|cx| {
             let mut lsp_store = LspStore::new_local(
                 buffer_store.clone(),
@@ -68,9 +68,14 @@ impl AppSession {
         let _subscriptions = vec![cx.on_app_quit(Self::app_will_quit)];
 
         let _serialization_task = Some(cx.spawn(|_, cx| async move {
+            let mut stored_windows = None;
             loop {
                 if let Some(windows) = cx.update(|cx| cx.window_stack()).ok().flatten() {
-                    store_window_stack(windows).await;
+                    // Only write when the stack changed, as this runs on every tick.
+                    if stored_windows.as_ref() != Some(&windows) {
+                        store_window_stack(windows.clone()).await;
+                        stored_windows = Some(windows);
+                    }
                 }
 
                 cx.background_executor()
@@ -1,23 +1,23 @@
 use crate::{settings_store::parse_json_with_comments, SettingsAssets};
 use anyhow::{anyhow, Context, Result};
-use collections::BTreeMap;
+use collections::{BTreeMap, HashMap, HashSet};
 use gpui::{Action, AppContext, KeyBinding, SharedString};
 use schemars::{
     gen::{SchemaGenerator, SchemaSettings},
     schema::{InstanceType, Schema, SchemaObject, SingleOrVec, SubschemaValidation},
     JsonSchema,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use util::{asset_str, ResultExt};
 
-#[derive(Debug, Deserialize, Default, Clone, JsonSchema)]
+#[derive(Debug, Deserialize, Serialize, Default, Clone, JsonSchema)]
 #[serde(transparent)]
 pub struct KeymapFile(Vec<KeymapBlock>);
 
-#[derive(Debug, Deserialize, Default, Clone, JsonSchema)]
+#[derive(Debug, Deserialize, Serialize, Default, Clone, JsonSchema)]
 pub struct KeymapBlock {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     context: Option<String>,
     bindings: BTreeMap<String, KeymapAction>,
 }
@@ -32,7 +32,7 @@ impl KeymapBlock {
     }
 }
 
-#[derive(Debug, Deserialize, Default, Clone)]
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
 #[serde(transparent)]
 pub struct KeymapAction(Value);
 
@@ -49,6 +49,30 @@ impl std::fmt::Display for KeymapAction {
     }
 }
 
+/// A binding in a keymap file that is shadowed by a later binding for the same
+/// keystrokes in the same context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeymapConflict {
+    pub context: Option<String>,
+    pub keystrokes: String,
+    pub shadowed_action: String,
+    pub action: String,
+}
+
+impl std::fmt::Display for KeymapConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is bound to both {} and {}",
+            self.keystrokes, self.shadowed_action, self.action
+        )?;
+        if let Some(context) = &self.context {
+            write!(f, " in context \"{context}\"")?;
+        }
+        Ok(())
+    }
+}
+
 impl JsonSchema for KeymapAction {
     fn schema_name() -> String {
         "KeymapAction".into()
@@ -84,6 +108,7 @@ impl KeymapFile {
                     // We want to deserialize the action data as a `RawValue` so that we can
                     // deserialize the action itself dynamically directly from the JSON
                     // string. But `RawValue` currently does not work inside of an untagged enum.
+                    let mut action_input = None;
                     match action {
                         Value::Array(items) => {
                             let Ok([name, data]): Result<[serde_json::Value; 2], _> =
@@ -96,6 +121,7 @@ impl KeymapFile {
                                     "Expected first item in array to be a string."
                                 )));
                             };
+                            action_input = Some(data.clone());
                             cx.build_action(&name, Some(data))
                         }
                         Value::String(name) => cx.build_action(&name, None),
@@ -110,7 +136,13 @@ impl KeymapFile {
                         )
                     })
                     .log_err()
-                    .map(|action| KeyBinding::load(&keystroke, action, context.as_deref()))
+                    .map(|action| {
+                        let binding = KeyBinding::load(&keystroke, action, context.as_deref())?;
+                        Ok(match action_input {
+                            Some(action_input) => binding.with_action_input(action_input),
+                            None => binding,
+                        })
+                    })
                 })
                 .collect::<Result<Vec<_>>>()?;
 
@@ -162,6 +194,85 @@ impl KeymapFile {
     pub fn blocks(&self) -> &[KeymapBlock] {
         &self.0
     }
+
+    /// Returns the bindings that are shadowed by a later binding in this file for the
+    /// same keystrokes and context. Duplicate keys within a single block are already
+    /// collapsed by the JSON parser, so this compares bindings across blocks.
+    pub fn conflicts(&self) -> Vec<KeymapConflict> {
+        let mut bound_actions = HashMap::default();
+        let mut conflicts = Vec::new();
+        for block in &self.0 {
+            let context = block
+                .context
+                .as_deref()
+                .map(str::trim)
+                .filter(|context| !context.is_empty());
+            for (keystrokes, action) in &block.bindings {
+                let keystrokes = keystrokes.split_whitespace().collect::<Vec<_>>().join(" ");
+                let Some(shadowed_action) =
+                    bound_actions.insert((context, keystrokes.clone()), action)
+                else {
+                    continue;
+                };
+                if shadowed_action.0 != action.0 {
+                    conflicts.push(KeymapConflict {
+                        context: context.map(ToOwned::to_owned),
+                        keystrokes,
+                        shadowed_action: shadowed_action.to_string(),
+                        action: action.to_string(),
+                    });
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Builds a keymap file describing the given bindings as they are resolved at
+    /// runtime: when several bindings share keystrokes and a context, only the last
+    /// one is kept. Disabled bindings are written as `null`, and actions that were bound
+    /// with arguments as `[name, arguments]`.
+    pub fn from_bindings<'a>(bindings: impl DoubleEndedIterator<Item = &'a KeyBinding>) -> Self {
+        let mut seen = HashSet::default();
+        let mut effective = bindings
+            .rev()
+            .filter_map(|binding| {
+                let context = binding.predicate().map(|predicate| predicate.to_string());
+                let keystrokes = binding
+                    .keystrokes()
+                    .iter()
+                    .map(|keystroke| keystroke.unparse())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                seen.insert((context.clone(), keystrokes.clone()))
+                    .then(|| (context, keystrokes, binding))
+            })
+            .collect::<Vec<_>>();
+        effective.reverse();
+
+        let mut blocks: Vec<KeymapBlock> = Vec::new();
+        for (context, keystrokes, binding) in effective {
+            let name = Value::String(binding.action().name().to_string());
+            let action = if binding.action().partial_eq(&gpui::NoAction) {
+                Value::Null
+            } else if let Some(input) = binding.action_input() {
+                Value::Array(vec![name, input.clone()])
+            } else {
+                name
+            };
+            let block = match blocks.iter_mut().position(|block| block.context == context) {
+                Some(ix) => &mut blocks[ix],
+                None => {
+                    blocks.push(KeymapBlock {
+                        context,
+                        bindings: BTreeMap::default(),
+                    });
+                    blocks.last_mut().unwrap()
+                }
+            };
+            block.bindings.insert(keystrokes, KeymapAction(action));
+        }
+        Self(blocks)
+    }
 }
 
 fn no_action() -> Box<dyn gpui::Action> {
@@ -170,7 +281,8 @@ fn no_action() -> Box<dyn gpui::Action> {
 
 #[cfg(test)]
 mod tests {
-    use crate::KeymapFile;
+    use crate::{KeymapConflict, KeymapFile};
+    use gpui::{actions, KeyBinding, NoAction};
 
     #[test]
     fn can_deserialize_keymap_with_trailing_comma() {
@@ -187,4 +299,68 @@ mod tests {
         };
         KeymapFile::parse(json).unwrap();
     }
+
+    #[test]
+    fn reports_bindings_shadowed_within_the_file() {
+        let json = indoc::indoc! {r#"[
+              {
+                "context": "Editor",
+                "bindings": {
+                  "ctrl-k ctrl-u": "editor::ConvertToUpperCase",
+                  "ctrl-d": "editor::SelectNext"
+                }
+              },
+              {
+                "bindings": {
+                  "ctrl-d": "editor::DeleteLine"
+                }
+              },
+              {
+                "context": " Editor ",
+                "bindings": {
+                  "ctrl-k  ctrl-u": "editor::ConvertToLowerCase",
+                  "ctrl-d": "editor::SelectNext"
+                }
+              }
+            ]"#
+        };
+        let conflicts = KeymapFile::parse(json).unwrap().conflicts();
+        assert_eq!(
+            conflicts,
+            vec![KeymapConflict {
+                context: Some("Editor".into()),
+                keystrokes: "ctrl-k ctrl-u".into(),
+                shadowed_action: "editor::ConvertToUpperCase".into(),
+                action: "editor::ConvertToLowerCase".into(),
+            }]
+        );
+        assert_eq!(
+            conflicts[0].to_string(),
+            "ctrl-k ctrl-u is bound to both editor::ConvertToUpperCase and editor::ConvertToLowerCase in context \"Editor\""
+        );
+    }
+
+    #[test]
+    fn exports_only_the_bindings_that_take_effect() {
+        actions!(test, [First, Second]);
+
+        let bindings = [
+            KeyBinding::new("ctrl-a", First, None),
+            KeyBinding::new("ctrl-b", First, Some("Editor")),
+            KeyBinding::new("ctrl-a", Second, None),
+            KeyBinding::new("ctrl-b k", Second, Some("Editor && mode == full")),
+            KeyBinding::new("ctrl-b", NoAction, Some("Editor")),
+            KeyBinding::new("ctrl-c", First, None).with_action_input(serde_json::json!(1)),
+        ];
+        let json = serde_json::to_string(&KeymapFile::from_bindings(bindings.iter())).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"[{"bindings":{"ctrl-a":"test::Second","ctrl-c":["test::First",1]}},"#,
+                r#"{"context":"Editor && mode == full","bindings":{"ctrl-b k":"test::Second"}},"#,
+                r#"{"context":"Editor","bindings":{"ctrl-b":null}}]"#
+            )
+        );
+        assert!(KeymapFile::parse(&json).unwrap().conflicts().is_empty());
+    }
 }
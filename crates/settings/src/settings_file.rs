@@ -1,8 +1,17 @@
 use crate::{settings_store::SettingsStore, Settings};
-use fs::Fs;
-use futures::{channel::mpsc, StreamExt};
-use gpui::{AppContext, BackgroundExecutor, ReadGlobal, UpdateGlobal};
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use fs::{Fs, PathEvent};
+use futures::{
+    channel::mpsc,
+    future::{self, Either},
+    stream::BoxStream,
+    StreamExt,
+};
+use gpui::{AppContext, BackgroundExecutor, Global, ReadGlobal, UpdateGlobal};
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 use util::ResultExt;
 
 pub const EMPTY_THEME_NAME: &str = "empty-theme";
@@ -39,30 +48,218 @@ pub fn watch_config_file(
     let (tx, rx) = mpsc::unbounded();
     executor
         .spawn(async move {
-            let (events, _) = fs.watch(&path, Duration::from_millis(100)).await;
-            futures::pin_mut!(events);
+            let mut loaded = false;
+            'watch: loop {
+                // Watch the directory containing the file rather than the file itself, so that
+                // the file is still picked up after being deleted and recreated, as atomic saves
+                // and dotfile managers do. If the file is a symlink, watch the directory of its
+                // target too, since that's where the changes happen.
+                let target = fs.canonicalize(&path).await.ok();
+                let mut watched_paths = vec![path.clone()];
+                watched_paths.extend(target.clone().filter(|target| *target != path));
+                let mut watched_dirs = watched_paths
+                    .iter()
+                    .filter_map(|path| path.parent())
+                    .collect::<Vec<_>>();
+                watched_dirs.dedup();
+                let mut events = futures::stream::select_all(
+                    futures::future::join_all(watched_dirs.into_iter().map(|dir| {
+                        watch_unless_paused(
+                            fs.clone(),
+                            dir.to_path_buf(),
+                            Duration::from_millis(100),
+                        )
+                    }))
+                    .await,
+                );
 
-            let contents = fs.load(&path).await.unwrap_or_default();
-            if tx.unbounded_send(contents).is_err() {
-                return;
-            }
-
-            loop {
-                if events.next().await.is_none() {
-                    break;
+                if !loaded {
+                    loaded = true;
+                    let contents = fs.load(&path).await.unwrap_or_default();
+                    if tx.unbounded_send(contents).is_err() {
+                        return;
+                    }
                 }
 
-                if let Ok(contents) = fs.load(&path).await {
-                    if tx.unbounded_send(contents).is_err() {
-                        break;
+                while let Some(events) = events.next().await {
+                    // An empty batch means that the watchers were resumed, and the file may have
+                    // changed in the meantime.
+                    if !events.is_empty()
+                        && !events
+                            .iter()
+                            .any(|event| watched_paths.contains(&event.path))
+                    {
+                        continue;
+                    }
+
+                    if let Ok(contents) = fs.load(&path).await {
+                        if tx.unbounded_send(contents).is_err() {
+                            return;
+                        }
+                    }
+
+                    // The symlink was pointed somewhere else, so watch the new target instead.
+                    if fs.canonicalize(&path).await.ok() != target {
+                        continue 'watch;
                     }
                 }
+                break;
             }
         })
         .detach();
     rx
 }
 
+static FILE_WATCHERS: FileWatchers = FileWatchers {
+    state: Mutex::new(FileWatchersState {
+        paused: false,
+        subscribers: Vec::new(),
+    }),
+};
+
+/// Whether the watchers of config files and themes are paused, see [`set_file_watchers_paused`].
+pub fn file_watchers_paused() -> bool {
+    FILE_WATCHERS.state.lock().unwrap().paused
+}
+
+/// Pauses or resumes the watchers of config files and themes, e.g. to save battery or to avoid
+/// polling a slow network mount. Watchers re-read what they watch when they're resumed.
+pub fn set_file_watchers_paused(paused: bool) {
+    FILE_WATCHERS.set_paused(paused);
+}
+
+/// Watches `path` like [`Fs::watch`], except that nothing is watched while the file watchers are
+/// paused. After they're resumed, an empty batch of events is yielded, so that the caller can
+/// re-read whatever it's watching.
+pub async fn watch_unless_paused(
+    fs: Arc<dyn Fs>,
+    path: PathBuf,
+    latency: Duration,
+) -> BoxStream<'static, Vec<PathEvent>> {
+    FILE_WATCHERS.watch(fs, path, latency).await
+}
+
+struct FileWatchers {
+    state: Mutex<FileWatchersState>,
+}
+
+struct FileWatchersState {
+    paused: bool,
+    subscribers: Vec<mpsc::UnboundedSender<bool>>,
+}
+
+impl FileWatchers {
+    fn set_paused(&self, paused: bool) {
+        let mut state = self.state.lock().unwrap();
+        if state.paused == paused {
+            return;
+        }
+        state.paused = paused;
+        state
+            .subscribers
+            .retain(|subscriber| subscriber.unbounded_send(paused).is_ok());
+    }
+
+    async fn watch(
+        &self,
+        fs: Arc<dyn Fs>,
+        path: PathBuf,
+        latency: Duration,
+    ) -> BoxStream<'static, Vec<PathEvent>> {
+        let (paused, pause_changes) = {
+            let mut state = self.state.lock().unwrap();
+            let (tx, rx) = mpsc::unbounded();
+            state.subscribers.push(tx);
+            (state.paused, rx)
+        };
+        let events = if paused {
+            None
+        } else {
+            Some(fs.watch(&path, latency).await.0)
+        };
+        futures::stream::unfold(
+            (events, paused, pause_changes),
+            move |(mut events, mut paused, mut pause_changes)| {
+                let fs = fs.clone();
+                let path = path.clone();
+                async move {
+                    loop {
+                        if paused {
+                            paused = pause_changes.next().await?;
+                            if !paused {
+                                // Start watching before the caller re-reads the path, so that
+                                // no change is missed.
+                                events = Some(fs.watch(&path, latency).await.0);
+                                return Some((Vec::new(), (events, paused, pause_changes)));
+                            }
+                            continue;
+                        }
+
+                        let current_events = match &mut events {
+                            Some(events) => events,
+                            None => events.insert(fs.watch(&path, latency).await.0),
+                        };
+                        let next = match future::select(pause_changes.next(), current_events.next())
+                            .await
+                        {
+                            Either::Left((pause_change, _)) => Err(pause_change),
+                            Either::Right((batch, _)) => Ok(batch),
+                        };
+                        match next {
+                            Ok(batch) => {
+                                return batch.map(|batch| (batch, (events, paused, pause_changes)))
+                            }
+                            Err(pause_change) => {
+                                paused = pause_change?;
+                                if paused {
+                                    events = None;
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+        )
+        .boxed()
+    }
+}
+
+/// Callbacks waiting for the user settings to be loaded, see [`on_settings_ready`].
+#[derive(Default)]
+struct SettingsReady {
+    ready: bool,
+    callbacks: Vec<Box<dyn FnOnce(&mut AppContext)>>,
+}
+
+impl Global for SettingsReady {}
+
+/// Runs `callback` once the user settings have been loaded successfully for the first time, or
+/// right away if they already have been.
+///
+/// Init steps that bake in a setting, rather than observing the [`SettingsStore`], should run
+/// from here so they see the user's values instead of the defaults. For example the log level,
+/// window decorations and the telemetry opt-out. If the settings file is invalid at startup, the
+/// callbacks run once it has been fixed.
+pub fn on_settings_ready(cx: &mut AppContext, callback: impl FnOnce(&mut AppContext) + 'static) {
+    let settings_ready = cx.default_global::<SettingsReady>();
+    if settings_ready.ready {
+        callback(cx);
+    } else {
+        settings_ready.callbacks.push(Box::new(callback));
+    }
+}
+
+fn mark_settings_ready(cx: &mut AppContext) {
+    let settings_ready = cx.default_global::<SettingsReady>();
+    if settings_ready.ready {
+        return;
+    }
+    settings_ready.ready = true;
+    for callback in std::mem::take(&mut settings_ready.callbacks) {
+        callback(cx);
+    }
+}
+
 pub fn handle_settings_file_changes(
     mut user_settings_file_rx: mpsc::UnboundedReceiver<String>,
     cx: &mut AppContext,
@@ -72,11 +269,14 @@ pub fn handle_settings_file_changes(
         .background_executor()
         .block(user_settings_file_rx.next())
         .unwrap();
-    SettingsStore::update_global(cx, |store, cx| {
+    let result = SettingsStore::update_global(cx, |store, cx| {
         store
             .set_user_settings(&user_settings_content, cx)
-            .log_err();
+            .log_err()
     });
+    if result.is_some() {
+        mark_settings_ready(cx);
+    }
     cx.spawn(move |mut cx| async move {
         while let Some(user_settings_content) = user_settings_file_rx.next().await {
             let result = cx.update_global(|store: &mut SettingsStore, cx| {
@@ -84,12 +284,16 @@ pub fn handle_settings_file_changes(
                 if let Err(err) = &result {
                     log::error!("Failed to load user settings: {err}");
                 }
+                let loaded = result.is_ok();
                 settings_changed(result.err(), cx);
                 cx.refresh();
+                loaded
             });
-            if result.is_err() {
-                break; // App dropped
-            }
+            match result {
+                Ok(true) => cx.update(mark_settings_ready).ok(),
+                Ok(false) => None,
+                Err(_) => break, // App dropped
+            };
         }
     })
     .detach();
@@ -102,3 +306,150 @@ pub fn update_settings_file<T: Settings>(
 ) {
     SettingsStore::global(cx).update_settings_file::<T>(fs, update);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::{FakeFs, RemoveOptions};
+    use gpui::TestAppContext;
+    use std::{cell::Cell, path::Path, rc::Rc};
+
+    #[gpui::test]
+    async fn test_on_settings_ready_after_invalid_settings(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+        });
+        let (tx, rx) = mpsc::unbounded();
+        tx.unbounded_send("[]".to_string()).unwrap();
+        cx.update(|cx| handle_settings_file_changes(rx, cx, |_, _| {}));
+
+        let runs = Rc::new(Cell::new(0));
+        cx.update(|cx| {
+            let runs = runs.clone();
+            on_settings_ready(cx, move |_| runs.set(runs.get() + 1));
+        });
+        assert_eq!(runs.get(), 0, "the settings failed to load");
+
+        tx.unbounded_send("{}".to_string()).unwrap();
+        cx.run_until_parked();
+        assert_eq!(runs.get(), 1);
+
+        tx.unbounded_send("{\"a\": 1}".to_string()).unwrap();
+        cx.run_until_parked();
+        assert_eq!(runs.get(), 1, "callbacks only run once");
+
+        cx.update(|cx| {
+            let runs = runs.clone();
+            on_settings_ready(cx, move |_| runs.set(runs.get() + 1));
+        });
+        assert_eq!(runs.get(), 2, "late callbacks run right away");
+    }
+
+    #[gpui::test]
+    async fn test_watch_config_file_after_recreation(cx: &mut TestAppContext) {
+        let executor = cx.executor();
+        let fs = FakeFs::new(executor.clone());
+        fs.insert_file("/config/settings.json", b"{\"a\": 1}".to_vec())
+            .await;
+
+        let mut rx = watch_config_file(
+            &executor,
+            fs.clone(),
+            PathBuf::from("/config/settings.json"),
+        );
+        executor.run_until_parked();
+        assert_eq!(rx.next().await.unwrap(), "{\"a\": 1}");
+
+        fs.insert_file("/config/other.json", b"{}".to_vec()).await;
+        executor.run_until_parked();
+        assert!(rx.try_next().is_err(), "unrelated files are ignored");
+
+        fs.remove_file("/config/settings.json".as_ref(), RemoveOptions::default())
+            .await
+            .unwrap();
+        executor.run_until_parked();
+        fs.insert_file("/config/settings.json", b"{\"a\": 2}".to_vec())
+            .await;
+        executor.run_until_parked();
+        let mut latest = None;
+        while let Ok(Some(contents)) = rx.try_next() {
+            latest = Some(contents);
+        }
+        assert_eq!(latest.as_deref(), Some("{\"a\": 2}"));
+
+        fs.insert_file("/config/settings.json", b"{\"a\": 3}".to_vec())
+            .await;
+        executor.run_until_parked();
+        let mut latest = None;
+        while let Ok(Some(contents)) = rx.try_next() {
+            latest = Some(contents);
+        }
+        assert_eq!(latest.as_deref(), Some("{\"a\": 3}"));
+    }
+
+    #[gpui::test]
+    async fn test_paused_file_watchers(cx: &mut TestAppContext) {
+        let executor = cx.executor();
+        let fs = FakeFs::new(executor.clone());
+        fs.create_dir("/config".as_ref()).await.unwrap();
+
+        let watchers = FileWatchers {
+            state: Mutex::new(FileWatchersState {
+                paused: false,
+                subscribers: Vec::new(),
+            }),
+        };
+        let mut events = watchers
+            .watch(
+                fs.clone(),
+                PathBuf::from("/config"),
+                Duration::from_millis(100),
+            )
+            .await;
+        let (tx, mut rx) = mpsc::unbounded();
+        executor
+            .spawn(async move {
+                while let Some(batch) = events.next().await {
+                    tx.unbounded_send(batch).ok();
+                }
+            })
+            .detach();
+        let mut take_batches = || {
+            let mut batches = Vec::new();
+            while let Ok(Some(batch)) = rx.try_next() {
+                batches.push(batch);
+            }
+            batches
+        };
+
+        fs.insert_file("/config/a.json", Vec::new()).await;
+        executor.run_until_parked();
+        let batches = take_batches();
+        assert!(batches
+            .iter()
+            .flatten()
+            .any(|event| event.path == Path::new("/config/a.json")));
+
+        watchers.set_paused(true);
+        executor.run_until_parked();
+        fs.insert_file("/config/b.json", Vec::new()).await;
+        executor.run_until_parked();
+        assert!(take_batches().is_empty(), "nothing is watched while paused");
+
+        watchers.set_paused(false);
+        executor.run_until_parked();
+        let batches = take_batches();
+        assert!(
+            batches.len() == 1 && batches[0].is_empty(),
+            "resuming yields an empty batch"
+        );
+
+        fs.insert_file("/config/c.json", Vec::new()).await;
+        executor.run_until_parked();
+        assert!(take_batches()
+            .iter()
+            .flatten()
+            .any(|event| event.path == Path::new("/config/c.json")));
+    }
+}
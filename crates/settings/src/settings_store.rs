@@ -315,6 +315,41 @@ impl SettingsStore {
         &self.raw_user_settings
     }
 
+    /// Get the default settings, as parsed from the default settings file.
+    pub fn raw_default_settings(&self) -> &serde_json::Value {
+        &self.raw_default_settings
+    }
+
+    /// Returns the default, extension and user settings merged into a single JSON object,
+    /// with the user's overrides for the current release channel applied.
+    pub fn effective_settings(&self) -> Result<serde_json::Value> {
+        use release_channel::ReleaseChannel;
+
+        let release_channel_names = [
+            ReleaseChannel::Dev,
+            ReleaseChannel::Nightly,
+            ReleaseChannel::Preview,
+            ReleaseChannel::Stable,
+        ]
+        .map(|channel| channel.dev_name());
+
+        let mut user_settings = self.raw_user_settings.clone();
+        let release_channel_settings = user_settings.as_object_mut().and_then(|settings| {
+            let current = settings.remove(release_channel::RELEASE_CHANNEL.dev_name());
+            settings.retain(|key, _| !release_channel_names.contains(&key.as_str()));
+            current
+        });
+
+        SettingsSources {
+            default: &self.raw_default_settings,
+            extensions: Some(&self.raw_extension_settings),
+            user: Some(&user_settings),
+            release_channel: release_channel_settings.as_ref(),
+            project: &[],
+        }
+        .json_merge()
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn test(cx: &mut AppContext) -> Self {
         let mut this = Self::new(cx);
@@ -1139,6 +1174,41 @@ mod tests {
     use serde_derive::Deserialize;
     use unindent::Unindent;
 
+    #[gpui::test]
+    fn test_effective_settings(cx: &mut AppContext) {
+        use release_channel::{ReleaseChannel, RELEASE_CHANNEL};
+
+        let mut store = SettingsStore::new(cx);
+        store
+            .set_default_settings(
+                r#"{ "turbo": false, "user": { "name": "", "age": 0 } }"#,
+                cx,
+            )
+            .unwrap();
+
+        let other_channel = if *RELEASE_CHANNEL == ReleaseChannel::Stable {
+            ReleaseChannel::Preview
+        } else {
+            ReleaseChannel::Stable
+        };
+        let user_settings = serde_json::json!({
+            "user": { "name": "Jane Doe" },
+            RELEASE_CHANNEL.dev_name(): { "turbo": true },
+            other_channel.dev_name(): { "user": { "age": 99 } },
+        });
+        store
+            .set_user_settings(&user_settings.to_string(), cx)
+            .unwrap();
+
+        assert_eq!(
+            store.effective_settings().unwrap(),
+            serde_json::json!({
+                "turbo": true,
+                "user": { "name": "Jane Doe", "age": 0 }
+            })
+        );
+    }
+
     #[gpui::test]
     fn test_settings_store_basic(cx: &mut AppContext) {
         let mut store = SettingsStore::new(cx);
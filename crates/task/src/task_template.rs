@@ -57,6 +57,10 @@ pub struct TaskTemplate {
     /// Which shell to use when spawning the task.
     #[serde(default)]
     pub shell: Shell,
+    /// Whether to spawn the task when the project is opened.
+    /// Only honored for tasks from a worktree's `.zed/tasks.json`, and only after the user has trusted that worktree.
+    #[serde(default)]
+    pub run_on_open: bool,
 }
 
 /// What to do with the terminal pane and tab, after the command was started.
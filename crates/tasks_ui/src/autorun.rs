@@ -0,0 +1,200 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ::settings::Settings;
+use collections::{HashMap, HashSet};
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{Model, PromptLevel, ViewContext};
+use project::{Inventory, TaskSourceKind, WorktreeId};
+use sha2::{Digest, Sha256};
+use task::{TaskContext, TaskTemplate, TaskVariables, VariableName};
+use workspace::{tasks::schedule_task, Workspace};
+
+use crate::settings::TaskSettings;
+
+/// Spawns the worktree tasks marked with `run_on_open` as their task files get loaded.
+///
+/// Since cloning a repository must never result in running its commands, the user is asked
+/// to trust each worktree before its tasks are run for the first time, and again whenever
+/// the commands of those tasks change.
+pub(crate) fn observe_tasks_to_run_on_open(
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().read(cx);
+    if !project.is_local() {
+        return;
+    }
+
+    let inventory = project.task_inventory().clone();
+    let mut handled_tasks = HashSet::default();
+    cx.observe(&inventory, move |workspace, inventory, cx| {
+        run_new_tasks_on_open(workspace, &inventory, &mut handled_tasks, cx);
+    })
+    .detach();
+}
+
+fn run_new_tasks_on_open(
+    workspace: &mut Workspace,
+    inventory: &Model<Inventory>,
+    handled_tasks: &mut HashSet<(PathBuf, String)>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    if !TaskSettings::get_global(cx).run_on_open {
+        return;
+    }
+
+    let mut tasks_by_worktree =
+        HashMap::<WorktreeId, Vec<(TaskSourceKind, TaskTemplate)>>::default();
+    for (kind, template) in inventory.read(cx).list_tasks(None, None, None, cx) {
+        let TaskSourceKind::Worktree { id, abs_path, .. } = &kind else {
+            continue;
+        };
+        // Every task is only considered once per workspace, so that editing the tasks file
+        // or dismissing the trust prompt does not spawn the tasks over and over again.
+        if !template.run_on_open
+            || !handled_tasks.insert((abs_path.clone(), template.label.clone()))
+        {
+            continue;
+        }
+        tasks_by_worktree
+            .entry(*id)
+            .or_default()
+            .push((kind, template));
+    }
+
+    for (worktree_id, tasks) in tasks_by_worktree {
+        let Some(worktree) = workspace
+            .project()
+            .read(cx)
+            .worktree_for_id(worktree_id, cx)
+        else {
+            continue;
+        };
+        let worktree_root = worktree.read(cx).abs_path();
+        // The `.zed/env` file is read right away rather than taken from the worktree scan, so
+        // that the variables shown to the user are exactly the ones the tasks run with.
+        let env_file_variables = workspace.project().update(cx, |project, cx| {
+            project.load_env_file(worktree_id, cx)
+        });
+        cx.spawn(|workspace, mut cx| async move {
+            let env_file_variables = env_file_variables.await.unwrap_or_default();
+            let trust_key = trusted_worktree_key(&worktree_root, &tasks, &env_file_variables);
+            if KEY_VALUE_STORE.read_kvp(&trust_key)?.is_none() {
+                let task_list = tasks
+                    .iter()
+                    .map(|(_, template)| format!("{}: {}", template.label, template.command))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let mut detail = format!(
+                    "{} wants to run these tasks when it is opened:\n\n{task_list}\n\n",
+                    worktree_root.display(),
+                );
+                if !env_file_variables.is_empty() {
+                    let variable_list = sorted_variables(&env_file_variables)
+                        .into_iter()
+                        .map(|(key, value)| format!("{key}={value}"))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    detail.push_str(&format!(
+                        "With these variables from its .zed/env file:\n\n{variable_list}\n\n"
+                    ));
+                }
+                detail.push_str("Only trust projects whose tasks you have reviewed.");
+                let answer = workspace.update(&mut cx, |_, cx| {
+                    cx.prompt(
+                        PromptLevel::Warning,
+                        "Run this project's tasks?",
+                        Some(&detail),
+                        &["Trust and Run", "Don't Run"],
+                    )
+                })?;
+                if answer.await? != 0 {
+                    return Ok(());
+                }
+                KEY_VALUE_STORE
+                    .write_kvp(trust_key, "trusted".to_string())
+                    .await?;
+            }
+
+            let mut project_env = workspace
+                .update(&mut cx, |workspace, cx| {
+                    workspace.project().update(cx, |project, cx| {
+                        project.project_environment(Some(worktree_id), cx)
+                    })
+                })?
+                .await
+                .unwrap_or_default();
+            project_env.extend(env_file_variables);
+            workspace.update(&mut cx, |workspace, cx| {
+                let task_context = worktree_task_context(&worktree_root, project_env);
+                for (task_source_kind, template) in tasks {
+                    schedule_task(
+                        workspace,
+                        task_source_kind,
+                        &template,
+                        &task_context,
+                        false,
+                        cx,
+                    );
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+/// The trust is bound to the exact commands that get run and the `.zed/env` variables they
+/// run with, so that pulling changes to either file cannot run anything the user has not seen yet.
+fn trusted_worktree_key(
+    worktree_root: &Path,
+    tasks: &[(TaskSourceKind, TaskTemplate)],
+    env_file_variables: &HashMap<String, String>,
+) -> String {
+    let mut tasks = tasks
+        .iter()
+        .map(|(_, template)| {
+            (
+                &template.label,
+                &template.command,
+                &template.args,
+                &template.cwd,
+                sorted_variables(&template.env),
+            )
+        })
+        .collect::<Vec<_>>();
+    tasks.sort();
+
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{tasks:?}").as_bytes());
+    if !env_file_variables.is_empty() {
+        hasher.update(format!("{:?}", sorted_variables(env_file_variables)).as_bytes());
+    }
+    format!(
+        "tasks_run_on_open_trusted_{}_{}",
+        worktree_root.display(),
+        hex::encode(hasher.finalize())
+    )
+}
+
+fn sorted_variables(variables: &HashMap<String, String>) -> Vec<(&String, &String)> {
+    let mut variables = variables.iter().collect::<Vec<_>>();
+    variables.sort();
+    variables
+}
+
+fn worktree_task_context(
+    worktree_root: &Arc<Path>,
+    project_env: HashMap<String, String>,
+) -> TaskContext {
+    let mut task_variables = TaskVariables::default();
+    task_variables.insert(
+        VariableName::WorktreeRoot,
+        worktree_root.to_string_lossy().to_string(),
+    );
+    TaskContext {
+        cwd: Some(worktree_root.to_path_buf()),
+        task_variables,
+        project_env,
+    }
+}
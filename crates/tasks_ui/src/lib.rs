@@ -6,6 +6,7 @@ use project::{Location, WorktreeId};
 use workspace::tasks::schedule_task;
 use workspace::{tasks::schedule_resolved_task, Workspace};
 
+mod autorun;
 mod modal;
 mod settings;
 
@@ -14,7 +15,8 @@ pub use modal::{Rerun, Spawn};
 pub fn init(cx: &mut AppContext) {
     settings::TaskSettings::register(cx);
     cx.observe_new_views(
-        |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
+        |workspace: &mut Workspace, cx: &mut ViewContext<Workspace>| {
+            autorun::observe_tasks_to_run_on_open(workspace, cx);
             workspace
                 .register_action(spawn_task_or_modal)
                 .register_action(move |workspace, action: &modal::Rerun, cx| {
@@ -5,6 +5,7 @@ use settings::{Settings, SettingsSources};
 #[derive(Serialize, Deserialize, PartialEq, Default)]
 pub(crate) struct TaskSettings {
     pub(crate) show_status_indicator: bool,
+    pub(crate) run_on_open: bool,
 }
 
 /// Task-related settings.
@@ -12,6 +13,9 @@ pub(crate) struct TaskSettings {
 pub(crate) struct TaskSettingsContent {
     /// Whether to show task status indicator in the status bar. Default: true
     show_status_indicator: Option<bool>,
+    /// Whether to offer running the tasks that a project marks with `run_on_open`
+    /// when it is opened. Each project has to be trusted before its tasks are run. Default: true
+    run_on_open: Option<bool>,
 }
 
 impl Settings for TaskSettings {
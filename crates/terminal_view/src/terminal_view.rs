@@ -1078,6 +1078,13 @@ impl Item for TerminalView {
         }
     }
 
+    fn is_busy(&self, cx: &gpui::AppContext) -> bool {
+        self.terminal
+            .read(cx)
+            .task()
+            .map_or(false, |task| task.status == TaskStatus::Running)
+    }
+
     fn has_conflict(&self, _cx: &AppContext) -> bool {
         false
     }
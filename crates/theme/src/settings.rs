@@ -4,7 +4,7 @@ use anyhow::Result;
 use derive_more::{Deref, DerefMut};
 use gpui::{
     px, AppContext, Font, FontFallbacks, FontFeatures, FontStyle, FontWeight, Global, Pixels,
-    Subscription, ViewContext, WindowContext,
+    SharedString, Subscription, ViewContext, WindowContext,
 };
 use refineable::Refineable;
 use schemars::{
@@ -92,6 +92,7 @@ pub struct ThemeSettings {
     pub theme_overrides: Option<ThemeStyleContent>,
     pub ui_density: UiDensity,
     pub unnecessary_code_fade: f32,
+    pub persist_theme_appearance_toggle: bool,
 }
 
 impl ThemeSettings {
@@ -129,6 +130,52 @@ impl ThemeSettings {
             }
         }
     }
+
+    /// Reloads the active theme from the [`ThemeRegistry`] if it is one of the given
+    /// themes and repaints all windows, so that edits to its file show up immediately.
+    ///
+    /// Every window renders with the one active theme, so all of them are repainted. Edits to
+    /// other themes only update the registry, and don't affect any window.
+    ///
+    /// Returns whether the active theme was reloaded.
+    pub fn reload_active_theme_if_changed(
+        changed_themes: &[SharedString],
+        cx: &mut AppContext,
+    ) -> bool {
+        let mut theme_settings = ThemeSettings::get_global(cx).clone();
+        let active_theme_name = theme_settings.active_theme.name.clone();
+        if !changed_themes.contains(&active_theme_name) {
+            return false;
+        }
+        let Some(theme) = ThemeRegistry::global(cx).get(&active_theme_name).log_err() else {
+            return false;
+        };
+
+        theme_settings.active_theme = theme;
+        theme_settings.apply_theme_overrides();
+        ThemeSettings::override_global(theme_settings, cx);
+        cx.refresh();
+        true
+    }
+
+    /// Makes the theme with the given name active, without changing the user's settings.
+    ///
+    /// Returns an error if the [`ThemeRegistry`] has no theme with that name. The theme
+    /// selected in the settings takes over again the next time the current theme is reloaded.
+    pub fn set_active_theme(theme_name: &str, cx: &mut AppContext) -> Result<Arc<Theme>> {
+        let theme = ThemeRegistry::global(cx).get(theme_name)?;
+        let mut theme_settings = ThemeSettings::get_global(cx).clone();
+        theme_settings.active_theme = theme.clone();
+        theme_settings.apply_theme_overrides();
+        ThemeSettings::override_global(theme_settings, cx);
+        cx.refresh();
+        Ok(theme)
+    }
+
+    /// Returns the names of all themes in the [`ThemeRegistry`], sorted alphabetically.
+    pub fn available_theme_names(cx: &AppContext) -> Vec<SharedString> {
+        ThemeRegistry::global(cx).list_names(false)
+    }
 }
 
 /// The appearance of the system.
@@ -280,6 +327,14 @@ pub struct ThemeSettingsContent {
     #[serde(default)]
     pub theme: Option<ThemeSelection>,
 
+    /// Whether toggling between the light and dark theme saves the new
+    /// appearance in the settings file, rather than only applying it until
+    /// the next restart.
+    ///
+    /// Default: true
+    #[serde(default)]
+    pub persist_theme_appearance_toggle: Option<bool>,
+
     /// UNSTABLE: Expect many elements to be broken.
     ///
     // Controls the density of the UI.
@@ -562,6 +617,9 @@ impl settings::Settings for ThemeSettings {
             theme_overrides: None,
             ui_density: defaults.ui_density.unwrap_or(UiDensity::Default),
             unnecessary_code_fade: defaults.unnecessary_code_fade.unwrap_or(0.0),
+            persist_theme_appearance_toggle: defaults
+                .persist_theme_appearance_toggle
+                .unwrap_or(true),
         };
 
         for value in sources.user.into_iter().chain(sources.release_channel) {
@@ -618,6 +676,10 @@ impl settings::Settings for ThemeSettings {
             this.buffer_font_size = this.buffer_font_size.clamp(px(6.), px(100.));
 
             merge(&mut this.buffer_line_height, value.buffer_line_height);
+            merge(
+                &mut this.persist_theme_appearance_toggle,
+                value.persist_theme_appearance_toggle,
+            );
 
             // Clamp the `unnecessary_code_fade` to ensure text can't disappear entirely.
             merge(&mut this.unnecessary_code_fade, value.unnecessary_code_fade);
@@ -10,10 +10,12 @@ use picker::{Picker, PickerDelegate};
 use serde::Deserialize;
 use settings::{update_settings_file, SettingsStore};
 use std::sync::Arc;
-use theme::{Appearance, Theme, ThemeMeta, ThemeRegistry, ThemeSettings};
+use theme::{
+    Appearance, Theme, ThemeMeta, ThemeMode, ThemeRegistry, ThemeSelection, ThemeSettings,
+};
 use ui::{prelude::*, v_flex, ListItem, ListItemSpacing};
 use util::ResultExt;
-use workspace::{ui::HighlightedLabel, ModalView, Workspace};
+use workspace::{notifications::NotificationId, ui::HighlightedLabel, ModalView, Toast, Workspace};
 
 #[derive(PartialEq, Clone, Default, Debug, Deserialize)]
 pub struct Toggle {
@@ -22,18 +24,39 @@ pub struct Toggle {
 }
 
 impl_actions!(theme_selector, [Toggle]);
-actions!(theme_selector, [Reload]);
+actions!(theme_selector, [Reload, ToggleThemeAppearance]);
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(
         |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
-            workspace.register_action(toggle);
+            workspace
+                .register_action(toggle)
+                .register_action(toggle_theme_appearance);
         },
     )
     .detach();
 }
 
 pub fn toggle(workspace: &mut Workspace, toggle: &Toggle, cx: &mut ViewContext<Workspace>) {
+    toggle_with_on_confirm(workspace, toggle.themes_filter.as_ref(), None, cx);
+}
+
+/// Opens the theme selector, and calls `on_confirm` with the name of the theme once one is
+/// chosen. Nothing is called when the selector is dismissed.
+pub fn select_theme(
+    workspace: &mut Workspace,
+    on_confirm: impl Fn(SharedString, &mut WindowContext) + 'static,
+    cx: &mut ViewContext<Workspace>,
+) {
+    toggle_with_on_confirm(workspace, None, Some(Box::new(on_confirm)), cx);
+}
+
+fn toggle_with_on_confirm(
+    workspace: &mut Workspace,
+    themes_filter: Option<&Vec<String>>,
+    on_confirm: Option<Box<dyn Fn(SharedString, &mut WindowContext)>>,
+    cx: &mut ViewContext<Workspace>,
+) {
     let fs = workspace.app_state().fs.clone();
     let telemetry = workspace.client().telemetry().clone();
     workspace.toggle_modal(cx, |cx| {
@@ -41,13 +64,105 @@ pub fn toggle(workspace: &mut Workspace, toggle: &Toggle, cx: &mut ViewContext<W
             cx.view().downgrade(),
             fs,
             telemetry,
-            toggle.themes_filter.as_ref(),
+            themes_filter,
+            on_confirm,
             cx,
         );
         ThemeSelector::new(delegate, cx)
     });
 }
 
+/// Switches between the light and the dark theme, keeping the other settings intact.
+fn toggle_theme_appearance(
+    workspace: &mut Workspace,
+    _: &ToggleThemeAppearance,
+    cx: &mut ViewContext<Workspace>,
+) {
+    struct NoCounterpartTheme;
+
+    let theme_settings = ThemeSettings::get_global(cx);
+    let current_theme = theme_settings.active_theme.clone();
+    let persist = theme_settings.persist_theme_appearance_toggle;
+    let appearance = match current_theme.appearance {
+        Appearance::Light => Appearance::Dark,
+        Appearance::Dark => Appearance::Light,
+    };
+    let Some(counterpart) = counterpart_theme(
+        theme_settings.theme_selection.as_ref(),
+        &current_theme,
+        appearance,
+        cx,
+    ) else {
+        workspace.show_toast(
+            Toast::new(
+                NotificationId::unique::<NoCounterpartTheme>(),
+                format!("There is no theme to switch to from {}", current_theme.name),
+            )
+            .autohide(),
+            cx,
+        );
+        return;
+    };
+
+    if persist {
+        let (mode, light, dark) = match appearance {
+            Appearance::Light => (
+                ThemeMode::Light,
+                counterpart,
+                current_theme.name.to_string(),
+            ),
+            Appearance::Dark => (ThemeMode::Dark, current_theme.name.to_string(), counterpart),
+        };
+        let fs = workspace.app_state().fs.clone();
+        update_settings_file::<ThemeSettings>(fs, cx, move |settings, _| {
+            settings.theme = Some(ThemeSelection::Dynamic { mode, light, dark });
+        });
+    } else {
+        ThemeSettings::set_active_theme(&counterpart, cx).log_err();
+    }
+}
+
+/// Returns the theme to use for the given appearance instead of `current_theme`.
+///
+/// Prefers the theme configured for that appearance, then the theme from the same
+/// family (e.g. "One Light" for "One Dark"), and falls back to the default theme.
+fn counterpart_theme(
+    theme_selection: Option<&ThemeSelection>,
+    current_theme: &Theme,
+    appearance: Appearance,
+    cx: &AppContext,
+) -> Option<String> {
+    let registry = ThemeRegistry::global(cx);
+    if let Some(ThemeSelection::Dynamic { light, dark, .. }) = theme_selection {
+        let configured = match appearance {
+            Appearance::Light => light,
+            Appearance::Dark => dark,
+        };
+        if configured.as_str() != current_theme.name.as_ref() && registry.get(configured).is_ok() {
+            return Some(configured.clone());
+        }
+    }
+
+    let (from, to) = match appearance {
+        Appearance::Light => ("Dark", "Light"),
+        Appearance::Dark => ("Light", "Dark"),
+    };
+    let sibling = current_theme.name.replace(from, to);
+    let themes = registry.list(false);
+    let candidates = || {
+        themes
+            .iter()
+            .filter(|theme| theme.appearance == appearance && theme.name != current_theme.name)
+    };
+    candidates()
+        .find(|theme| theme.name.as_ref() == sibling)
+        .or_else(|| {
+            candidates()
+                .find(|theme| theme.name.as_ref() == ThemeSettings::default_theme(appearance))
+        })
+        .map(|theme| theme.name.to_string())
+}
+
 impl ModalView for ThemeSelector {}
 
 pub struct ThemeSelector {
@@ -83,6 +198,7 @@ pub struct ThemeSelectorDelegate {
     selection_completed: bool,
     selected_index: usize,
     telemetry: Arc<Telemetry>,
+    on_confirm: Option<Box<dyn Fn(SharedString, &mut WindowContext)>>,
     view: WeakView<ThemeSelector>,
 }
 
@@ -92,6 +208,7 @@ impl ThemeSelectorDelegate {
         fs: Arc<dyn Fs>,
         telemetry: Arc<Telemetry>,
         themes_filter: Option<&Vec<String>>,
+        on_confirm: Option<Box<dyn Fn(SharedString, &mut WindowContext)>>,
         cx: &mut ViewContext<ThemeSelector>,
     ) -> Self {
         let original_theme = cx.theme().clone();
@@ -133,6 +250,7 @@ impl ThemeSelectorDelegate {
             selected_index: 0,
             selection_completed: false,
             telemetry,
+            on_confirm,
             view: weak_view,
         };
 
@@ -194,9 +312,13 @@ impl PickerDelegate for ThemeSelectorDelegate {
 
         let appearance = Appearance::from(cx.appearance());
 
-        update_settings_file::<ThemeSettings>(self.fs.clone(), cx, move |settings, _| {
-            settings.set_theme(theme_name.to_string(), appearance);
+        update_settings_file::<ThemeSettings>(self.fs.clone(), cx, {
+            let theme_name = theme_name.clone();
+            move |settings, _| settings.set_theme(theme_name.to_string(), appearance)
         });
+        if let Some(on_confirm) = &self.on_confirm {
+            on_confirm(theme_name, cx);
+        }
 
         self.view
             .update(cx, |_, cx| {
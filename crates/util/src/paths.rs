@@ -289,6 +289,117 @@ impl PathWithPosition {
     }
 }
 
+/// A location in a file that's given by a search pattern or a byte offset, rather than by row
+/// and column. Matching values example: `test.rs:/fn main/`, `test.rs@1024`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FileTarget {
+    /// The start of the first match of a regular expression.
+    Pattern(String),
+    /// An offset in bytes from the start of the file, as it is on disk.
+    ByteOffset(usize),
+}
+
+impl FileTarget {
+    /// Splits a string with a `:/pattern/` or `@offset` suffix into the path and the target.
+    /// Returns `None` when there's no such suffix, in which case the string can be parsed
+    /// with [`PathWithPosition::parse_str`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use util::paths::FileTarget;
+    /// # use std::path::PathBuf;
+    /// assert_eq!(
+    ///     FileTarget::parse_str("src/main.rs:/fn main/"),
+    ///     Some((PathBuf::from("src/main.rs"), FileTarget::Pattern("fn main".into())))
+    /// );
+    /// assert_eq!(
+    ///     FileTarget::parse_str("src/main.rs@120"),
+    ///     Some((PathBuf::from("src/main.rs"), FileTarget::ByteOffset(120)))
+    /// );
+    /// assert_eq!(FileTarget::parse_str("src/main.rs:12"), None);
+    /// ```
+    pub fn parse_str(s: &str) -> Option<(PathBuf, Self)> {
+        let trimmed = s.trim();
+
+        if let Some(without_slash) = trimmed.strip_suffix('/') {
+            // Don't mistake the `:/` of a Windows drive, as in `C:/`, for the pattern's start.
+            let search_start = match without_slash.as_bytes() {
+                [drive, b':', b'/', ..] if drive.is_ascii_alphabetic() => 2,
+                _ => 0,
+            };
+            if let Some(ix) = without_slash[search_start..].find(":/") {
+                let (path, pattern) = without_slash.split_at(search_start + ix);
+                let pattern = &pattern[2..];
+                if !path.is_empty() && !pattern.is_empty() {
+                    return Some((PathBuf::from(path), Self::Pattern(pattern.to_string())));
+                }
+            }
+        }
+
+        let (path, offset) = trimmed.rsplit_once('@')?;
+        if path.is_empty() || offset.is_empty() || !offset.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        Some((PathBuf::from(path), Self::ByteOffset(offset.parse().ok()?)))
+    }
+
+    /// Appends the target to the given path, in the format accepted by [`FileTarget::parse_str`].
+    pub fn to_string(&self, path: &str) -> String {
+        match self {
+            Self::Pattern(pattern) => format!("{path}:/{pattern}/"),
+            Self::ByteOffset(offset) => format!("{path}@{offset}"),
+        }
+    }
+
+    /// Returns the byte offset in `text` that the target refers to. `text` is the file's
+    /// content with its line endings normalized to `\n`, and `crlf` tells whether they are
+    /// `\r\n` on disk, which byte offsets count in. Offsets inside a character or a line
+    /// ending are moved back to its start.
+    pub fn resolve(&self, text: &str, crlf: bool) -> anyhow::Result<usize> {
+        match self {
+            Self::Pattern(pattern) => {
+                let regex = Regex::new(pattern)
+                    .map_err(|error| anyhow::anyhow!("invalid pattern {pattern:?}: {error}"))?;
+                regex
+                    .find(text)
+                    .map(|found| found.start())
+                    .ok_or_else(|| anyhow::anyhow!("no match for pattern {pattern:?}"))
+            }
+            Self::ByteOffset(disk_offset) => {
+                let disk_len = if crlf {
+                    text.len() + text.matches('\n').count()
+                } else {
+                    text.len()
+                };
+                if *disk_offset > disk_len {
+                    anyhow::bail!(
+                        "byte offset {disk_offset} is past the end of the file, \
+                        which has {disk_len} bytes"
+                    );
+                }
+                let mut offset = *disk_offset;
+                if crlf {
+                    // Each `\n` in `text` takes two bytes on disk.
+                    let mut disk_position = 0;
+                    offset = text.len();
+                    for (ix, byte) in text.bytes().enumerate() {
+                        disk_position += if byte == b'\n' { 2 } else { 1 };
+                        if disk_position > *disk_offset {
+                            offset = ix;
+                            break;
+                        }
+                    }
+                }
+                while !text.is_char_boundary(offset) {
+                    offset -= 1;
+                }
+                Ok(offset)
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct PathMatcher {
     sources: Vec<String>,
@@ -437,6 +548,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn file_target_parse_str() {
+        assert_eq!(
+            FileTarget::parse_str("/code/src/lib.rs:/struct \\w+/"),
+            Some((
+                PathBuf::from("/code/src/lib.rs"),
+                FileTarget::Pattern("struct \\w+".into())
+            ))
+        );
+        // The pattern may contain slashes of its own.
+        assert_eq!(
+            FileTarget::parse_str("lib.rs:/a/b/"),
+            Some((PathBuf::from("lib.rs"), FileTarget::Pattern("a/b".into())))
+        );
+        assert_eq!(
+            FileTarget::parse_str("C:/code/lib.rs:/fn/"),
+            Some((
+                PathBuf::from("C:/code/lib.rs"),
+                FileTarget::Pattern("fn".into())
+            ))
+        );
+        assert_eq!(
+            FileTarget::parse_str("node_modules/@types/node@42"),
+            Some((
+                PathBuf::from("node_modules/@types/node"),
+                FileTarget::ByteOffset(42)
+            ))
+        );
+        assert_eq!(FileTarget::parse_str("C:/code/"), None);
+        assert_eq!(FileTarget::parse_str("lib.rs:/"), None);
+        assert_eq!(FileTarget::parse_str("icon@2x.png"), None);
+        assert_eq!(FileTarget::parse_str("lib.rs@+1"), None);
+        assert_eq!(FileTarget::parse_str("@12"), None);
+    }
+
+    #[test]
+    fn file_target_resolve() {
+        let text = "fn main() {\n    println!(\"héllo\");\n}\n";
+        assert_eq!(
+            FileTarget::Pattern("println".into())
+                .resolve(text, false)
+                .unwrap(),
+            16
+        );
+        assert!(FileTarget::Pattern("(".into())
+            .resolve(text, false)
+            .is_err());
+        assert!(FileTarget::Pattern("missing".into())
+            .resolve(text, false)
+            .is_err());
+        assert_eq!(FileTarget::ByteOffset(3).resolve(text, false).unwrap(), 3);
+        // The offset of the second byte of `é` is moved back to its first.
+        assert_eq!(FileTarget::ByteOffset(28).resolve(text, false).unwrap(), 27);
+        assert!(FileTarget::ByteOffset(text.len() + 1)
+            .resolve(text, false)
+            .is_err());
+
+        // With `\r\n` line endings on disk, `println` starts one byte later there.
+        assert_eq!(FileTarget::ByteOffset(17).resolve(text, true).unwrap(), 16);
+        // An offset between `\r` and `\n` is moved back to the line ending's start.
+        assert_eq!(FileTarget::ByteOffset(12).resolve(text, true).unwrap(), 11);
+        assert_eq!(FileTarget::ByteOffset(11).resolve(text, true).unwrap(), 11);
+        let crlf_len = text.len() + 3;
+        assert_eq!(
+            FileTarget::ByteOffset(crlf_len)
+                .resolve(text, true)
+                .unwrap(),
+            text.len()
+        );
+        assert!(FileTarget::ByteOffset(crlf_len + 1)
+            .resolve(text, true)
+            .is_err());
+    }
+
     #[test]
     fn path_with_position_parse_posix_path() {
         // Test POSIX filename edge cases
@@ -270,6 +270,31 @@ pub fn parse_env_output(env: &str, mut f: impl FnMut(String, String)) {
     }
 }
 
+/// Parse a dotenv-style file of `KEY=VALUE` lines, ignoring blank lines and `#` comments.
+/// Lines may start with `export`, and values may be wrapped in single or double quotes.
+pub fn parse_env_file(content: &str, mut f: impl FnMut(String, String)) {
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            continue;
+        }
+        let value = value.trim();
+        let value = ['"', '\'']
+            .into_iter()
+            .find_map(|quote| value.strip_prefix(quote)?.strip_suffix(quote))
+            .unwrap_or(value);
+        f(key.to_string(), value.to_string())
+    }
+}
+
 pub fn merge_json_value_into(source: serde_json::Value, target: &mut serde_json::Value) {
     use serde_json::Value;
 
@@ -696,6 +721,37 @@ pub fn word_consists_of_emojis(s: &str) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_env_file() {
+        let mut env = Vec::new();
+        parse_env_file(
+            r#"
+            # Comments and blank lines are ignored.
+
+            PLAIN=value
+            export EXPORTED=1
+            DOUBLE_QUOTED="with spaces"
+            SINGLE_QUOTED='$NOT_EXPANDED'
+            WITH_EQUALS=a=b
+            EMPTY=
+            not a variable
+            "#,
+            |key, value| env.push((key, value)),
+        );
+        assert_eq!(
+            env,
+            [
+                ("PLAIN", "value"),
+                ("EXPORTED", "1"),
+                ("DOUBLE_QUOTED", "with spaces"),
+                ("SINGLE_QUOTED", "$NOT_EXPANDED"),
+                ("WITH_EQUALS", "a=b"),
+                ("EMPTY", ""),
+            ]
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+        );
+    }
+
     #[test]
     fn test_extend_sorted() {
         let mut vec = vec![];
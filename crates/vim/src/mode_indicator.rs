@@ -1,5 +1,6 @@
 use gpui::{div, Element, Render, Subscription, View, ViewContext, WeakView};
 use itertools::Itertools;
+use settings::SettingsStore;
 use workspace::{item::ItemHandle, ui::prelude::*, StatusItemView};
 
 use crate::{Vim, VimEvent};
@@ -20,6 +21,10 @@ impl ModeIndicator {
         })
         .detach();
 
+        // Re-render when vim mode is toggled so the indicator appears or clears.
+        cx.observe_global::<SettingsStore>(|_, cx| cx.notify())
+            .detach();
+
         let handle = cx.view().clone();
         let window = cx.window_handle();
         cx.observe_new_views::<Vim>(move |_, cx| {
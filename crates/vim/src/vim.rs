@@ -36,7 +36,7 @@ use normal::search::SearchSubmit;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_derive::Serialize;
-use settings::{update_settings_file, Settings, SettingsSources, SettingsStore};
+use settings::{Settings, SettingsSources, SettingsStore};
 use state::{Mode, Operator, RecordedSelection, SearchState, VimGlobals};
 use std::{ops::Range, sync::Arc};
 use surrounds::SurroundsType;
@@ -97,14 +97,6 @@ pub fn init(cx: &mut AppContext) {
         .detach();
 
     cx.observe_new_views(|workspace: &mut Workspace, _| {
-        workspace.register_action(|workspace, _: &ToggleVimMode, cx| {
-            let fs = workspace.app_state().fs.clone();
-            let currently_enabled = Vim::enabled(cx);
-            update_settings_file::<VimModeSetting>(fs, cx, move |setting, _| {
-                *setting = Some(!currently_enabled)
-            })
-        });
-
         workspace.register_action(|_, _: &OpenDefaultKeymap, cx| {
             cx.emit(workspace::Event::OpenBundledFile {
                 text: settings::vim_keymap(),
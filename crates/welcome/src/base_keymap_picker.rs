@@ -26,12 +26,30 @@ pub fn toggle(
     workspace: &mut Workspace,
     _: &ToggleBaseKeymapSelector,
     cx: &mut ViewContext<Workspace>,
+) {
+    toggle_with_on_confirm(workspace, None, cx);
+}
+
+/// Opens the base keymap picker, and calls `on_confirm` with the base keymap once one is
+/// chosen. Nothing is called when the picker is dismissed.
+pub fn select_base_keymap(
+    workspace: &mut Workspace,
+    on_confirm: impl Fn(BaseKeymap, &mut WindowContext) + 'static,
+    cx: &mut ViewContext<Workspace>,
+) {
+    toggle_with_on_confirm(workspace, Some(Box::new(on_confirm)), cx);
+}
+
+fn toggle_with_on_confirm(
+    workspace: &mut Workspace,
+    on_confirm: Option<Box<dyn Fn(BaseKeymap, &mut WindowContext)>>,
+    cx: &mut ViewContext<Workspace>,
 ) {
     let fs = workspace.app_state().fs.clone();
     let telemetry = workspace.client().telemetry().clone();
     workspace.toggle_modal(cx, |cx| {
         BaseKeymapSelector::new(
-            BaseKeymapSelectorDelegate::new(cx.view().downgrade(), fs, telemetry, cx),
+            BaseKeymapSelectorDelegate::new(cx.view().downgrade(), fs, telemetry, on_confirm, cx),
             cx,
         )
     });
@@ -72,6 +90,7 @@ pub struct BaseKeymapSelectorDelegate {
     selected_index: usize,
     telemetry: Arc<Telemetry>,
     fs: Arc<dyn Fs>,
+    on_confirm: Option<Box<dyn Fn(BaseKeymap, &mut WindowContext)>>,
 }
 
 impl BaseKeymapSelectorDelegate {
@@ -79,6 +98,7 @@ impl BaseKeymapSelectorDelegate {
         weak_view: WeakView<BaseKeymapSelector>,
         fs: Arc<dyn Fs>,
         telemetry: Arc<Telemetry>,
+        on_confirm: Option<Box<dyn Fn(BaseKeymap, &mut WindowContext)>>,
         cx: &mut ViewContext<BaseKeymapSelector>,
     ) -> Self {
         let base = BaseKeymap::get(None, cx);
@@ -92,6 +112,7 @@ impl BaseKeymapSelectorDelegate {
             selected_index,
             telemetry,
             fs,
+            on_confirm,
         }
     }
 }
@@ -179,6 +200,9 @@ impl PickerDelegate for BaseKeymapSelectorDelegate {
             update_settings_file::<BaseKeymap>(self.fs.clone(), cx, move |setting, _| {
                 *setting = Some(base_keymap)
             });
+            if let Some(on_confirm) = &self.on_confirm {
+                on_confirm(base_keymap, cx);
+            }
         }
 
         self.view
@@ -71,6 +71,24 @@ impl BaseKeymap {
         }
     }
 
+    /// Parses a keymap from its setting value (e.g. `SublimeText`) or its display
+    /// name (e.g. `Sublime Text`), ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            Self::VSCode,
+            Self::JetBrains,
+            Self::SublimeText,
+            Self::Atom,
+            Self::TextMate,
+            Self::None,
+        ]
+        .into_iter()
+        .find(|keymap| {
+            keymap.to_string().eq_ignore_ascii_case(name)
+                || format!("{keymap:?}").eq_ignore_ascii_case(name)
+        })
+    }
+
     pub fn names() -> impl Iterator<Item = &'static str> {
         Self::OPTIONS.iter().map(|(name, _)| *name)
     }
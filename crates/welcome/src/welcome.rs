@@ -4,6 +4,7 @@ mod multibuffer_hint;
 
 use client::{telemetry::Telemetry, TelemetrySettings};
 use db::kvp::KEY_VALUE_STORE;
+use futures::{channel::mpsc, StreamExt};
 use gpui::{
     actions, svg, AppContext, EventEmitter, FocusHandle, FocusableView, InteractiveElement,
     ParentElement, Render, Styled, Subscription, Task, View, ViewContext, VisualContext, WeakView,
@@ -16,7 +17,7 @@ use vim::VimModeSetting;
 use workspace::{
     dock::DockPosition,
     item::{Item, ItemEvent},
-    open_new, AppState, Welcome, Workspace, WorkspaceId,
+    open_new, AppState, Welcome, Workspace, WorkspaceId, WorkspaceSettings,
 };
 
 pub use base_keymap_setting::BaseKeymap;
@@ -27,6 +28,28 @@ actions!(welcome, [ResetHints]);
 pub const FIRST_OPEN: &str = "first_open";
 pub const DOCS_URL: &str = "https://zed.dev/docs/";
 
+/// A default that the user chose on the welcome page.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WelcomeChoice {
+    Theme(SharedString),
+    BaseKeymap(BaseKeymap),
+    VimMode(bool),
+    MetricTelemetry(bool),
+    DiagnosticTelemetry(bool),
+}
+
+/// The defaults chosen on the welcome page opened by [`show_welcome_view`], in the order they
+/// were chosen. The welcome page saves the choices to the settings file and reports them;
+/// this lets the caller act on them without waiting for the file to be reloaded. It ends once
+/// the welcome page is closed.
+pub struct WelcomeChoices(mpsc::UnboundedReceiver<WelcomeChoice>);
+
+impl WelcomeChoices {
+    pub async fn next(&mut self) -> Option<WelcomeChoice> {
+        self.0.next().await
+    }
+}
+
 pub fn init(cx: &mut AppContext) {
     BaseKeymap::register(cx);
 
@@ -43,13 +66,30 @@ pub fn init(cx: &mut AppContext) {
     base_keymap_picker::init(cx);
 }
 
+/// Whether the welcome view should be shown, which is the case on first launch unless the
+/// `show_welcome` setting is disabled. In that case, the first launch is recorded right away
+/// so that enabling the setting later doesn't bring the welcome view back.
+pub fn should_show_welcome_view(cx: &mut AppContext) -> bool {
+    if !matches!(KEY_VALUE_STORE.read_kvp(FIRST_OPEN), Ok(None)) {
+        return false;
+    }
+    if WorkspaceSettings::get_global(cx).show_welcome {
+        return true;
+    }
+    db::write_and_log(cx, || {
+        KEY_VALUE_STORE.write_kvp(FIRST_OPEN.to_string(), "false".to_string())
+    });
+    false
+}
+
 pub fn show_welcome_view(
     app_state: Arc<AppState>,
     cx: &mut AppContext,
-) -> Task<anyhow::Result<()>> {
-    open_new(Default::default(), app_state, cx, |workspace, cx| {
+) -> Task<anyhow::Result<WelcomeChoices>> {
+    let (choices_tx, choices_rx) = mpsc::unbounded();
+    let open = open_new(Default::default(), app_state, cx, |workspace, cx| {
         workspace.toggle_dock(DockPosition::Left, cx);
-        let welcome_page = WelcomePage::new(workspace, cx);
+        let welcome_page = WelcomePage::with_choices(workspace, Some(choices_tx), cx);
         workspace.add_item_to_center(Box::new(welcome_page.clone()), cx);
         cx.focus_view(&welcome_page);
         cx.notify();
@@ -57,6 +97,10 @@ pub fn show_welcome_view(
         db::write_and_log(cx, || {
             KEY_VALUE_STORE.write_kvp(FIRST_OPEN.to_string(), "false".to_string())
         });
+    });
+    cx.spawn(|_| async move {
+        open.await?;
+        Ok(WelcomeChoices(choices_rx))
     })
 }
 
@@ -64,6 +108,8 @@ pub struct WelcomePage {
     workspace: WeakView<Workspace>,
     focus_handle: FocusHandle,
     telemetry: Arc<Telemetry>,
+    /// Where to send the choices made on this page, if it was opened by [`show_welcome_view`].
+    choices: Option<mpsc::UnboundedSender<WelcomeChoice>>,
     _settings_subscription: Subscription,
 }
 
@@ -96,11 +142,19 @@ impl Render for WelcomePage {
                                         this.telemetry.report_app_event(
                                             "welcome page: change theme".to_string(),
                                         );
+                                        let page = cx.view().downgrade();
                                         this.workspace
                                             .update(cx, |workspace, cx| {
-                                                theme_selector::toggle(
+                                                theme_selector::select_theme(
                                                     workspace,
-                                                    &Default::default(),
+                                                    move |theme, cx| {
+                                                        page.update(cx, |page, _| {
+                                                            page.record_choice(
+                                                                WelcomeChoice::Theme(theme),
+                                                            )
+                                                        })
+                                                        .ok();
+                                                    },
                                                     cx,
                                                 )
                                             })
@@ -114,11 +168,21 @@ impl Render for WelcomePage {
                                         this.telemetry.report_app_event(
                                             "welcome page: change keymap".to_string(),
                                         );
+                                        let page = cx.view().downgrade();
                                         this.workspace
                                             .update(cx, |workspace, cx| {
-                                                base_keymap_picker::toggle(
+                                                base_keymap_picker::select_base_keymap(
                                                     workspace,
-                                                    &Default::default(),
+                                                    move |base_keymap, cx| {
+                                                        page.update(cx, |page, _| {
+                                                            page.record_choice(
+                                                                WelcomeChoice::BaseKeymap(
+                                                                    base_keymap,
+                                                                ),
+                                                            )
+                                                        })
+                                                        .ok();
+                                                    },
                                                     cx,
                                                 )
                                             })
@@ -207,6 +271,11 @@ impl Render for WelcomePage {
                                         cx,
                                         |setting, value| *setting = Some(value),
                                     );
+                                    if let Some(enabled) = selection_value(selection) {
+                                        this.telemetry
+                                            .report_setting_event("vim mode", enabled.to_string());
+                                        this.record_choice(WelcomeChoice::VimMode(enabled));
+                                    }
                                 }),
                             ))
                             .child(CheckboxWithLabel::new(
@@ -221,18 +290,18 @@ impl Render for WelcomePage {
                                     this.telemetry.report_app_event(
                                         "welcome page: toggle metric telemetry".to_string(),
                                     );
-                                    this.update_settings::<TelemetrySettings>(selection, cx, {
-                                        let telemetry = this.telemetry.clone();
-
-                                        move |settings, value| {
-                                            settings.metrics = Some(value);
-
-                                            telemetry.report_setting_event(
-                                                "metric telemetry",
-                                                value.to_string(),
-                                            );
-                                        }
-                                    });
+                                    this.update_settings::<TelemetrySettings>(
+                                        selection,
+                                        cx,
+                                        |settings, value| settings.metrics = Some(value),
+                                    );
+                                    if let Some(enabled) = selection_value(selection) {
+                                        this.telemetry.report_setting_event(
+                                            "metric telemetry",
+                                            enabled.to_string(),
+                                        );
+                                        this.record_choice(WelcomeChoice::MetricTelemetry(enabled));
+                                    }
                                 }),
                             ))
                             .child(CheckboxWithLabel::new(
@@ -247,18 +316,20 @@ impl Render for WelcomePage {
                                     this.telemetry.report_app_event(
                                         "welcome page: toggle diagnostic telemetry".to_string(),
                                     );
-                                    this.update_settings::<TelemetrySettings>(selection, cx, {
-                                        let telemetry = this.telemetry.clone();
-
-                                        move |settings, value| {
-                                            settings.diagnostics = Some(value);
-
-                                            telemetry.report_setting_event(
-                                                "diagnostic telemetry",
-                                                value.to_string(),
-                                            );
-                                        }
-                                    });
+                                    this.update_settings::<TelemetrySettings>(
+                                        selection,
+                                        cx,
+                                        |settings, value| settings.diagnostics = Some(value),
+                                    );
+                                    if let Some(enabled) = selection_value(selection) {
+                                        this.telemetry.report_setting_event(
+                                            "diagnostic telemetry",
+                                            enabled.to_string(),
+                                        );
+                                        this.record_choice(WelcomeChoice::DiagnosticTelemetry(
+                                            enabled,
+                                        ));
+                                    }
                                 }),
                             )),
                     ),
@@ -268,6 +339,14 @@ impl Render for WelcomePage {
 
 impl WelcomePage {
     pub fn new(workspace: &Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        Self::with_choices(workspace, None, cx)
+    }
+
+    fn with_choices(
+        workspace: &Workspace,
+        choices: Option<mpsc::UnboundedSender<WelcomeChoice>>,
+        cx: &mut ViewContext<Workspace>,
+    ) -> View<Self> {
         let this = cx.new_view(|cx| {
             cx.on_release(|this: &mut Self, _, _| {
                 this.telemetry
@@ -275,18 +354,41 @@ impl WelcomePage {
             })
             .detach();
 
-            WelcomePage {
-                focus_handle: cx.focus_handle(),
-                workspace: workspace.weak_handle(),
-                telemetry: workspace.client().telemetry().clone(),
-                _settings_subscription: cx
-                    .observe_global::<SettingsStore>(move |_, cx| cx.notify()),
-            }
+            Self::build(
+                workspace.weak_handle(),
+                workspace.client().telemetry().clone(),
+                choices,
+                cx,
+            )
         });
 
         this
     }
 
+    fn build(
+        workspace: WeakView<Workspace>,
+        telemetry: Arc<Telemetry>,
+        choices: Option<mpsc::UnboundedSender<WelcomeChoice>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        WelcomePage {
+            focus_handle: cx.focus_handle(),
+            workspace,
+            telemetry,
+            choices,
+            _settings_subscription: cx.observe_global::<SettingsStore>(move |_, cx| cx.notify()),
+        }
+    }
+
+    /// Passes a choice on to the caller of [`show_welcome_view`], if the page was opened by it.
+    fn record_choice(&mut self, choice: WelcomeChoice) {
+        if let Some(choices) = &self.choices {
+            if choices.unbounded_send(choice).is_err() {
+                self.choices = None;
+            }
+        }
+    }
+
     fn update_settings<T: Settings>(
         &mut self,
         selection: &Selection,
@@ -295,20 +397,24 @@ impl WelcomePage {
     ) {
         if let Some(workspace) = self.workspace.upgrade() {
             let fs = workspace.read(cx).app_state().fs.clone();
-            let selection = *selection;
+            let Some(value) = selection_value(selection) else {
+                return;
+            };
             settings::update_settings_file::<T>(fs, cx, move |settings, _| {
-                let value = match selection {
-                    Selection::Unselected => false,
-                    Selection::Selected => true,
-                    _ => return,
-                };
-
                 callback(settings, value)
             });
         }
     }
 }
 
+fn selection_value(selection: &Selection) -> Option<bool> {
+    match selection {
+        Selection::Unselected => Some(false),
+        Selection::Selected => Some(true),
+        _ => None,
+    }
+}
+
 impl EventEmitter<ItemEvent> for WelcomePage {}
 
 impl FocusableView for WelcomePage {
@@ -337,11 +443,13 @@ impl Item for WelcomePage {
         _workspace_id: Option<WorkspaceId>,
         cx: &mut ViewContext<Self>,
     ) -> Option<View<Self>> {
-        Some(cx.new_view(|cx| WelcomePage {
-            focus_handle: cx.focus_handle(),
-            workspace: self.workspace.clone(),
-            telemetry: self.telemetry.clone(),
-            _settings_subscription: cx.observe_global::<SettingsStore>(move |_, cx| cx.notify()),
+        Some(cx.new_view(|cx| {
+            Self::build(
+                self.workspace.clone(),
+                self.telemetry.clone(),
+                self.choices.clone(),
+                cx,
+            )
         }))
     }
 
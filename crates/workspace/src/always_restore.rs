@@ -0,0 +1,120 @@
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use std::path::PathBuf;
+use util::ResultExt as _;
+
+use crate::LocalPaths;
+
+const ALWAYS_RESTORED_WORKSPACES_KEY: &str = "always_restored_workspaces";
+
+/// Returns the workspaces that are reopened on startup whatever `restore_on_startup` is set to.
+pub fn always_restored_workspaces() -> Vec<LocalPaths> {
+    read_always_restored_workspaces()
+        .log_err()
+        .unwrap_or_default()
+        .into_iter()
+        .map(LocalPaths::new)
+        .collect()
+}
+
+/// Splits the workspaces to restore on startup, in the order they're opened in, into the ones to
+/// open now and the ones deferred by the `max_restored_workspaces` setting. The ones opened first
+/// are the least recently used, so they're deferred first, except for the always restored ones,
+/// which don't count towards the limit.
+pub fn defer_restored_workspaces(
+    locations: Vec<LocalPaths>,
+    max_restored_workspaces: Option<usize>,
+) -> (Vec<LocalPaths>, Vec<LocalPaths>) {
+    defer_workspaces(
+        locations,
+        &always_restored_workspaces(),
+        max_restored_workspaces,
+    )
+}
+
+fn defer_workspaces(
+    locations: Vec<LocalPaths>,
+    always_restored: &[LocalPaths],
+    max_restored_workspaces: Option<usize>,
+) -> (Vec<LocalPaths>, Vec<LocalPaths>) {
+    let Some(max) = max_restored_workspaces.map(|max| max.max(1)) else {
+        return (locations, Vec::new());
+    };
+    let deferrable = locations
+        .iter()
+        .filter(|location| !always_restored.contains(location))
+        .count();
+    let mut to_defer = deferrable.saturating_sub(max);
+    let mut restored = Vec::new();
+    let mut deferred = Vec::new();
+    for location in locations {
+        if to_defer > 0 && !always_restored.contains(&location) {
+            to_defer -= 1;
+            deferred.push(location);
+        } else {
+            restored.push(location);
+        }
+    }
+    (restored, deferred)
+}
+
+/// Makes the workspace with the given roots always restored, or stops doing so if it already
+/// is. Returns whether it's always restored now.
+pub(crate) async fn toggle_always_restore(paths: &LocalPaths) -> Result<bool> {
+    let mut workspaces = read_always_restored_workspaces()?;
+    let paths = paths.paths().to_vec();
+    let always_restored = match workspaces.iter().position(|workspace| *workspace == paths) {
+        Some(ix) => {
+            workspaces.remove(ix);
+            false
+        }
+        None => {
+            workspaces.push(paths);
+            true
+        }
+    };
+    KEY_VALUE_STORE
+        .write_kvp(
+            ALWAYS_RESTORED_WORKSPACES_KEY.into(),
+            serde_json::to_string(&workspaces)?,
+        )
+        .await?;
+    Ok(always_restored)
+}
+
+fn read_always_restored_workspaces() -> Result<Vec<Vec<PathBuf>>> {
+    match KEY_VALUE_STORE.read_kvp(ALWAYS_RESTORED_WORKSPACES_KEY)? {
+        Some(json) => Ok(serde_json::from_str(&json)?),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_always_restored_workspaces_are_not_deferred() {
+        let [a, b, c, d] = ["/a", "/b", "/c", "/d"].map(|path| LocalPaths::new([path]));
+
+        // Always restored workspaces come first, followed by the last session's from least to
+        // most recently used.
+        let locations = vec![a.clone(), b.clone(), c.clone(), d.clone()];
+        assert_eq!(
+            defer_workspaces(locations.clone(), &[a.clone()], Some(2)),
+            (vec![a.clone(), c.clone(), d.clone()], vec![b.clone()])
+        );
+        assert_eq!(
+            defer_workspaces(locations.clone(), &[a.clone(), b.clone()], Some(1)),
+            (vec![a.clone(), b.clone(), d.clone()], vec![c.clone()])
+        );
+        assert_eq!(
+            defer_workspaces(locations.clone(), &[], Some(2)),
+            (vec![c.clone(), d.clone()], vec![a.clone(), b.clone()])
+        );
+        assert_eq!(
+            defer_workspaces(locations.clone(), &[a.clone()], None),
+            (locations, Vec::new())
+        );
+    }
+}
@@ -579,6 +579,15 @@ impl Dock {
         }
     }
 
+    /// Resizes all panels of the dock, so that it keeps its size whichever panel is shown.
+    pub fn resize_all_panels(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        let size = size.map(|size| size.max(RESIZE_HANDLE_SIZE).round());
+        for entry in &mut self.panel_entries {
+            entry.panel.set_size(size, cx);
+        }
+        cx.notify();
+    }
+
     pub fn toggle_action(&self) -> Box<dyn Action> {
         match self.position {
             DockPosition::Left => crate::ToggleLeftDock.boxed_clone(),
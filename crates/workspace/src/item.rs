@@ -6,7 +6,7 @@ use crate::{
     DelayedDebouncedEditAction, FollowableViewRegistry, ItemNavHistory, SerializableItemRegistry,
     ToolbarItemLocation, ViewId, Workspace, WorkspaceId,
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use client::{
     proto::{self, PeerId},
     Client,
@@ -183,6 +183,7 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
 
     fn to_item_events(_event: &Self::Event, _f: impl FnMut(ItemEvent)) {}
 
+    fn activated(&mut self, _: &mut ViewContext<Self>) {}
     fn deactivated(&mut self, _: &mut ViewContext<Self>) {}
     fn discarded(&self, _project: Model<Project>, _cx: &mut ViewContext<Self>) {}
     fn workspace_deactivated(&mut self, _: &mut ViewContext<Self>) {}
@@ -224,6 +225,11 @@ pub trait Item: FocusableView + EventEmitter<Self::Event> {
     fn is_dirty(&self, _: &AppContext) -> bool {
         false
     }
+    /// Whether the item is running something that quitting would stop, like a task in a
+    /// terminal. Busy items may also report themselves as dirty, but have nothing to save.
+    fn is_busy(&self, _: &AppContext) -> bool {
+        false
+    }
     fn has_conflict(&self, _: &AppContext) -> bool {
         false
     }
@@ -311,6 +317,30 @@ pub trait SerializableItem: Item {
         _cx: &mut ViewContext<Pane>,
     ) -> Task<Result<View<Self>>>;
 
+    /// The tab title of a serialized item, shown while the item isn't loaded yet.
+    fn serialized_tab_title(
+        _workspace_id: WorkspaceId,
+        _item_id: ItemId,
+        _cx: &AppContext,
+    ) -> Option<SharedString> {
+        None
+    }
+
+    /// Copies the state of a serialized item to another item id, so that an item restored as a
+    /// placeholder can keep its state under the placeholder's id. Items that don't support this
+    /// are always loaded when restored.
+    fn copy_serialized(
+        _workspace_id: WorkspaceId,
+        _from_item_id: ItemId,
+        _to_item_id: ItemId,
+        _cx: &mut WindowContext,
+    ) -> Task<Result<()>> {
+        Task::ready(Err(anyhow!(
+            "{} items can't be copied",
+            Self::serialized_item_kind()
+        )))
+    }
+
     fn serialize(
         &mut self,
         workspace: &mut Workspace,
@@ -394,6 +424,7 @@ pub trait ItemHandle: 'static + Send {
         pane: View<Pane>,
         cx: &mut ViewContext<Workspace>,
     );
+    fn activated(&self, cx: &mut WindowContext);
     fn deactivated(&self, cx: &mut WindowContext);
     fn discarded(&self, project: Model<Project>, cx: &mut WindowContext);
     fn workspace_deactivated(&self, cx: &mut WindowContext);
@@ -401,6 +432,7 @@ pub trait ItemHandle: 'static + Send {
     fn item_id(&self) -> EntityId;
     fn to_any(&self) -> AnyView;
     fn is_dirty(&self, cx: &AppContext) -> bool;
+    fn is_busy(&self, cx: &AppContext) -> bool;
     fn has_conflict(&self, cx: &AppContext) -> bool;
     fn can_save(&self, cx: &AppContext) -> bool;
     fn save(
@@ -740,6 +772,10 @@ impl<T: Item> ItemHandle for View<T> {
         self.update(cx, |this, cx| this.discarded(project, cx));
     }
 
+    fn activated(&self, cx: &mut WindowContext) {
+        self.update(cx, |this, cx| this.activated(cx));
+    }
+
     fn deactivated(&self, cx: &mut WindowContext) {
         self.update(cx, |this, cx| this.deactivated(cx));
     }
@@ -764,6 +800,10 @@ impl<T: Item> ItemHandle for View<T> {
         self.read(cx).is_dirty(cx)
     }
 
+    fn is_busy(&self, cx: &AppContext) -> bool {
+        self.read(cx).is_busy(cx)
+    }
+
     fn has_conflict(&self, cx: &AppContext) -> bool {
         self.read(cx).has_conflict(cx)
     }
@@ -0,0 +1,226 @@
+use anyhow::{anyhow, Result};
+use fs::Fs;
+use gpui::{AsyncWindowContext, Img, Model, PromptLevel, WeakView};
+use language::{Buffer, Capability};
+use project::ProjectPath;
+use settings::Settings;
+use std::{io::Read, path::Path, sync::Arc};
+
+use crate::{Workspace, WorkspaceSettings};
+
+/// How many bytes from the start of a file are checked for NUL bytes, the same amount git uses.
+const BINARY_SNIFF_LEN: u64 = 8000;
+
+pub(crate) enum PreparedFile {
+    /// The file can be opened as usual.
+    Normal,
+    /// The file is large, and its buffer was opened read-only and as plain text, so that it isn't
+    /// parsed or sent to language servers. The buffer has to be kept alive until its item is
+    /// built.
+    Large(Model<Buffer>),
+    /// The user chose not to open the file.
+    Cancelled,
+}
+
+/// Checks a file that's about to be opened by [`Workspace::open_paths`], so that passing a log
+/// or core dump by accident doesn't hang the editor.
+///
+/// Binary files are refused, unless the image viewer can show them. Files above the size
+/// threshold are opened read-only as plain text, asking the user first if `prompt` is true.
+pub(crate) async fn prepare_to_open_file(
+    workspace: &WeakView<Workspace>,
+    fs: Arc<dyn Fs>,
+    abs_path: &Path,
+    project_path: &ProjectPath,
+    prompt: bool,
+    cx: &mut AsyncWindowContext,
+) -> Result<PreparedFile> {
+    let project = workspace.update(cx, |workspace, _| workspace.project().clone())?;
+    let is_open = project.update(cx, |project, cx| {
+        project.get_open_buffer(project_path, cx).is_some()
+    })?;
+    if is_open {
+        return Ok(PreparedFile::Normal);
+    }
+    let Some(metadata) = fs.metadata(abs_path).await? else {
+        return Ok(PreparedFile::Normal);
+    };
+    let settings = cx.update(|cx| WorkspaceSettings::get_global(cx).large_files)?;
+
+    if settings.refuse_binary && !is_image(abs_path) && looks_binary(fs, abs_path, cx).await? {
+        return Err(anyhow!(
+            "{abs_path:?} looks like a binary file, so it wasn't opened"
+        ));
+    }
+
+    let Some(threshold_mb) = settings.size_threshold_mb else {
+        return Ok(PreparedFile::Normal);
+    };
+    if metadata.len <= threshold_mb.saturating_mul(1024 * 1024) {
+        return Ok(PreparedFile::Normal);
+    }
+    if prompt {
+        let detail = format!(
+            "{} is {} MB. It will be opened read-only, \
+            without syntax highlighting or language servers.",
+            abs_path.display(),
+            metadata.len / (1024 * 1024)
+        );
+        let answer = workspace.update(cx, |_, cx| {
+            cx.prompt(
+                PromptLevel::Warning,
+                "This file is large, open anyway?",
+                Some(&detail),
+                &["Open Read-Only", "Cancel"],
+            )
+        })?;
+        if answer.await? != 0 {
+            return Ok(PreparedFile::Cancelled);
+        }
+    }
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_as_plain_text(project_path.clone(), cx);
+            project.open_buffer(project_path.clone(), cx)
+        })?
+        .await?;
+    buffer.update(cx, |buffer, cx| {
+        buffer.set_capability(Capability::ReadOnly, cx)
+    })?;
+    Ok(PreparedFile::Large(buffer))
+}
+
+fn is_image(abs_path: &Path) -> bool {
+    abs_path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map_or(false, |extension| {
+            extension != "svg" && Img::extensions().contains(&extension)
+        })
+}
+
+async fn looks_binary(fs: Arc<dyn Fs>, abs_path: &Path, cx: &AsyncWindowContext) -> Result<bool> {
+    let abs_path = abs_path.to_path_buf();
+    cx.background_executor()
+        .spawn(async move {
+            let mut head = Vec::new();
+            fs.open_sync(&abs_path)
+                .await?
+                .take(BINARY_SNIFF_LEN)
+                .read_to_end(&mut head)?;
+            Ok(is_binary(&head))
+        })
+        .await
+}
+
+fn is_binary(head: &[u8]) -> bool {
+    // UTF-16 text is full of NUL bytes, but starts with a byte order mark.
+    if head.starts_with(&[0xFF, 0xFE]) || head.starts_with(&[0xFE, 0xFF]) {
+        return false;
+    }
+    head.contains(&0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace_settings::LargeFilesSettings;
+    use fs::FakeFs;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::Project;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_large_file_threshold(cx: &mut TestAppContext) {
+        crate::tests::init_test(cx);
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |settings, cx| {
+                settings.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.large_files = Some(LargeFilesSettings {
+                        size_threshold_mb: Some(1),
+                        refuse_binary: true,
+                    });
+                })
+            });
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file("/root/small.txt", b"small".to_vec()).await;
+        fs.insert_file("/root/large.txt", vec![b'a'; 2 * 1024 * 1024])
+            .await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let prepare = |name: &'static str, prompt: bool, cx: &mut VisualTestContext| {
+            let fs = fs.clone();
+            let project_path = ProjectPath {
+                worktree_id,
+                path: Path::new(name).into(),
+            };
+            workspace.update(cx, |_, cx| {
+                cx.spawn(|workspace, mut cx| async move {
+                    let abs_path = Path::new("/root").join(name);
+                    prepare_to_open_file(
+                        &workspace,
+                        fs,
+                        &abs_path,
+                        &project_path,
+                        prompt,
+                        &mut cx,
+                    )
+                    .await
+                })
+            })
+        };
+
+        // Files below the threshold open as usual, without asking.
+        let task = prepare("small.txt", true, cx);
+        cx.run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        assert!(matches!(task.await.unwrap(), PreparedFile::Normal));
+
+        // Cancelling the prompt doesn't open the file.
+        let task = prepare("large.txt", true, cx);
+        cx.run_until_parked();
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(1);
+        assert!(matches!(task.await.unwrap(), PreparedFile::Cancelled));
+        let large_path = ProjectPath {
+            worktree_id,
+            path: Path::new("large.txt").into(),
+        };
+        project.update(cx, |project, cx| {
+            assert!(project.get_open_buffer(&large_path, cx).is_none());
+        });
+
+        // Confirming it opens the buffer read-only, and as plain text.
+        let task = prepare("large.txt", true, cx);
+        cx.run_until_parked();
+        cx.simulate_prompt_answer(0);
+        let PreparedFile::Large(buffer) = task.await.unwrap() else {
+            panic!("expected the file to be opened as a large file");
+        };
+        buffer.update(cx, |buffer, _| {
+            assert!(buffer.read_only());
+            assert!(buffer.language().is_none());
+        });
+        drop(buffer);
+        cx.run_until_parked();
+
+        // Files restored from the last session don't ask again.
+        let task = prepare("large.txt", false, cx);
+        cx.run_until_parked();
+        assert!(!cx.has_pending_prompt());
+        assert!(matches!(task.await.unwrap(), PreparedFile::Large(_)));
+    }
+
+    #[test]
+    fn test_is_binary() {
+        assert!(!is_binary(b"fn main() {}\n"));
+        assert!(!is_binary(b""));
+        assert!(is_binary(b"\x7fELF\x02\x01\x01\x00\x00"));
+        assert!(!is_binary(b"\xFF\xFEa\x00b\x00"));
+    }
+}
@@ -0,0 +1,368 @@
+//! A portable description of a window's pane layout, which can be written to a file and used
+//! to reopen the same files in the same splits, e.g. on another machine.
+
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use fs::normalize_path;
+use gpui::{AppContext, Axis, Task, View, ViewContext, WindowHandle};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    pane_group::{Member, PaneAxis},
+    AppState, OpenVisible, Pane, PaneGroup, Workspace,
+};
+
+/// The smallest share of its split that a pane can be given, so that every pane stays visible.
+const MIN_SPLIT_SHARE: f32 = 0.05;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WindowLayout {
+    /// The folders that are open in the window. Relative paths are resolved against the
+    /// directory of the layout file.
+    pub roots: Vec<PathBuf>,
+    pub center: LayoutMember,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutMember {
+    Axis {
+        axis: LayoutAxis,
+        flexes: Vec<f32>,
+        members: Vec<LayoutMember>,
+    },
+    Pane {
+        /// The files open in the pane, in tab order. Files within a root are given as the
+        /// root's name followed by the path within it, others relative to the first root.
+        items: Vec<PathBuf>,
+        #[serde(default)]
+        active_item: usize,
+        #[serde(default)]
+        active: bool,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutAxis {
+    Horizontal,
+    Vertical,
+}
+
+impl Workspace {
+    /// Describes the center pane layout of this workspace, with the files open in each pane.
+    /// Items that aren't files, like terminals, are left out.
+    ///
+    /// Paths are kept relative where possible, so that the layout can be shared: the roots are
+    /// relative to `base_dir`, where the layout file is written.
+    pub fn layout(&self, base_dir: &Path, cx: &AppContext) -> Result<WindowLayout> {
+        let project = self.project.read(cx);
+        if !project.is_local() {
+            return Err(anyhow!("only local workspaces can be exported"));
+        }
+        let root_abs_paths = project
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+            .collect::<Vec<_>>();
+        let roots = root_abs_paths
+            .iter()
+            .map(|root| relative_path(root, base_dir))
+            .collect();
+        Ok(WindowLayout {
+            roots,
+            center: self.layout_member(&self.center.root, root_abs_paths.first(), cx),
+        })
+    }
+
+    /// Sets the relative sizes of the panes in the outermost split of the center, e.g. `[2., 1.]`
+    /// makes the first pane twice as large as the second. Sizes are clamped so that every pane
+    /// stays visible.
+    pub fn set_center_split_ratios(
+        &mut self,
+        ratios: &[f32],
+        cx: &mut ViewContext<Self>,
+    ) -> Result<()> {
+        let Member::Axis(axis) = &self.center.root else {
+            return Err(anyhow!("the window isn't split"));
+        };
+        if ratios.len() != axis.members.len() {
+            return Err(anyhow!(
+                "expected {} split ratios, got {}",
+                axis.members.len(),
+                ratios.len()
+            ));
+        }
+        *axis.flexes.lock() = flexes_from_ratios(ratios);
+        cx.notify();
+        Ok(())
+    }
+
+    fn layout_member(
+        &self,
+        member: &Member,
+        first_root: Option<&PathBuf>,
+        cx: &AppContext,
+    ) -> LayoutMember {
+        match member {
+            Member::Axis(axis) => LayoutMember::Axis {
+                axis: match axis.axis {
+                    Axis::Horizontal => LayoutAxis::Horizontal,
+                    Axis::Vertical => LayoutAxis::Vertical,
+                },
+                flexes: axis.flexes.lock().clone(),
+                members: axis
+                    .members
+                    .iter()
+                    .map(|member| self.layout_member(member, first_root, cx))
+                    .collect(),
+            },
+            Member::Pane(pane_view) => {
+                let pane = pane_view.read(cx);
+                let project = self.project.read(cx);
+                let mut items = Vec::new();
+                let mut active_item = 0;
+                for (ix, item) in pane.items().enumerate() {
+                    let Some(project_path) = item.project_path(cx) else {
+                        continue;
+                    };
+                    let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx)
+                    else {
+                        continue;
+                    };
+                    let worktree = worktree.read(cx);
+                    let root = match first_root {
+                        Some(_) if worktree.is_visible() => PathBuf::from(worktree.root_name()),
+                        Some(first_root) => relative_path(&worktree.abs_path(), first_root),
+                        None => worktree.abs_path().to_path_buf(),
+                    };
+                    // Worktrees of single files have an empty path for their only entry.
+                    let path = if project_path.path.as_os_str().is_empty() {
+                        root
+                    } else {
+                        root.join(&project_path.path)
+                    };
+                    if ix == pane.active_item_index() {
+                        active_item = items.len();
+                    }
+                    items.push(path);
+                }
+                LayoutMember::Pane {
+                    items,
+                    active_item,
+                    active: *pane_view == self.active_pane,
+                }
+            }
+        }
+    }
+
+    fn build_layout_member(
+        &mut self,
+        member: &LayoutMember,
+        roots: &[PathBuf],
+        panes_with_items: &mut Vec<(View<Pane>, Vec<PathBuf>, usize)>,
+        active_pane: &mut Option<View<Pane>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Member {
+        match member {
+            LayoutMember::Axis {
+                axis,
+                flexes,
+                members,
+            } => {
+                let mut members = members
+                    .iter()
+                    .map(|member| {
+                        self.build_layout_member(member, roots, panes_with_items, active_pane, cx)
+                    })
+                    .collect::<Vec<_>>();
+                // Pane axes always have at least two members, see `PaneAxis::remove`.
+                if members.len() < 2 {
+                    return members
+                        .pop()
+                        .unwrap_or_else(|| Member::Pane(self.add_pane(cx)));
+                }
+                let axis = match axis {
+                    LayoutAxis::Horizontal => Axis::Horizontal,
+                    LayoutAxis::Vertical => Axis::Vertical,
+                };
+                let flexes = (flexes.len() == members.len()).then(|| flexes_from_ratios(flexes));
+                Member::Axis(PaneAxis::load(axis, members, flexes))
+            }
+            LayoutMember::Pane {
+                items,
+                active_item,
+                active,
+            } => {
+                let pane = self.add_pane(cx);
+                if *active {
+                    *active_pane = Some(pane.clone());
+                }
+                let paths = items
+                    .iter()
+                    .map(|path| resolve_item_path(path, roots))
+                    .collect();
+                panes_with_items.push((pane.clone(), paths, *active_item));
+                Member::Pane(pane)
+            }
+        }
+    }
+}
+
+/// Opens a new window with the roots of the given layout, and the files open in its panes.
+/// Relative roots are resolved against `base_dir`.
+pub fn open_layout(
+    layout: WindowLayout,
+    base_dir: &Path,
+    app_state: Arc<AppState>,
+    cx: &mut AppContext,
+) -> Task<Result<WindowHandle<Workspace>>> {
+    let roots = layout
+        .roots
+        .iter()
+        .map(|root| normalize_path(&base_dir.join(root)))
+        .collect::<Vec<_>>();
+    let open_window = Workspace::new_local(roots.clone(), app_state, None, None, cx);
+    cx.spawn(|mut cx| async move {
+        let (window, _) = open_window.await?;
+        let opened_items = window.update(&mut cx, |workspace, cx| {
+            let mut panes_with_items = Vec::new();
+            let mut active_pane = None;
+            let center = workspace.build_layout_member(
+                &layout.center,
+                &roots,
+                &mut panes_with_items,
+                &mut active_pane,
+                cx,
+            );
+            workspace.remove_panes(workspace.center.root.clone(), cx);
+            workspace.center = PaneGroup::with_root(center);
+            let active_pane = active_pane.unwrap_or_else(|| workspace.center.first_pane());
+            workspace.last_active_center_pane = Some(active_pane.downgrade());
+            workspace.active_pane = active_pane.clone();
+            cx.focus_view(&active_pane);
+            cx.notify();
+
+            panes_with_items
+                .into_iter()
+                .map(|(pane, paths, active_item)| {
+                    let open =
+                        workspace.open_paths(paths, OpenVisible::None, Some(pane.downgrade()), cx);
+                    (pane, open, active_item)
+                })
+                .collect::<Vec<_>>()
+        })?;
+
+        for (pane, open, active_item) in opened_items {
+            open.await;
+            pane.update(&mut cx, |pane, cx| {
+                if active_item < pane.items_len() {
+                    pane.activate_item(active_item, false, false, cx);
+                }
+            })?;
+        }
+        Ok(window)
+    })
+}
+
+/// Turns relative sizes into the flexes of a pane axis, which add up to the number of its
+/// members. Sizes that aren't positive, or that would make a pane too small, are clamped.
+fn flexes_from_ratios(ratios: &[f32]) -> Vec<f32> {
+    let ratios = ratios
+        .iter()
+        .map(|ratio| if ratio.is_finite() { ratio.max(0.) } else { 0. })
+        .collect::<Vec<_>>();
+    let total = ratios.iter().sum::<f32>();
+    if total <= 0. {
+        return vec![1.; ratios.len()];
+    }
+    let shares = ratios
+        .iter()
+        .map(|ratio| (ratio / total).max(MIN_SPLIT_SHARE))
+        .collect::<Vec<_>>();
+    let total = shares.iter().sum::<f32>();
+    let len = shares.len() as f32;
+    shares.iter().map(|share| share / total * len).collect()
+}
+
+/// Expresses `path` relative to `base`, or keeps it absolute if that isn't possible, e.g. for
+/// paths on another Windows drive.
+fn relative_path(path: &Path, base: &Path) -> PathBuf {
+    match pathdiff::diff_paths(path, base) {
+        Some(relative) if relative.as_os_str().is_empty() => PathBuf::from("."),
+        Some(relative) => relative,
+        None => path.to_path_buf(),
+    }
+}
+
+/// Resolves a path from a layout file: either absolute, or the name of a root followed by
+/// the path within that root.
+fn resolve_item_path(path: &Path, roots: &[PathBuf]) -> PathBuf {
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    let mut components = path.components();
+    if let Some(Component::Normal(root_name)) = components.next() {
+        if let Some(root) = roots
+            .iter()
+            .find(|root| root.file_name() == Some(root_name))
+        {
+            return root.join(components.as_path());
+        }
+    }
+    match roots.first() {
+        Some(root) => normalize_path(&root.join(path)),
+        None => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flexes_from_ratios() {
+        assert_eq!(flexes_from_ratios(&[2., 1., 1.]), vec![1.5, 0.75, 0.75]);
+        assert_eq!(flexes_from_ratios(&[0., 0.]), vec![1., 1.]);
+        assert_eq!(
+            flexes_from_ratios(&[f32::NAN, 1.]),
+            flexes_from_ratios(&[0., 1.])
+        );
+
+        let flexes = flexes_from_ratios(&[-1., 1.]);
+        assert!(flexes[0] > 0.);
+        assert!((flexes.iter().sum::<f32>() - 2.).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let base = Path::new("/code/zed");
+        assert_eq!(relative_path(Path::new("/code/zed"), base), Path::new("."));
+        assert_eq!(
+            relative_path(Path::new("/code/docs"), base),
+            Path::new("../docs")
+        );
+        assert_eq!(
+            relative_path(Path::new("/etc/hosts"), base),
+            Path::new("../../etc/hosts")
+        );
+    }
+
+    #[test]
+    fn test_resolve_item_path() {
+        let roots = vec![PathBuf::from("/code/zed"), PathBuf::from("/code/docs")];
+        assert_eq!(
+            resolve_item_path(Path::new("docs/src/intro.md"), &roots),
+            PathBuf::from("/code/docs/src/intro.md")
+        );
+        assert_eq!(
+            resolve_item_path(Path::new("/etc/hosts"), &roots),
+            PathBuf::from("/etc/hosts")
+        );
+        assert_eq!(
+            resolve_item_path(Path::new("src/main.rs"), &roots),
+            PathBuf::from("/code/zed/src/main.rs")
+        );
+    }
+}
@@ -131,6 +131,21 @@ impl Workspace {
             .collect()
     }
 
+    /// The messages of the simple notifications and toasts that are shown.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn notification_messages(&self, cx: &AppContext) -> Vec<SharedString> {
+        self.notifications
+            .iter()
+            .filter_map(|(_, notification)| {
+                let notification = notification
+                    .to_any()
+                    .downcast::<simple_message_notification::MessageNotification>()
+                    .ok()?;
+                Some(notification.read(cx).message().clone())
+            })
+            .collect()
+    }
+
     pub fn show_notification<V: Notification>(
         &mut self,
         id: NotificationId,
@@ -478,6 +493,11 @@ pub mod simple_message_notification {
             }
         }
 
+        #[cfg(any(test, feature = "test-support"))]
+        pub fn message(&self) -> &SharedString {
+            &self.message
+        }
+
         pub fn with_click_message<S>(mut self, message: S) -> Self
         where
             S: Into<SharedString>,
@@ -191,6 +191,8 @@ pub enum Event {
     RemovedItem {
         item_id: EntityId,
     },
+    /// The user closed the last item of the pane.
+    ClosedLastItem,
     Split(SplitDirection),
     JoinAll,
     JoinIntoNext,
@@ -216,6 +218,7 @@ impl fmt::Debug for Event {
                 .field("local", local)
                 .finish(),
             Event::Remove { .. } => f.write_str("Remove"),
+            Event::ClosedLastItem => f.write_str("ClosedLastItem"),
             Event::RemoveItem { idx } => f.debug_struct("RemoveItem").field("idx", idx).finish(),
             Event::RemovedItem { item_id } => f
                 .debug_struct("RemovedItem")
@@ -268,6 +271,8 @@ pub struct Pane {
         Option<Arc<dyn Fn(&mut Pane, &dyn Any, &mut ViewContext<Pane>) -> ControlFlow<(), ()>>>,
     can_split: bool,
     should_display_tab_bar: Rc<dyn Fn(&ViewContext<Pane>) -> bool>,
+    /// Hides the tab bar regardless of `should_display_tab_bar`, for the workspace's zen mode.
+    pub(crate) hide_tab_bar: bool,
     render_tab_bar_buttons:
         Rc<dyn Fn(&mut Pane, &mut ViewContext<Pane>) -> (Option<AnyElement>, Option<AnyElement>)>,
     _subscriptions: Vec<Subscription>,
@@ -388,6 +393,7 @@ impl Pane {
             custom_drop_handle: None,
             can_split: true,
             should_display_tab_bar: Rc::new(|cx| TabBarSettings::get_global(cx).show),
+            hide_tab_bar: false,
             render_tab_bar_buttons: Rc::new(move |pane, cx| {
                 if !pane.has_focus(cx) && !pane.context_menu_focused(cx) {
                     return (None, None);
@@ -1019,6 +1025,7 @@ impl Pane {
                         .next_activation_timestamp
                         .fetch_add(1, Ordering::SeqCst),
                 });
+                newly_active_item.activated(cx);
             }
 
             self.update_toolbar(cx);
@@ -1355,6 +1362,10 @@ impl Pane {
                         .iter()
                         .position(|i| i.item_id() == item.item_id())
                     {
+                        // Emitted first, since removing the last item can remove the pane.
+                        if pane.items.len() == 1 {
+                            cx.emit(Event::ClosedLastItem);
+                        }
                         pane.remove_item(item_ix, false, true, cx);
                     }
                 })
@@ -2562,7 +2573,7 @@ impl Render for Pane {
         }
 
         let should_display_tab_bar = self.should_display_tab_bar.clone();
-        let display_tab_bar = should_display_tab_bar(cx);
+        let display_tab_bar = !self.hide_tab_bar && should_display_tab_bar(cx);
 
         v_flex()
             .key_context(key_context)
@@ -2924,6 +2935,16 @@ impl NavHistory {
             .retain(|entry| entry.item.id() != item_id);
     }
 
+    /// When the most recently closed item was closed, comparable between the panes of a
+    /// workspace.
+    pub fn last_closed_timestamp(&self) -> Option<usize> {
+        self.0
+            .lock()
+            .closed_stack
+            .back()
+            .map(|entry| entry.timestamp)
+    }
+
     pub fn path_for_item(&self, item_id: EntityId) -> Option<(ProjectPath, Option<PathBuf>)> {
         self.0.lock().paths_by_item.get(&item_id).cloned()
     }
@@ -3026,6 +3047,7 @@ mod tests {
     use gpui::{TestAppContext, VisualTestContext};
     use project::FakeFs;
     use settings::SettingsStore;
+    use std::cell::Cell;
     use theme::LoadThemes;
 
     #[gpui::test]
@@ -3350,6 +3372,45 @@ mod tests {
         assert_item_labels(&pane, ["A*"], cx);
     }
 
+    #[gpui::test]
+    async fn test_closed_last_item_event(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, None, cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+
+        let closed_last_item_count = Rc::new(Cell::new(0));
+        cx.update(|cx| {
+            let closed_last_item_count = closed_last_item_count.clone();
+            cx.subscribe(&pane, move |_, event, _| {
+                if let Event::ClosedLastItem = event {
+                    closed_last_item_count.set(closed_last_item_count.get() + 1);
+                }
+            })
+            .detach();
+        });
+
+        add_labeled_item(&pane, "A", false, cx);
+        add_labeled_item(&pane, "B", false, cx);
+        pane.update(cx, |pane, cx| {
+            pane.close_active_item(&CloseActiveItem { save_intent: None }, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap();
+        assert_eq!(closed_last_item_count.get(), 0);
+
+        pane.update(cx, |pane, cx| {
+            pane.close_active_item(&CloseActiveItem { save_intent: None }, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap();
+        assert_eq!(closed_last_item_count.get(), 1);
+    }
+
     #[gpui::test]
     async fn test_close_inactive_items(cx: &mut TestAppContext) {
         init_test(cx);
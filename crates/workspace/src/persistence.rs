@@ -1205,7 +1205,7 @@ impl WorkspaceDb {
 mod tests {
     use super::*;
     use crate::persistence::model::SerializedWorkspace;
-    use crate::persistence::model::{SerializedItem, SerializedPane, SerializedPaneGroup};
+    use crate::persistence::model::{ItemId, SerializedItem, SerializedPane, SerializedPaneGroup};
     use db::open_test_db;
     use gpui::{self};
 
@@ -1902,4 +1902,49 @@ mod tests {
 
         assert_eq!(workspace.center_group, new_workspace.center_group);
     }
+
+    #[test]
+    fn test_items_to_load() {
+        let center_group = group(
+            Axis::Horizontal,
+            vec![
+                SerializedPaneGroup::Pane(SerializedPane::new(
+                    vec![
+                        SerializedItem::new("Terminal", 1, false, false),
+                        SerializedItem::new("Terminal", 2, true, false),
+                        SerializedItem::new("Terminal", 3, false, false),
+                    ],
+                    false,
+                    0,
+                )),
+                SerializedPaneGroup::Pane(SerializedPane::new(
+                    vec![
+                        SerializedItem::new("Editor", 4, false, false),
+                        SerializedItem::new("Editor", 5, false, false),
+                        SerializedItem::new("Editor", 6, true, false),
+                    ],
+                    true,
+                    0,
+                )),
+            ],
+        );
+
+        let item_ids = |limit| {
+            let mut ids = center_group
+                .items_to_load(limit)
+                .into_iter()
+                .map(|(_, item_id)| item_id)
+                .collect::<Vec<_>>();
+            ids.sort();
+            ids
+        };
+        assert_eq!(item_ids(0), Vec::<ItemId>::new());
+        // The active pane's active item comes first, then the other pane's.
+        assert_eq!(item_ids(1), vec![6]);
+        assert_eq!(item_ids(2), vec![2, 6]);
+        // Then the items next to the active ones.
+        assert_eq!(item_ids(3), vec![2, 5, 6]);
+        assert_eq!(item_ids(5), vec![1, 2, 3, 5, 6]);
+        assert_eq!(item_ids(10), vec![1, 2, 3, 4, 5, 6]);
+    }
 }
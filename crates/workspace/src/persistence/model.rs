@@ -1,15 +1,17 @@
 use super::{SerializedAxis, SerializedWindowBounds};
 use crate::{
-    item::ItemHandle, Member, Pane, PaneAxis, SerializableItemRegistry, Workspace, WorkspaceId,
+    item::ItemHandle, unloaded_item::UnloadedItem, Member, Pane, PaneAxis,
+    SerializableItemRegistry, Workspace, WorkspaceId,
 };
 use anyhow::{Context, Result};
 use async_recursion::async_recursion;
 use client::DevServerProjectId;
+use collections::HashSet;
 use db::sqlez::{
     bindable::{Bind, Column, StaticColumnCount},
     statement::Statement,
 };
-use gpui::{AsyncWindowContext, Model, View, WeakView};
+use gpui::{AsyncWindowContext, Model, View, VisualContext as _, WeakView};
 use project::Project;
 use remote::ssh_session::SshProjectId;
 use serde::{Deserialize, Serialize};
@@ -375,12 +377,64 @@ impl Default for SerializedPaneGroup {
 }
 
 impl SerializedPaneGroup {
+    /// Picks at most `limit` items to load when restoring this group: the active item of each
+    /// pane, starting with the active pane, and then the items closest to them.
+    pub(crate) fn items_to_load(&self, limit: usize) -> HashSet<(Arc<str>, ItemId)> {
+        fn collect_panes<'a>(group: &'a SerializedPaneGroup, panes: &mut Vec<&'a SerializedPane>) {
+            match group {
+                SerializedPaneGroup::Group { children, .. } => {
+                    for child in children {
+                        collect_panes(child, panes);
+                    }
+                }
+                SerializedPaneGroup::Pane(pane) => panes.push(pane),
+            }
+        }
+
+        let mut panes = Vec::new();
+        collect_panes(self, &mut panes);
+        panes.sort_by_key(|pane| !pane.active);
+
+        let active_indices = panes
+            .iter()
+            .map(|pane| {
+                pane.children
+                    .iter()
+                    .position(|item| item.active)
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+        let max_len = panes
+            .iter()
+            .map(|pane| pane.children.len())
+            .max()
+            .unwrap_or(0);
+
+        let mut items = HashSet::default();
+        for distance in 0..max_len {
+            for (pane, &active_ix) in panes.iter().zip(&active_indices) {
+                let before = active_ix.checked_sub(distance);
+                let after = (distance > 0).then_some(active_ix + distance);
+                for ix in before.into_iter().chain(after) {
+                    if items.len() == limit {
+                        return items;
+                    }
+                    if let Some(item) = pane.children.get(ix) {
+                        items.insert((item.kind.clone(), item.item_id));
+                    }
+                }
+            }
+        }
+        items
+    }
+
     #[async_recursion(?Send)]
     pub(crate) async fn deserialize(
         self,
         project: &Model<Project>,
         workspace_id: WorkspaceId,
         workspace: WeakView<Workspace>,
+        items_to_load: Option<&HashSet<(Arc<str>, ItemId)>>,
         cx: &mut AsyncWindowContext,
     ) -> Option<(Member, Option<View<Pane>>, Vec<Option<Box<dyn ItemHandle>>>)> {
         match self {
@@ -394,7 +448,7 @@ impl SerializedPaneGroup {
                 let mut items = Vec::new();
                 for child in children {
                     if let Some((new_member, active_pane, new_items)) = child
-                        .deserialize(project, workspace_id, workspace.clone(), cx)
+                        .deserialize(project, workspace_id, workspace.clone(), items_to_load, cx)
                         .await
                     {
                         members.push(new_member);
@@ -423,7 +477,14 @@ impl SerializedPaneGroup {
                     .log_err()?;
                 let active = serialized_pane.active;
                 let new_items = serialized_pane
-                    .deserialize_to(project, &pane, workspace_id, workspace.clone(), cx)
+                    .deserialize_to(
+                        project,
+                        &pane,
+                        workspace_id,
+                        workspace.clone(),
+                        items_to_load,
+                        cx,
+                    )
                     .await
                     .log_err()?;
 
@@ -470,6 +531,7 @@ impl SerializedPane {
         pane: &WeakView<Pane>,
         workspace_id: WorkspaceId,
         workspace: WeakView<Workspace>,
+        items_to_load: Option<&HashSet<(Arc<str>, ItemId)>>,
         cx: &mut AsyncWindowContext,
     ) -> Result<Vec<Option<Box<dyn ItemHandle>>>> {
         let mut item_tasks = Vec::new();
@@ -477,15 +539,59 @@ impl SerializedPane {
         let mut preview_item_index = None;
         for (index, item) in self.children.iter().enumerate() {
             let project = project.clone();
+            let load = items_to_load.map_or(true, |items| {
+                items.contains(&(item.kind.clone(), item.item_id))
+            });
             item_tasks.push(pane.update(cx, |_, cx| {
-                SerializableItemRegistry::deserialize(
-                    &item.kind,
-                    project,
-                    workspace.clone(),
-                    workspace_id,
-                    item.item_id,
-                    cx,
-                )
+                if load {
+                    SerializableItemRegistry::deserialize(
+                        &item.kind,
+                        project,
+                        workspace.clone(),
+                        workspace_id,
+                        item.item_id,
+                        cx,
+                    )
+                } else {
+                    let placeholder = cx.new_view(|cx| {
+                        UnloadedItem::new(
+                            item.kind.clone(),
+                            item.item_id,
+                            workspace_id,
+                            project.clone(),
+                            workspace.clone(),
+                            cx,
+                        )
+                    });
+                    // The item's old id may be given to a new item in this session, so the
+                    // placeholder keeps the item's state under its own id instead.
+                    let copy = SerializableItemRegistry::copy_serialized(
+                        &item.kind,
+                        workspace_id,
+                        item.item_id,
+                        placeholder.item_id().as_u64(),
+                        cx,
+                    );
+                    let kind = item.kind.clone();
+                    let item_id = item.item_id;
+                    let workspace = workspace.clone();
+                    cx.spawn(|pane, mut cx| async move {
+                        if copy.await.is_ok() {
+                            return Ok(Box::new(placeholder) as Box<dyn ItemHandle>);
+                        }
+                        pane.update(&mut cx, |_, cx| {
+                            SerializableItemRegistry::deserialize(
+                                &kind,
+                                project,
+                                workspace,
+                                workspace_id,
+                                item_id,
+                                cx,
+                            )
+                        })?
+                        .await
+                    })
+                }
             })?);
             if item.active {
                 active_item_index = Some(index);
@@ -0,0 +1,94 @@
+use anyhow::Result;
+use futures::{future::LocalBoxFuture, Future, FutureExt as _};
+use gpui::{AppContext, AsyncAppContext, BorrowAppContext, Global};
+use std::time::Duration;
+
+/// How long [`run_quit_hooks`] waits for the registered hooks before giving up on them.
+pub const QUIT_HOOK_TIMEOUT: Duration = Duration::from_secs(2);
+
+type QuitHook = Box<dyn FnMut(&mut AppContext) -> LocalBoxFuture<'static, bool>>;
+
+#[derive(Default)]
+struct QuitHooks(Vec<QuitHook>);
+
+impl Global for QuitHooks {}
+
+/// Registers a callback that runs once per quit, after every workspace has
+/// agreed to close. Resolving to `false` vetoes the quit, the same way a
+/// cancelled save prompt does in `Workspace::prepare_to_close`.
+///
+/// Hooks must not register further hooks while they are running.
+pub fn on_quit<Fut>(cx: &mut AppContext, mut callback: impl FnMut(&mut AppContext) -> Fut + 'static)
+where
+    Fut: Future<Output = bool> + 'static,
+{
+    cx.default_global::<QuitHooks>()
+        .0
+        .push(Box::new(move |cx| callback(cx).boxed_local()));
+}
+
+/// Runs every registered quit hook concurrently and returns whether the quit
+/// should proceed. Hooks that haven't finished after [`QUIT_HOOK_TIMEOUT`] are
+/// abandoned, so a stuck subsystem can't keep the app open.
+pub async fn run_quit_hooks(cx: &mut AsyncAppContext) -> Result<bool> {
+    let hooks = cx.update(|cx| {
+        if !cx.has_global::<QuitHooks>() {
+            return Vec::new();
+        }
+        cx.update_global::<QuitHooks, _>(|hooks, cx| {
+            hooks.0.iter_mut().map(|hook| hook(cx)).collect::<Vec<_>>()
+        })
+    })?;
+    if hooks.is_empty() {
+        return Ok(true);
+    }
+
+    let results = futures::future::join_all(hooks).fuse();
+    let timeout = cx.background_executor().timer(QUIT_HOOK_TIMEOUT).fuse();
+    futures::pin_mut!(results, timeout);
+    futures::select_biased! {
+        results = results => Ok(results.into_iter().all(|should_quit| should_quit)),
+        _ = timeout => {
+            log::warn!("quit hooks did not finish within {QUIT_HOOK_TIMEOUT:?}, quitting anyway");
+            Ok(true)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use std::{cell::Cell, rc::Rc};
+
+    #[gpui::test]
+    async fn test_quit_hooks_run_once_per_quit(cx: &mut TestAppContext) {
+        let calls = Rc::new(Cell::new(0));
+        cx.update(|cx| {
+            let calls = calls.clone();
+            on_quit(cx, move |_| {
+                calls.set(calls.get() + 1);
+                async { true }
+            });
+        });
+
+        let mut async_cx = cx.to_async();
+        assert!(run_quit_hooks(&mut async_cx).await.unwrap());
+        assert_eq!(calls.get(), 1);
+
+        assert!(run_quit_hooks(&mut async_cx).await.unwrap());
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[gpui::test]
+    async fn test_quit_hook_veto(cx: &mut TestAppContext) {
+        let mut async_cx = cx.to_async();
+        assert!(run_quit_hooks(&mut async_cx).await.unwrap());
+
+        cx.update(|cx| {
+            on_quit(cx, |_| async { true });
+            on_quit(cx, |_| async { false });
+        });
+        assert!(!run_quit_hooks(&mut async_cx).await.unwrap());
+    }
+}
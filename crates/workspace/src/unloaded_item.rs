@@ -0,0 +1,190 @@
+//! Placeholder tabs for restored items beyond the `max_restored_tabs` limit. Each one stands in
+//! for a serialized item, and replaces itself with that item the first time it's activated.
+
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use gpui::{
+    AppContext, EventEmitter, FocusHandle, FocusableView, Model, Render, SharedString, Task,
+    WeakView, WindowContext,
+};
+use project::Project;
+use ui::{prelude::*, Icon, IconName};
+use util::ResultExt as _;
+
+use crate::{
+    item::Item, persistence::model::ItemId, SerializableItemRegistry, Workspace, WorkspaceId,
+};
+
+pub struct UnloadedItem {
+    kind: Arc<str>,
+    serialized_item_id: ItemId,
+    workspace_id: WorkspaceId,
+    title: SharedString,
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    focus_handle: FocusHandle,
+    load_task: Option<Task<()>>,
+    load_error: Option<SharedString>,
+}
+
+impl UnloadedItem {
+    /// Creates a placeholder for the item restored from `restored_item_id`. The item's state is
+    /// expected to be copied to the placeholder's own id, under which it's loaded and serialized.
+    pub(crate) fn new(
+        kind: Arc<str>,
+        restored_item_id: ItemId,
+        workspace_id: WorkspaceId,
+        project: Model<Project>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let title = SerializableItemRegistry::serialized_tab_title(
+            &kind,
+            workspace_id,
+            restored_item_id,
+            cx,
+        )
+        .unwrap_or_else(|| kind.to_string().into());
+        Self {
+            kind,
+            serialized_item_id: cx.entity_id().as_u64(),
+            workspace_id,
+            title,
+            project,
+            workspace,
+            focus_handle: cx.focus_handle(),
+            load_task: None,
+            load_error: None,
+        }
+    }
+
+    /// The kind and id the item's state is stored under until the item is loaded.
+    pub(crate) fn serialized_item(&self) -> (Arc<str>, ItemId) {
+        (self.kind.clone(), self.serialized_item_id)
+    }
+
+    fn is_active(&self, cx: &mut ViewContext<Self>) -> bool {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return false;
+        };
+        let this = cx.view().clone();
+        let Some(pane) = workspace.read(cx).pane_for(&this) else {
+            return false;
+        };
+        let active_item_id = pane.read(cx).active_item().map(|item| item.item_id());
+        active_item_id == Some(cx.entity_id())
+    }
+
+    fn load(&mut self, cx: &mut ViewContext<Self>) {
+        let kind = self.kind.clone();
+        let item_id = self.serialized_item_id;
+        let workspace_id = self.workspace_id;
+        let project = self.project.clone();
+        let workspace = self.workspace.clone();
+        self.load_task = Some(cx.spawn(|this, mut cx| async move {
+            let result = async {
+                let this_view = this
+                    .upgrade()
+                    .ok_or_else(|| anyhow!("placeholder was closed"))?;
+                let (pane, load) = workspace.update(&mut cx, |workspace, cx| {
+                    let pane = workspace
+                        .pane_for(&this_view)
+                        .ok_or_else(|| anyhow!("placeholder isn't in a pane"))?;
+                    let load = pane.update(cx, |_, cx| {
+                        SerializableItemRegistry::deserialize(
+                            &kind,
+                            project,
+                            workspace.weak_handle(),
+                            workspace_id,
+                            item_id,
+                            cx,
+                        )
+                    });
+                    anyhow::Ok((pane, load))
+                })??;
+                let item = load.await?;
+
+                pane.update(&mut cx, |pane, cx| {
+                    let Some(index) = pane.index_for_item(&this_view) else {
+                        return;
+                    };
+                    let focus = this_view.read(cx).focus_handle.contains_focused(cx);
+                    pane.add_item(item, false, focus, Some(index), cx);
+                    if let Some(index) = pane.index_for_item(&this_view) {
+                        pane.remove_item(index, false, false, cx);
+                    }
+                })?;
+                anyhow::Ok(())
+            }
+            .await;
+
+            if let Err(error) = result {
+                log::error!("failed to load restored {kind} item: {error:#}");
+                this.update(&mut cx, |this, cx| {
+                    this.load_error = Some(format!("{error:#}").into());
+                    cx.notify();
+                })
+                .log_err();
+            }
+        }));
+    }
+}
+
+impl EventEmitter<()> for UnloadedItem {}
+
+impl FocusableView for UnloadedItem {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for UnloadedItem {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let message = match &self.load_error {
+            Some(error) => format!("Failed to load {}: {error}", self.title).into(),
+            None => SharedString::from("Loading…"),
+        };
+        div()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .flex()
+            .items_center()
+            .justify_center()
+            .bg(cx.theme().colors().editor_background)
+            .child(Label::new(message).color(Color::Muted))
+    }
+}
+
+impl Item for UnloadedItem {
+    type Event = ();
+
+    fn tab_content_text(&self, _: &WindowContext) -> Option<SharedString> {
+        Some(self.title.clone())
+    }
+
+    fn tab_icon(&self, _: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::FileGeneric))
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some(format!("{} (not loaded yet)", self.title).into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn activated(&mut self, cx: &mut ViewContext<Self>) {
+        // Adding items to a pane activates each of them in turn, so only load the item if this
+        // placeholder is still the active one once the pane is done with that.
+        cx.spawn(|this, mut cx| async move {
+            this.update(&mut cx, |this, cx| {
+                if this.load_task.is_none() && this.is_active(cx) {
+                    this.load(cx);
+                }
+            })
+        })
+        .detach();
+    }
+}
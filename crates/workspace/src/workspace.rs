@@ -1,17 +1,23 @@
+mod always_restore;
 pub mod dock;
 pub mod item;
+mod large_files;
+pub mod layout;
 mod modal_layer;
 pub mod notifications;
 pub mod pane;
 pub mod pane_group;
 mod persistence;
+mod quit_hooks;
 pub mod searchable;
 pub mod shared_screen;
 mod status_bar;
 pub mod tasks;
 mod toolbar;
+mod unloaded_item;
 mod workspace_settings;
 
+pub use always_restore::{always_restored_workspaces, defer_restored_workspaces};
 use anyhow::{anyhow, Context as _, Result};
 use call::{call_settings::CallSettings, ActiveCall};
 use client::{
@@ -43,10 +49,13 @@ pub use item::{
     ProjectItem, SerializableItem, SerializableItemHandle, WeakItemHandle,
 };
 use itertools::Itertools;
-use language::{LanguageRegistry, Rope};
+use language::{Capability, LanguageRegistry, Rope};
+use large_files::PreparedFile;
 pub use modal_layer::*;
 use node_runtime::NodeRuntime;
-use notifications::{simple_message_notification::MessageNotification, NotificationHandle};
+use notifications::{
+    simple_message_notification::MessageNotification, DetachAndPromptErr, NotificationHandle,
+};
 pub use pane::*;
 pub use pane_group::*;
 pub use persistence::{
@@ -61,6 +70,7 @@ use postage::stream::Stream;
 use project::{
     DirectoryLister, Project, ProjectEntryId, ProjectPath, ResolvedPath, Worktree, WorktreeId,
 };
+pub use quit_hooks::{on_quit, run_quit_hooks, QUIT_HOOK_TIMEOUT};
 use remote::{SshConnectionOptions, SshSession};
 use serde::Deserialize;
 use session::AppSession;
@@ -83,21 +93,23 @@ use std::{
     path::{Path, PathBuf},
     rc::Rc,
     sync::{atomic::AtomicUsize, Arc, LazyLock, Weak},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use task::SpawnInTerminal;
 use theme::{ActiveTheme, SystemAppearance, ThemeSettings};
 pub use toolbar::{Toolbar, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
 pub use ui;
 use ui::{
-    div, h_flex, px, BorrowAppContext, Context as _, Div, FluentBuilder, InteractiveElement as _,
-    IntoElement, ParentElement as _, Pixels, SharedString, Styled as _, ViewContext,
-    VisualContext as _, WindowContext,
+    div, h_flex, px, BorrowAppContext, Color, Context as _, Div, FluentBuilder,
+    InteractiveElement as _, IntoElement, Label, LabelCommon as _, ParentElement as _, Pixels,
+    SharedString, Styled as _, ViewContext, VisualContext as _, WindowContext,
 };
+use unloaded_item::UnloadedItem;
 use util::{maybe, ResultExt, TryFutureExt};
 use uuid::Uuid;
 pub use workspace_settings::{
-    AutosaveSetting, RestoreOnStartupBehavior, TabBarSettings, WorkspaceSettings,
+    AutosaveSetting, LastTabClosedBehavior, QuitWithRunningTasks, RestoreOnStartupBehavior,
+    StartupView, TabBarSettings, WorkspaceSettings,
 };
 
 use crate::notifications::NotificationId;
@@ -106,6 +118,9 @@ use crate::persistence::{
     SerializedAxis,
 };
 
+const PERIODIC_SERIALIZATION_INTERVAL: Duration = Duration::from_secs(30);
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
 static ZED_WINDOW_SIZE: LazyLock<Option<Size<Pixels>>> = LazyLock::new(|| {
     env::var("ZED_WINDOW_SIZE")
         .ok()
@@ -136,6 +151,7 @@ actions!(
         CloseWindow,
         CopyPath,
         CopyRelativePath,
+        DuplicateWindow,
         Feedback,
         FollowNextCollaborator,
         NewCenterTerminal,
@@ -150,10 +166,12 @@ actions!(
         ReloadActiveItem,
         SaveAs,
         SaveWithoutFormat,
+        ToggleAlwaysRestore,
         ToggleBottomDock,
         ToggleCenteredLayout,
         ToggleLeftDock,
         ToggleRightDock,
+        ToggleZenMode,
         ToggleZoom,
         Unfollow,
         Welcome,
@@ -206,6 +224,14 @@ pub struct Reload {
     pub binary_path: Option<PathBuf>,
 }
 
+#[derive(Clone, PartialEq, Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadWindow {
+    /// Reopen only the worktree roots, without the tabs that were open.
+    #[serde(default)]
+    pub skip_items: bool,
+}
+
 action_as!(project_symbols, ToggleProjectSymbols as Toggle);
 
 #[derive(Default, PartialEq, Eq, Clone, serde::Deserialize)]
@@ -225,6 +251,7 @@ impl_actions!(
         CloseInactiveTabsAndPanes,
         OpenTerminal,
         Reload,
+        ReloadWindow,
         Save,
         SaveAll,
         SwapPaneInDirection,
@@ -467,6 +494,8 @@ struct SerializableItemDescriptor {
         &mut ViewContext<Pane>,
     ) -> Task<Result<Box<dyn ItemHandle>>>,
     cleanup: fn(WorkspaceId, Vec<ItemId>, &mut WindowContext) -> Task<Result<()>>,
+    serialized_tab_title: fn(WorkspaceId, ItemId, &AppContext) -> Option<SharedString>,
+    copy_serialized: fn(WorkspaceId, ItemId, ItemId, &mut WindowContext) -> Task<Result<()>>,
     view_to_serializable_item: fn(AnyView) -> Box<dyn SerializableItemHandle>,
 }
 
@@ -513,6 +542,33 @@ impl SerializableItemRegistry {
         (descriptor.cleanup)(workspace_id, loaded_items, cx)
     }
 
+    fn serialized_tab_title(
+        item_kind: &str,
+        workspace_id: WorkspaceId,
+        item_id: ItemId,
+        cx: &AppContext,
+    ) -> Option<SharedString> {
+        let descriptor = Self::descriptor(item_kind, cx)?;
+        (descriptor.serialized_tab_title)(workspace_id, item_id, cx)
+    }
+
+    fn copy_serialized(
+        item_kind: &str,
+        workspace_id: WorkspaceId,
+        from_item_id: ItemId,
+        to_item_id: ItemId,
+        cx: &mut WindowContext,
+    ) -> Task<Result<()>> {
+        let Some(descriptor) = Self::descriptor(item_kind, cx) else {
+            return Task::ready(Err(anyhow!(
+                "cannot copy {}, descriptor not found",
+                item_kind
+            )));
+        };
+
+        (descriptor.copy_serialized)(workspace_id, from_item_id, to_item_id, cx)
+    }
+
     fn view_to_serializable_item_handle(
         view: AnyView,
         cx: &AppContext,
@@ -539,6 +595,12 @@ pub fn register_serializable_item<I: SerializableItem>(cx: &mut AppContext) {
                 .spawn(async { Ok(Box::new(task.await?) as Box<_>) })
         },
         cleanup: |workspace_id, loaded_items, cx| I::cleanup(workspace_id, loaded_items, cx),
+        serialized_tab_title: |workspace_id, item_id, cx| {
+            I::serialized_tab_title(workspace_id, item_id, cx)
+        },
+        copy_serialized: |workspace_id, from_item_id, to_item_id, cx| {
+            I::copy_serialized(workspace_id, from_item_id, to_item_id, cx)
+        },
         view_to_serializable_item: |view| Box::new(view.downcast::<I>().unwrap()),
     };
     registry
@@ -753,9 +815,25 @@ pub struct Workspace {
     _apply_leader_updates: Task<Result<()>>,
     _observe_current_user: Task<Result<()>>,
     _schedule_serialize: Option<Task<()>>,
+    _periodic_serialize: Task<()>,
+    inactive_window_autosave: Option<Task<Result<()>>>,
+    last_user_activity: Instant,
+    /// Restarts the countdown to hiding the contents while a task is running, without
+    /// pretending that the user was active.
+    hide_contents_countdown_start: Instant,
+    contents_hidden: bool,
+    /// Whether the window is waiting for the user to confirm that it can close.
+    closing: bool,
+    hidden_contents_focus_handle: FocusHandle,
+    focus_before_hiding_contents: Option<FocusHandle>,
+    /// The `last_user_activity` that idle housekeeping last ran after, so that it runs once
+    /// per idle period.
+    housekeeping_ran_after_activity: Option<Instant>,
+    _check_idle: Task<()>,
     pane_history_timestamp: Arc<AtomicUsize>,
     bounds: Bounds<Pixels>,
     centered_layout: bool,
+    zen_mode: bool,
     bounds_save_task_queued: Option<Task<()>>,
     on_prompt_for_new_path: Option<PromptForNewPath>,
     on_prompt_for_open_path: Option<PromptForOpenPath>,
@@ -977,6 +1055,12 @@ impl Workspace {
 
         let subscriptions = vec![
             cx.observe_window_activation(Self::on_window_activation_changed),
+            cx.observe_keystrokes(move |this, _, cx| {
+                // Keystroke observers are notified about keystrokes in every window.
+                if cx.window_handle() == window_handle.into() {
+                    this.register_user_activity(cx);
+                }
+            }),
             cx.observe_window_bounds(move |this, cx| {
                 if this.bounds_save_task_queued.is_some() {
                     return;
@@ -1036,6 +1120,35 @@ impl Workspace {
         cx.defer(|this, cx| {
             this.update_window_title(cx);
         });
+        // Most changes serialize the workspace as they happen. This catches the rest, so that
+        // the restored state is close to current even after a crash.
+        let _periodic_serialize = cx.spawn(|this, mut cx| async move {
+            loop {
+                cx.background_executor()
+                    .timer(PERIODIC_SERIALIZATION_INTERVAL)
+                    .await;
+                if this
+                    .update(&mut cx, |this, cx| this.serialize_workspace(cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        let _check_idle = cx.spawn(|this, mut cx| async move {
+            loop {
+                cx.background_executor().timer(IDLE_CHECK_INTERVAL).await;
+                if this
+                    .update(&mut cx, |this, cx| {
+                        this.hide_contents_if_idle(cx);
+                        this.release_resources_if_idle(cx);
+                    })
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
         Workspace {
             weak_self: weak_handle.clone(),
             zoomed: None,
@@ -1064,6 +1177,16 @@ impl Workspace {
             _observe_current_user,
             _apply_leader_updates,
             _schedule_serialize: None,
+            _periodic_serialize,
+            inactive_window_autosave: None,
+            last_user_activity: Instant::now(),
+            hide_contents_countdown_start: Instant::now(),
+            contents_hidden: false,
+            closing: false,
+            hidden_contents_focus_handle: cx.focus_handle(),
+            focus_before_hiding_contents: None,
+            housekeeping_ran_after_activity: None,
+            _check_idle,
             leader_updates_tx,
             _subscriptions: subscriptions,
             pane_history_timestamp,
@@ -1071,6 +1194,7 @@ impl Workspace {
             // This data will be incorrect, but it will be overwritten by the time it needs to be used.
             bounds: Default::default(),
             centered_layout: false,
+            zen_mode: false,
             bounds_save_task_queued: None,
             on_prompt_for_new_path: None,
             on_prompt_for_open_path: None,
@@ -1190,7 +1314,10 @@ impl Workspace {
 
                 // Use the serialized workspace to construct the new window
                 let mut options = cx.update(|cx| (app_state.build_window_options)(display, cx))?;
-                options.window_bounds = window_bounds;
+                // If the display the workspace was last shown on has been disconnected,
+                // its bounds are meaningless, so let the window open on the primary display.
+                let display_disconnected = display.is_some() && options.display_id.is_none();
+                options.window_bounds = window_bounds.filter(|_| !display_disconnected);
                 let centered_layout = serialized_workspace
                     .as_ref()
                     .map(|w| w.centered_layout)
@@ -1228,6 +1355,33 @@ impl Workspace {
         self.weak_self.clone()
     }
 
+    /// Resizes the dock at the given position, clamping the size so that the center stays
+    /// visible. Returns false if no panels have been added to the dock yet, as panels are loaded
+    /// asynchronously after the workspace opens.
+    pub fn resize_dock(
+        &mut self,
+        position: DockPosition,
+        size: Pixels,
+        cx: &mut ViewContext<Self>,
+    ) -> bool {
+        const MAX_DOCK_SHARE: f32 = 0.8;
+
+        let viewport_size = cx.viewport_size();
+        let (dock, available) = match position {
+            DockPosition::Left => (self.left_dock.clone(), viewport_size.width),
+            DockPosition::Right => (self.right_dock.clone(), viewport_size.width),
+            DockPosition::Bottom => (self.bottom_dock.clone(), viewport_size.height),
+        };
+        let size = size.min(available * MAX_DOCK_SHARE);
+        dock.update(cx, |dock, cx| {
+            if dock.panels_len() == 0 {
+                return false;
+            }
+            dock.resize_all_panels(Some(size), cx);
+            true
+        })
+    }
+
     pub fn left_dock(&self) -> &View<Dock> {
         &self.left_dock
     }
@@ -1371,7 +1525,7 @@ impl Workspace {
                         break pane
                             .nav_history()
                             .path_for_item(entry.item.id())
-                            .map(|(project_path, _)| (project_path, entry));
+                            .map(|(project_path, abs_path)| (project_path, abs_path, entry));
                     }
                 }
             })
@@ -1379,49 +1533,86 @@ impl Workspace {
             None
         };
 
-        if let Some((project_path, entry)) = to_load {
-            // If the item was no longer present, then load it again from its previous path.
-            let task = self.load_path(project_path, cx);
-            cx.spawn(|workspace, mut cx| async move {
-                let task = task.await;
-                let mut navigated = false;
-                if let Some((project_entry_id, build_item)) = task.log_err() {
-                    let prev_active_item_id = pane.update(&mut cx, |pane, _| {
-                        pane.nav_history_mut().set_mode(mode);
-                        pane.active_item().map(|p| p.item_id())
-                    })?;
-
-                    pane.update(&mut cx, |pane, cx| {
-                        let item = pane.open_item(
-                            project_entry_id,
-                            true,
-                            entry.is_preview,
-                            cx,
-                            build_item,
-                        );
-                        navigated |= Some(item.item_id()) != prev_active_item_id;
-                        pane.nav_history_mut().set_mode(NavigationMode::Normal);
-                        if let Some(data) = entry.data {
-                            navigated |= item.navigate(data, cx);
-                        }
-                    })?;
-                }
-
-                if !navigated {
-                    workspace
-                        .update(&mut cx, |workspace, cx| {
-                            Self::navigate_history(workspace, pane, mode, cx)
-                        })?
-                        .await?;
+        if let Some((project_path, abs_path, entry)) = to_load {
+            // Loading a file that doesn't exist anymore would open an empty buffer instead, so
+            // skip closed items whose file was deleted, and reopen the next most recently
+            // closed item of any pane.
+            if matches!(mode, NavigationMode::ReopeningClosedItem) {
+                let abs_path =
+                    abs_path.or_else(|| self.project.read(cx).absolute_path(&project_path, cx));
+                if let Some(abs_path) = abs_path {
+                    let fs = self.app_state.fs.clone();
+                    return cx.spawn(|workspace, mut cx| async move {
+                        let deleted = !fs.is_file(&abs_path).await;
+                        workspace
+                            .update(&mut cx, |workspace, cx| {
+                                if deleted {
+                                    workspace.show_skipped_closed_item(&project_path, cx);
+                                    workspace.reopen_closed_item(cx)
+                                } else {
+                                    workspace.load_history_entry(
+                                        pane,
+                                        project_path,
+                                        entry,
+                                        mode,
+                                        cx,
+                                    )
+                                }
+                            })?
+                            .await
+                    });
                 }
+            }
 
-                Ok(())
-            })
+            self.load_history_entry(pane, project_path, entry, mode, cx)
         } else {
             Task::ready(Ok(()))
         }
     }
 
+    /// Opens the item of a navigation history entry that is no longer present in its pane.
+    fn load_history_entry(
+        &mut self,
+        pane: WeakView<Pane>,
+        project_path: ProjectPath,
+        entry: NavigationEntry,
+        mode: NavigationMode,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Task<Result<()>> {
+        // If the item was no longer present, then load it again from its previous path.
+        let task = self.load_path(project_path, cx);
+        cx.spawn(|workspace, mut cx| async move {
+            let task = task.await;
+            let mut navigated = false;
+            if let Some((project_entry_id, build_item)) = task.log_err() {
+                let prev_active_item_id = pane.update(&mut cx, |pane, _| {
+                    pane.nav_history_mut().set_mode(mode);
+                    pane.active_item().map(|p| p.item_id())
+                })?;
+
+                pane.update(&mut cx, |pane, cx| {
+                    let item =
+                        pane.open_item(project_entry_id, true, entry.is_preview, cx, build_item);
+                    navigated |= Some(item.item_id()) != prev_active_item_id;
+                    pane.nav_history_mut().set_mode(NavigationMode::Normal);
+                    if let Some(data) = entry.data {
+                        navigated |= item.navigate(data, cx);
+                    }
+                })?;
+            }
+
+            if !navigated {
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        Self::navigate_history(workspace, pane, mode, cx)
+                    })?
+                    .await?;
+            }
+
+            Ok(())
+        })
+    }
+
     pub fn go_back(
         &mut self,
         pane: WeakView<Pane>,
@@ -1438,12 +1629,33 @@ impl Workspace {
         self.navigate_history(pane, NavigationMode::GoingForward, cx)
     }
 
+    /// Reopens the most recently closed item of any pane, in the pane that it was closed in.
+    /// Items that can't be opened anymore, e.g. because their file was deleted, are skipped.
     pub fn reopen_closed_item(&mut self, cx: &mut ViewContext<Workspace>) -> Task<Result<()>> {
-        self.navigate_history(
-            self.active_pane().downgrade(),
-            NavigationMode::ReopeningClosedItem,
+        let pane = self
+            .panes
+            .iter()
+            .filter_map(|pane| {
+                let timestamp = pane.read(cx).nav_history().last_closed_timestamp()?;
+                Some((timestamp, pane))
+            })
+            .max_by_key(|(timestamp, _)| *timestamp)
+            .map_or_else(|| self.active_pane(), |(_, pane)| pane)
+            .downgrade();
+        self.navigate_history(pane, NavigationMode::ReopeningClosedItem, cx)
+    }
+
+    fn show_skipped_closed_item(&mut self, project_path: &ProjectPath, cx: &mut ViewContext<Self>) {
+        struct SkippedClosedItem;
+
+        let message = format!(
+            "Skipped {}, which was deleted after it was closed.",
+            project_path.path.display()
+        );
+        self.show_toast(
+            Toast::new(NotificationId::unique::<SkippedClosedItem>(), message).autohide(),
             cx,
-        )
+        );
     }
 
     pub fn client(&self) -> &Arc<Client> {
@@ -1674,6 +1886,147 @@ impl Workspace {
         .detach_and_log_err(cx)
     }
 
+    /// Whether the window is about to close, and is only waiting for the user to confirm
+    /// that its unsaved changes can be saved or discarded.
+    pub fn is_closing(&self) -> bool {
+        self.closing
+    }
+
+    /// Closes this workspace after the usual save checks, and reopens its worktree roots in
+    /// the same window with a fresh project, e.g. to recover from a stuck language server.
+    /// The open items are restored too, unless `restore_items` is false.
+    pub fn reload_window(
+        &mut self,
+        restore_items: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(paths) = self.local_paths(cx) else {
+            return Task::ready(Err(anyhow!("only local workspaces can be reloaded")));
+        };
+        if paths.is_empty() {
+            return Task::ready(Err(anyhow!(
+                "only workspaces with open folders can be reloaded"
+            )));
+        }
+
+        let app_state = self.app_state.clone();
+        let window = cx.window_handle().downcast::<Workspace>();
+        let prepare_to_close = self.prepare_to_close(CloseIntent::ReplaceWindow, cx);
+        cx.spawn(|this, mut cx| async move {
+            if !prepare_to_close.await? {
+                return Ok(());
+            }
+            // The reopened workspace restores its items from the database, so make sure that
+            // the latest layout was written there.
+            this.update(&mut cx, |this, cx| this.serialize_workspace_internal(cx))?
+                .await;
+
+            let paths = paths
+                .iter()
+                .map(|path| path.to_path_buf())
+                .collect::<Vec<_>>();
+            let open_options = OpenOptions {
+                open_new_workspace: Some(true),
+                replace_window: window,
+                ..Default::default()
+            };
+            let (window, _) = cx
+                .update(|cx| open_paths(&paths, app_state, open_options, cx))?
+                .await?;
+            if !restore_items {
+                let close_items = window.update(&mut cx, |workspace, cx| {
+                    workspace.close_all_internal(false, SaveIntent::Skip, cx)
+                })?;
+                if let Some(close_items) = close_items {
+                    close_items.await?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn reload_window_action(&mut self, action: &ReloadWindow, cx: &mut ViewContext<Self>) {
+        self.reload_window(!action.skip_items, cx)
+            .detach_and_prompt_err("Failed to reload window", cx, |_, _| None);
+    }
+
+    /// Reopens this workspace on every startup, whatever `restore_on_startup` is set to, or stops
+    /// doing so if it already is.
+    fn toggle_always_restore(&mut self, _: &ToggleAlwaysRestore, cx: &mut ViewContext<Self>) {
+        let Some(paths) = self.local_paths(cx).filter(|paths| !paths.is_empty()) else {
+            self.show_error(
+                &anyhow!("only local workspaces with open folders can be always restored"),
+                cx,
+            );
+            return;
+        };
+        let paths = LocalPaths::new(paths.iter());
+        cx.spawn(|this, mut cx| async move {
+            let always_restored = always_restore::toggle_always_restore(&paths).await?;
+            this.update(&mut cx, |this, cx| {
+                struct AlwaysRestoreToast;
+
+                let message = if always_restored {
+                    "This workspace will be reopened on every startup"
+                } else {
+                    "This workspace will be reopened according to `restore_on_startup`"
+                };
+                this.show_toast(
+                    Toast::new(NotificationId::unique::<AlwaysRestoreToast>(), message),
+                    cx,
+                )
+            })
+        })
+        .detach_and_prompt_err("Failed to update the restored workspaces", cx, |_, _| None);
+    }
+
+    /// Opens another window onto this workspace's project. Both windows share the project,
+    /// so buffers and worktrees stay consistent between them.
+    fn duplicate_window(&mut self, _: &DuplicateWindow, cx: &mut ViewContext<Self>) {
+        let project = self.project.clone();
+        let app_state = self.app_state.clone();
+        let display = cx.display().and_then(|display| display.uuid().ok());
+        let options = (app_state.build_window_options)(display, cx);
+        cx.spawn(|_, mut cx| async move {
+            // The duplicate isn't serialized until the original window closes and hands
+            // its database id over, so that the two windows don't overwrite each other.
+            cx.open_window(options, |cx| {
+                cx.new_view(|cx| Workspace::new(None, project, app_state, cx))
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Passes this workspace's database id on to a window opened with [`DuplicateWindow`],
+    /// if one is still open, so that the project stays in the session after this window closes.
+    fn hand_over_to_duplicate(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        let Some(database_id) = self.database_id else {
+            return false;
+        };
+        let this_window = cx.window_handle();
+        let duplicate = cx
+            .windows()
+            .into_iter()
+            .filter(|window| *window != this_window)
+            .filter_map(|window| window.downcast::<Workspace>())
+            .find(|window| {
+                window.read(cx).map_or(false, |workspace| {
+                    workspace.project == self.project && workspace.database_id.is_none()
+                })
+            });
+        let Some(duplicate) = duplicate else {
+            return false;
+        };
+        self.database_id = None;
+        duplicate
+            .update(cx, |workspace, cx| {
+                workspace.database_id = Some(database_id);
+                workspace.serialize_workspace(cx);
+            })
+            .is_ok()
+    }
+
     pub fn prepare_to_close(
         &mut self,
         close_intent: CloseIntent,
@@ -1686,8 +2039,14 @@ impl Workspace {
         let save_last_workspace = cfg!(not(target_os = "macos"))
             && close_intent != CloseIntent::ReplaceWindow
             && cx.windows().len() == 1;
-
-        cx.spawn(|this, mut cx| async move {
+        // With `confirm_once`, stopping the running tasks was already confirmed for all
+        // windows at once before quitting.
+        let skip_busy_items = close_intent == CloseIntent::Quit
+            && WorkspaceSettings::get_global(cx).quit_with_running_tasks
+                == QuitWithRunningTasks::ConfirmOnce;
+
+        self.closing = true;
+        let prepare = cx.spawn(|this, mut cx| async move {
             let workspace_count = (*cx).update(|cx| {
                 cx.windows()
                     .iter()
@@ -1722,26 +2081,32 @@ impl Workspace {
 
             let save_result = this
                 .update(&mut cx, |this, cx| {
-                    this.save_all_internal(SaveIntent::Close, cx)
+                    this.save_all_internal(SaveIntent::Close, skip_busy_items, cx)
                 })?
                 .await;
 
             // If we're not quitting, but closing, we remove the workspace from
-            // the current session.
+            // the current session, unless another window still shows the project.
             if close_intent != CloseIntent::Quit
                 && !save_last_workspace
                 && save_result.as_ref().map_or(false, |&res| res)
+                && !this.update(&mut cx, |this, cx| this.hand_over_to_duplicate(cx))?
             {
                 this.update(&mut cx, |this, cx| this.remove_from_session(cx))?
                     .await;
             }
 
             save_result
+        });
+        cx.spawn(|this, mut cx| async move {
+            let should_close = prepare.await;
+            this.update(&mut cx, |this, _| this.closing = false).ok();
+            should_close
         })
     }
 
     fn save_all(&mut self, action: &SaveAll, cx: &mut ViewContext<Self>) {
-        self.save_all_internal(action.save_intent.unwrap_or(SaveIntent::SaveAll), cx)
+        self.save_all_internal(action.save_intent.unwrap_or(SaveIntent::SaveAll), false, cx)
             .detach_and_log_err(cx);
     }
 
@@ -1788,6 +2153,7 @@ impl Workspace {
     fn save_all_internal(
         &mut self,
         mut save_intent: SaveIntent,
+        skip_busy_items: bool,
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<bool>> {
         if self.project.read(cx).is_disconnected() {
@@ -1798,7 +2164,7 @@ impl Workspace {
             .iter()
             .flat_map(|pane| {
                 pane.read(cx).items().filter_map(|item| {
-                    if item.is_dirty(cx) {
+                    if item.is_dirty(cx) && !(skip_busy_items && item.is_busy(cx)) {
                         Some((pane.downgrade(), item.boxed_clone()))
                     } else {
                         None
@@ -1954,23 +2320,42 @@ impl Workspace {
     }
 
     #[allow(clippy::type_complexity)]
+    /// Opens the given paths, adding worktrees for them as needed.
+    ///
+    /// Files are opened as tabs in the same order as they are given, and the returned
+    /// results are in that order as well. Directories yield `None`.
     pub fn open_paths(
         &mut self,
-        mut abs_paths: Vec<PathBuf>,
+        abs_paths: Vec<PathBuf>,
         visible: OpenVisible,
         pane: Option<WeakView<Pane>>,
         cx: &mut ViewContext<Self>,
+    ) -> Task<Vec<Option<Result<Box<dyn ItemHandle>, anyhow::Error>>>> {
+        self.open_paths_inner(abs_paths, visible, pane, true, cx)
+    }
+
+    /// Like [`Workspace::open_paths`], but when `prompt_for_large_files` is false, large files
+    /// are opened as plain text without asking, e.g. when they're restored from the last session.
+    #[allow(clippy::type_complexity)]
+    fn open_paths_inner(
+        &mut self,
+        abs_paths: Vec<PathBuf>,
+        visible: OpenVisible,
+        pane: Option<WeakView<Pane>>,
+        prompt_for_large_files: bool,
+        cx: &mut ViewContext<Self>,
     ) -> Task<Vec<Option<Result<Box<dyn ItemHandle>, anyhow::Error>>>> {
         log::info!("open paths {abs_paths:?}");
 
         let fs = self.app_state.fs.clone();
 
-        // Sort the paths to ensure we add worktrees for parents before their children.
-        abs_paths.sort_unstable();
+        // Visit the paths in sorted order to ensure we add worktrees for parents before their children.
+        let mut sorted_indices = (0..abs_paths.len()).collect::<Vec<_>>();
+        sorted_indices.sort_unstable_by(|&a, &b| abs_paths[a].cmp(&abs_paths[b]));
         cx.spawn(move |this, mut cx| async move {
-            let mut tasks = Vec::with_capacity(abs_paths.len());
-
-            for abs_path in &abs_paths {
+            let mut project_paths = abs_paths.iter().map(|_| None).collect::<Vec<_>>();
+            for ix in sorted_indices {
+                let abs_path = &abs_paths[ix];
                 let visible = match visible {
                     OpenVisible::All => Some(true),
                     OpenVisible::None => Some(false),
@@ -2002,11 +2387,15 @@ impl Workspace {
                     },
                     None => None,
                 };
+                project_paths[ix] = project_path;
+            }
 
+            // Load the items concurrently, but add them to the pane one after another,
+            // so that the tabs don't end up in the order in which loading finished.
+            let mut tasks = Vec::with_capacity(abs_paths.len());
+            for (abs_path, project_path) in abs_paths.into_iter().zip(project_paths) {
                 let this = this.clone();
-                let abs_path = abs_path.clone();
                 let fs = fs.clone();
-                let pane = pane.clone();
                 let task = cx.spawn(move |mut cx| async move {
                     let (worktree, project_path) = project_path?;
                     if fs.is_dir(&abs_path).await {
@@ -2033,22 +2422,69 @@ impl Workspace {
                         .log_err()?;
                         None
                     } else {
+                        let prepared = large_files::prepare_to_open_file(
+                            &this,
+                            fs.clone(),
+                            &abs_path,
+                            &project_path,
+                            prompt_for_large_files,
+                            &mut cx,
+                        )
+                        .await;
+                        let _large_file_buffer = match prepared {
+                            Ok(PreparedFile::Normal) => None,
+                            Ok(PreparedFile::Large(buffer)) => Some(buffer),
+                            Ok(PreparedFile::Cancelled) => return None,
+                            Err(error) => return Some(Err(error)),
+                        };
                         Some(
-                            this.update(&mut cx, |this, cx| {
-                                this.open_path(project_path, pane, true, cx)
-                            })
-                            .log_err()?
-                            .await,
+                            this.update(&mut cx, |this, cx| this.load_path(project_path, cx))
+                                .log_err()?
+                                .await,
                         )
                     }
                 });
                 tasks.push(task);
             }
+            let loaded_items = futures::future::join_all(tasks).await;
 
-            futures::future::join_all(tasks).await
+            this.update(&mut cx, |this, cx| {
+                let pane = pane.unwrap_or_else(|| this.default_open_pane());
+                loaded_items
+                    .into_iter()
+                    .map(|loaded_item| {
+                        Some(loaded_item?.and_then(|(project_entry_id, build_item)| {
+                            pane.update(cx, |pane, cx| {
+                                pane.open_item(project_entry_id, true, false, cx, build_item)
+                            })
+                        }))
+                    })
+                    .collect()
+            })
+            .log_err()
+            .unwrap_or_default()
         })
     }
 
+    /// Makes the buffers of the given opened items read-only, see [`OpenOptions::read_only`].
+    fn make_items_read_only(
+        &self,
+        items: &[Option<Result<Box<dyn ItemHandle>, anyhow::Error>>],
+        cx: &mut ViewContext<Self>,
+    ) {
+        for item in items.iter().flatten().flatten() {
+            let Some(project_path) = item.project_path(cx) else {
+                continue;
+            };
+            let Some(buffer) = self.project.read(cx).get_open_buffer(&project_path, cx) else {
+                continue;
+            };
+            buffer.update(cx, |buffer, cx| {
+                buffer.set_capability(Capability::ReadOnly, cx)
+            });
+        }
+    }
+
     pub fn open_resolved_path(
         &mut self,
         path: ResolvedPath,
@@ -2244,10 +2680,17 @@ impl Workspace {
 
     pub fn toggle_dock(&mut self, dock_side: DockPosition, cx: &mut ViewContext<Self>) {
         let dock = match dock_side {
-            DockPosition::Left => &self.left_dock,
-            DockPosition::Bottom => &self.bottom_dock,
-            DockPosition::Right => &self.right_dock,
+            DockPosition::Left => self.left_dock.clone(),
+            DockPosition::Bottom => self.bottom_dock.clone(),
+            DockPosition::Right => self.right_dock.clone(),
         };
+        if self.zen_mode {
+            self.set_zen_mode(false, cx);
+            // Zen mode only hid the dock, so leaving it already shows the dock again.
+            if dock.read(cx).is_open() {
+                return;
+            }
+        }
         let mut focus_center = false;
         let mut reveal_dock = false;
         dock.update(cx, |dock, cx| {
@@ -2339,6 +2782,7 @@ impl Workspace {
         cx: &mut ViewContext<Self>,
         should_focus: impl Fn(&dyn PanelHandle, &mut ViewContext<Dock>) -> bool,
     ) -> Option<Arc<dyn PanelHandle>> {
+        self.set_zen_mode(false, cx);
         let mut result_panel = None;
         let mut serialize = false;
         for dock in [&self.left_dock, &self.bottom_dock, &self.right_dock] {
@@ -2437,14 +2881,16 @@ impl Workspace {
 
     fn add_pane(&mut self, cx: &mut ViewContext<Self>) -> View<Pane> {
         let pane = cx.new_view(|cx| {
-            Pane::new(
+            let mut pane = Pane::new(
                 self.weak_handle(),
                 self.project.clone(),
                 self.pane_history_timestamp.clone(),
                 None,
                 NewFile.boxed_clone(),
                 cx,
-            )
+            );
+            pane.hide_tab_bar = self.zen_mode;
+            pane
         });
         cx.subscribe(&pane, Self::handle_pane_event).detach();
         self.panes.push(pane.clone());
@@ -2589,15 +3035,7 @@ impl Workspace {
         allow_preview: bool,
         cx: &mut WindowContext,
     ) -> Task<Result<Box<dyn ItemHandle>, anyhow::Error>> {
-        let pane = pane.unwrap_or_else(|| {
-            self.last_active_center_pane.clone().unwrap_or_else(|| {
-                self.panes
-                    .first()
-                    .expect("There must be an active pane")
-                    .downgrade()
-            })
-        });
-
+        let pane = pane.unwrap_or_else(|| self.default_open_pane());
         let task = self.load_path(path.into(), cx);
         cx.spawn(move |mut cx| async move {
             let (project_entry_id, build_item) = task.await?;
@@ -2648,6 +3086,16 @@ impl Workspace {
         })
     }
 
+    /// The pane that paths are opened in when no pane is specified.
+    fn default_open_pane(&self) -> WeakView<Pane> {
+        self.last_active_center_pane.clone().unwrap_or_else(|| {
+            self.panes
+                .first()
+                .expect("There must be an active pane")
+                .downgrade()
+        })
+    }
+
     fn load_path(
         &mut self,
         path: ProjectPath,
@@ -3030,6 +3478,17 @@ impl Workspace {
                 self.update_window_edited(cx);
             }
             pane::Event::RemoveItem { .. } => {}
+            pane::Event::ClosedLastItem => {
+                if self.items(cx).next().is_none() {
+                    match WorkspaceSettings::get_global(cx).when_closing_last_tab {
+                        LastTabClosedBehavior::KeepEmpty => {}
+                        LastTabClosedBehavior::CloseWindow => {
+                            cx.dispatch_action(Box::new(CloseWindow))
+                        }
+                        LastTabClosedBehavior::NewFile => cx.dispatch_action(Box::new(NewFile)),
+                    }
+                }
+            }
             pane::Event::RemovedItem { item_id } => {
                 cx.emit(Event::ActiveItemChanged);
                 self.update_window_edited(cx);
@@ -3908,6 +4367,7 @@ impl Workspace {
     pub fn on_window_activation_changed(&mut self, cx: &mut ViewContext<Self>) {
         if cx.is_window_active() {
             self.update_active_view_for_followers(cx);
+            self.inactive_window_autosave = None;
 
             if let Some(database_id) = self.database_id {
                 cx.background_executor()
@@ -3915,25 +4375,144 @@ impl Workspace {
                     .detach();
             }
         } else {
+            let mut delayed_autosaves = Vec::new();
             for pane in &self.panes {
                 pane.update(cx, |pane, cx| {
                     if let Some(item) = pane.active_item() {
                         item.workspace_deactivated(cx);
                     }
                     for item in pane.items() {
-                        if matches!(
-                            item.workspace_settings(cx).autosave,
-                            AutosaveSetting::OnWindowChange | AutosaveSetting::OnFocusChange
-                        ) {
-                            Pane::autosave_item(item.as_ref(), self.project.clone(), cx)
-                                .detach_and_log_err(cx);
+                        match item.workspace_settings(cx).autosave {
+                            AutosaveSetting::OnWindowChange | AutosaveSetting::OnFocusChange => {
+                                Pane::autosave_item(item.as_ref(), self.project.clone(), cx)
+                                    .detach_and_log_err(cx);
+                            }
+                            AutosaveSetting::AfterWindowInactive { milliseconds } => {
+                                delayed_autosaves.push((
+                                    Duration::from_millis(milliseconds),
+                                    item.boxed_clone(),
+                                ));
+                            }
+                            AutosaveSetting::Off | AutosaveSetting::AfterDelay { .. } => {}
                         }
                     }
                 });
             }
+            self.schedule_inactive_window_autosave(delayed_autosaves, cx);
         }
     }
 
+    /// Hides the window's contents once it has gone without user input for longer than
+    /// `hide_contents_after_idle_minutes`, unless a task is still running in it.
+    fn hide_contents_if_idle(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(idle_minutes) = WorkspaceSettings::get_global(cx).hide_contents_after_idle_minutes
+        else {
+            return;
+        };
+        let countdown_start = self
+            .last_user_activity
+            .max(self.hide_contents_countdown_start);
+        if self.contents_hidden
+            || countdown_start.elapsed() < Duration::from_secs(idle_minutes * 60)
+        {
+            return;
+        }
+        if self.project.read(cx).has_running_tasks(cx) {
+            // Keep the task output visible, and only start counting once it has finished.
+            self.hide_contents_countdown_start = Instant::now();
+            return;
+        }
+
+        self.contents_hidden = true;
+        // Move focus away from the hidden items, so that keystrokes can't edit them.
+        self.focus_before_hiding_contents = cx.focused();
+        cx.focus(&self.hidden_contents_focus_handle);
+        cx.notify();
+    }
+
+    /// Stops the language servers that no open buffer is using once the window has gone without
+    /// user input for longer than `idle_housekeeping.after_minutes`. This includes the time the
+    /// window spends in the background.
+    fn release_resources_if_idle(&mut self, cx: &mut ViewContext<Self>) {
+        let settings = WorkspaceSettings::get_global(cx).idle_housekeeping;
+        let Some(idle_minutes) = settings.after_minutes else {
+            return;
+        };
+        if self.housekeeping_ran_after_activity == Some(self.last_user_activity)
+            || self.last_user_activity.elapsed() < Duration::from_secs(idle_minutes * 60)
+        {
+            return;
+        }
+        self.housekeeping_ran_after_activity = Some(self.last_user_activity);
+
+        if settings.stop_unused_language_servers {
+            let lsp_store = self.project.read(cx).lsp_store();
+            lsp_store.update(cx, |lsp_store, cx| {
+                lsp_store.stop_unused_language_servers(cx)
+            });
+        }
+    }
+
+    fn register_user_activity(&mut self, cx: &mut ViewContext<Self>) {
+        self.last_user_activity = Instant::now();
+        if self.contents_hidden {
+            self.contents_hidden = false;
+            if let Some(focus_handle) = self.focus_before_hiding_contents.take() {
+                cx.focus(&focus_handle);
+            }
+            cx.notify();
+        }
+    }
+
+    fn render_hidden_contents_overlay(&self, cx: &mut ViewContext<Self>) -> Div {
+        div()
+            .track_focus(&self.hidden_contents_focus_handle)
+            .occlude()
+            .absolute()
+            .inset_0()
+            .flex()
+            .items_center()
+            .justify_center()
+            .bg(cx.theme().colors().background)
+            .on_any_mouse_down(cx.listener(|this, _, cx| this.register_user_activity(cx)))
+            .child(
+                Label::new(
+                    "Contents hidden after inactivity. Click or press any key to show them.",
+                )
+                .color(Color::Muted),
+            )
+    }
+
+    /// Saves the given items once the window has stayed inactive for their delay.
+    /// Re-activating the window drops the task, cancelling any pending saves.
+    fn schedule_inactive_window_autosave(
+        &mut self,
+        mut items: Vec<(Duration, Box<dyn ItemHandle>)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if items.is_empty() {
+            self.inactive_window_autosave = None;
+            return;
+        }
+
+        items.sort_by_key(|(delay, _)| *delay);
+        self.inactive_window_autosave = Some(cx.spawn(|this, mut cx| async move {
+            let mut elapsed = Duration::ZERO;
+            for (delay, item) in items {
+                if delay > elapsed {
+                    cx.background_executor().timer(delay - elapsed).await;
+                    elapsed = delay;
+                }
+                this.update(&mut cx, |this, cx| {
+                    Pane::autosave_item(item.as_ref(), this.project.clone(), cx)
+                })?
+                .await
+                .log_err();
+            }
+            Ok(())
+        }));
+    }
+
     fn active_call(&self) -> Option<&Model<ActiveCall>> {
         self.active_call.as_ref().map(|(call, _)| call)
     }
@@ -3957,6 +4536,69 @@ impl Workspace {
         self.database_id
     }
 
+    /// Reopens this workspace in a new window on the display with the given uuid.
+    /// The display is persisted with the workspace, so it keeps opening there.
+    pub fn move_to_display(
+        &mut self,
+        display_uuid: Uuid,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(display) = cx
+            .displays()
+            .into_iter()
+            .find(|display| display.uuid().ok() == Some(display_uuid))
+        else {
+            return Task::ready(Err(anyhow!("display {display_uuid} is not connected")));
+        };
+        let (Some(workspace_id), Some(paths)) = (self.database_id, self.local_paths(cx)) else {
+            return Task::ready(Err(anyhow!(
+                "only local workspaces can be moved to another display"
+            )));
+        };
+        if paths.is_empty() {
+            return Task::ready(Err(anyhow!(
+                "only workspaces with open folders can be moved to another display"
+            )));
+        }
+
+        let bounds = Bounds::centered_at(
+            display.bounds().center(),
+            cx.window_bounds().get_bounds().size,
+        );
+        let app_state = self.app_state.clone();
+        let window = cx.window_handle().downcast::<Workspace>();
+        let prepare_to_close = self.prepare_to_close(CloseIntent::ReplaceWindow, cx);
+        cx.spawn(|_, mut cx| async move {
+            if !prepare_to_close.await? {
+                return Ok(());
+            }
+            DB.set_window_open_status(
+                workspace_id,
+                SerializedWindowBounds(WindowBounds::Windowed(bounds)),
+                display_uuid,
+            )
+            .await?;
+
+            let paths = paths
+                .iter()
+                .map(|path| path.to_path_buf())
+                .collect::<Vec<_>>();
+            let open_options = OpenOptions {
+                open_new_workspace: Some(true),
+                ..Default::default()
+            };
+            cx.update(|cx| open_paths(&paths, app_state, open_options, cx))?
+                .await?;
+
+            // Only close the old window once the new one exists, since closing the last
+            // window quits the app on some platforms.
+            if let Some(window) = window {
+                window.update(&mut cx, |_, cx| cx.remove_window())?;
+            }
+            Ok(())
+        })
+    }
+
     fn local_paths(&self, cx: &AppContext) -> Option<Vec<Arc<Path>>> {
         let project = self.project().read(cx);
 
@@ -4038,11 +4680,20 @@ impl Workspace {
                 (
                     pane.items()
                         .filter_map(|handle| {
-                            let handle = handle.to_serializable_item_handle(cx)?;
+                            let (kind, item_id) =
+                                if let Some(unloaded_item) = handle.downcast::<UnloadedItem>() {
+                                    unloaded_item.read(cx).serialized_item()
+                                } else {
+                                    let serializable = handle.to_serializable_item_handle(cx)?;
+                                    (
+                                        Arc::from(serializable.serialized_item_kind()),
+                                        handle.item_id().as_u64(),
+                                    )
+                                };
 
                             Some(SerializedItem {
-                                kind: Arc::from(handle.serialized_item_kind()),
-                                item_id: handle.item_id().as_u64(),
+                                kind,
+                                item_id,
                                 active: Some(handle.item_id()) == active_item_id,
                                 preview: pane.is_active_preview_item(handle.item_id()),
                             })
@@ -4233,6 +4884,11 @@ impl Workspace {
             let mut center_group = None;
             let mut center_items = None;
 
+            // Items beyond the limit are restored as placeholders, which load when activated.
+            let items_to_load = cx
+                .update(|cx| WorkspaceSettings::get_global(cx).max_restored_tabs)?
+                .map(|limit| serialized_workspace.center_group.items_to_load(limit));
+
             // Traverse the splits tree and add to things
             if let Some((group, active_pane, items)) = serialized_workspace
                 .center_group
@@ -4240,6 +4896,7 @@ impl Workspace {
                     &project,
                     serialized_workspace.id,
                     workspace.clone(),
+                    items_to_load.as_ref(),
                     &mut cx,
                 )
                 .await
@@ -4255,9 +4912,16 @@ impl Workspace {
                 for item in center_items.unwrap_or_default().into_iter().flatten() {
                     if let Some(serializable_item_handle) = item.to_serializable_item_handle(cx) {
                         item_ids_by_kind
-                            .entry(serializable_item_handle.serialized_item_kind())
+                            .entry(Arc::from(serializable_item_handle.serialized_item_kind()))
                             .or_insert(Vec::new())
                             .push(item.item_id().as_u64() as ItemId);
+                    } else if let Some(unloaded_item) = item.downcast::<UnloadedItem>() {
+                        // Keep the state of items that haven't been loaded yet.
+                        let (kind, item_id) = unloaded_item.read(cx).serialized_item();
+                        item_ids_by_kind
+                            .entry(kind)
+                            .or_insert(Vec::new())
+                            .push(item_id);
                     }
 
                     if let Some(project_path) = item.project_path(cx) {
@@ -4319,7 +4983,7 @@ impl Workspace {
                     .into_iter()
                     .map(|(item_kind, loaded_items)| {
                         SerializableItemRegistry::cleanup(
-                            item_kind,
+                            &item_kind,
                             serialized_workspace.id,
                             loaded_items,
                             cx,
@@ -4355,6 +5019,10 @@ impl Workspace {
             .on_action(cx.listener(Self::follow_next_collaborator))
             .on_action(cx.listener(Self::open))
             .on_action(cx.listener(Self::close_window))
+            .on_action(cx.listener(Self::reload_window_action))
+            .on_action(cx.listener(Self::toggle_always_restore))
+            .on_action(cx.listener(Self::toggle_zen_mode))
+            .on_action(cx.listener(Self::duplicate_window))
             .on_action(cx.listener(Self::activate_pane_at_index))
             .on_action(cx.listener(|workspace, _: &Unfollow, cx| {
                 let pane = workspace.active_pane().clone();
@@ -4494,6 +5162,38 @@ impl Workspace {
         cx.notify();
     }
 
+    fn toggle_zen_mode(&mut self, _: &ToggleZenMode, cx: &mut ViewContext<Self>) {
+        self.set_zen_mode(!self.zen_mode, cx);
+    }
+
+    pub fn zen_mode(&self) -> bool {
+        self.zen_mode
+    }
+
+    /// Hides the docks, tab bars, title bar and status bar, leaving only the editors.
+    /// Nothing is closed, so turning zen mode off brings back the exact same layout.
+    pub fn set_zen_mode(&mut self, zen_mode: bool, cx: &mut ViewContext<Self>) {
+        if self.zen_mode == zen_mode {
+            return;
+        }
+        self.zen_mode = zen_mode;
+        for pane in self.center.panes() {
+            pane.update(cx, |pane, cx| {
+                pane.hide_tab_bar = zen_mode;
+                cx.notify();
+            });
+        }
+        if zen_mode {
+            let dock_is_focused = [&self.left_dock, &self.bottom_dock, &self.right_dock]
+                .into_iter()
+                .any(|dock| dock.focus_handle(cx).contains_focused(cx));
+            if dock_is_focused {
+                self.active_pane.update(cx, |pane, cx| pane.focus(cx));
+            }
+        }
+        cx.notify();
+    }
+
     fn adjust_padding(padding: Option<f32>) -> f32 {
         padding
             .unwrap_or(Self::DEFAULT_PADDING)
@@ -4506,7 +5206,7 @@ impl Workspace {
         dock: &View<Dock>,
         cx: &WindowContext,
     ) -> Option<Div> {
-        if self.zoomed_position == Some(position) {
+        if self.zen_mode || self.zoomed_position == Some(position) {
             return None;
         }
 
@@ -4639,7 +5339,7 @@ fn open_items(
                                     ix,
                                     workspace
                                         .update(&mut cx, |workspace, cx| {
-                                            workspace.open_path(file_project_path, None, true, cx)
+                                            workspace.load_path(file_project_path, cx)
                                         })
                                         .log_err()?
                                         .await,
@@ -4651,10 +5351,18 @@ fn open_items(
 
         let tasks = tasks.collect::<Vec<_>>();
 
-        let tasks = futures::future::join_all(tasks);
-        for (ix, path_open_result) in tasks.await.into_iter().flatten() {
-            opened_items[ix] = Some(path_open_result);
-        }
+        // Add the items in the order of the paths, rather than in the order they finished loading.
+        let loaded_items = futures::future::join_all(tasks).await;
+        workspace.update(&mut cx, |workspace, cx| {
+            let pane = workspace.default_open_pane();
+            for (ix, loaded_item) in loaded_items.into_iter().flatten() {
+                opened_items[ix] = Some(loaded_item.and_then(|(project_entry_id, build_item)| {
+                    pane.update(cx, |pane, cx| {
+                        pane.open_item(project_entry_id, true, false, cx, build_item)
+                    })
+                }));
+            }
+        })?;
 
         Ok(opened_items)
     })
@@ -4741,7 +5449,13 @@ impl Render for Workspace {
                 .items_start()
                 .text_color(colors.text)
                 .overflow_hidden()
-                .children(self.titlebar_item.clone())
+                .capture_any_mouse_down(cx.listener(|this, _, _| {
+                    this.last_user_activity = Instant::now();
+                }))
+                .on_mouse_move(cx.listener(|this, _, _| {
+                    this.last_user_activity = Instant::now();
+                }))
+                .children(self.titlebar_item.clone().filter(|_| !self.zen_mode))
                 .child(
                     div()
                         .id("workspace")
@@ -4856,7 +5570,7 @@ impl Render for Workspace {
                         .child(self.modal_layer.clone())
                         .children(self.render_notifications(cx)),
                 )
-                .child(self.status_bar.clone())
+                .when(!self.zen_mode, |this| this.child(self.status_bar.clone()))
                 .children(if self.project.read(cx).is_disconnected() {
                     if let Some(render) = self.render_disconnected_overlay.take() {
                         let result = render(self, cx);
@@ -4867,6 +5581,9 @@ impl Render for Workspace {
                     }
                 } else {
                     None
+                })
+                .when(self.contents_hidden, |this| {
+                    this.child(self.render_hidden_contents_overlay(cx))
                 }),
             cx,
         )
@@ -5297,24 +6014,24 @@ fn activate_any_workspace_window(cx: &mut AsyncAppContext) -> Option<WindowHandl
             return Some(workspace_window);
         }
 
-        for window in cx.windows() {
-            if let Some(workspace_window) = window.downcast::<Workspace>() {
-                workspace_window
-                    .update(cx, |_, cx| cx.activate_window())
-                    .ok();
-                return Some(workspace_window);
-            }
-        }
-        None
+        activate_workspace_window_at(0, cx)
     })
     .ok()
     .flatten()
 }
 
-pub fn local_workspace_windows(cx: &AppContext) -> Vec<WindowHandle<Workspace>> {
+/// Returns every window that shows a workspace, including the ones that are closing. Use
+/// [`Workspace::is_closing`] to leave those out.
+pub fn workspace_windows(cx: &AppContext) -> Vec<WindowHandle<Workspace>> {
     cx.windows()
         .into_iter()
         .filter_map(|window| window.downcast::<Workspace>())
+        .collect()
+}
+
+pub fn local_workspace_windows(cx: &AppContext) -> Vec<WindowHandle<Workspace>> {
+    workspace_windows(cx)
+        .into_iter()
         .filter(|workspace| {
             workspace
                 .read(cx)
@@ -5323,13 +6040,46 @@ pub fn local_workspace_windows(cx: &AppContext) -> Vec<WindowHandle<Workspace>>
         .collect()
 }
 
+/// Returns the workspace windows that aren't closing, which requests can be routed to.
+fn open_workspace_windows(cx: &AppContext) -> Vec<WindowHandle<Workspace>> {
+    workspace_windows(cx)
+        .into_iter()
+        .filter(|window| {
+            window
+                .read(cx)
+                .map_or(false, |workspace| !workspace.closing)
+        })
+        .collect()
+}
+
+/// Activates the workspace window at the given index, counting only the windows that aren't
+/// closing, in the order of [`workspace_windows`].
+pub fn activate_workspace_window_at(
+    index: usize,
+    cx: &mut AppContext,
+) -> Option<WindowHandle<Workspace>> {
+    let window = *open_workspace_windows(cx).get(index)?;
+    window.update(cx, |_, cx| cx.activate_window()).ok()?;
+    Some(window)
+}
+
 #[derive(Default)]
 pub struct OpenOptions {
     pub open_new_workspace: Option<bool>,
     pub replace_window: Option<WindowHandle<Workspace>>,
     pub env: Option<HashMap<String, String>>,
+    /// Whether to make the buffers of the opened files read-only, so that they can't be edited
+    /// until they're unlocked.
+    pub read_only: bool,
+    /// Whether the paths are reopened from the last session, in which case large files are
+    /// opened as plain text without asking again.
+    pub restoring: bool,
 }
 
+/// Opens the given paths in a single workspace, reusing an existing window when possible.
+///
+/// When the paths mix directories and files, only the directories become worktree roots,
+/// and the files are opened as tabs in that workspace.
 #[allow(clippy::type_complexity)]
 pub fn open_paths(
     abs_paths: &[PathBuf],
@@ -5346,6 +6096,8 @@ pub fn open_paths(
     let mut existing = None;
     let mut best_match = None;
     let mut open_visible = OpenVisible::All;
+    let read_only = open_options.read_only;
+    let restoring = open_options.restoring;
 
     if open_options.open_new_workspace != Some(true) {
         for window in local_workspace_windows(cx) {
@@ -5365,14 +6117,27 @@ pub fn open_paths(
     }
 
     cx.spawn(move |mut cx| async move {
-        if open_options.open_new_workspace.is_none() && existing.is_none() {
-            let all_files = abs_paths.iter().map(|path| app_state.fs.metadata(path));
-            if futures::future::join_all(all_files)
+        let (abs_paths, unique_ix_by_input) =
+            deduplicate_paths(abs_paths, app_state.fs.as_ref()).await;
+        let is_dir =
+            futures::future::join_all(abs_paths.iter().map(|path| app_state.fs.metadata(path)))
                 .await
                 .into_iter()
-                .filter_map(|result| result.ok().flatten())
-                .all(|file| !file.is_dir)
-            {
+                .map(|metadata| {
+                    metadata
+                        .ok()
+                        .flatten()
+                        .map_or(false, |metadata| metadata.is_dir)
+                })
+                .collect::<Vec<_>>();
+        let mixes_directories_and_files =
+            is_dir.iter().any(|is_dir| *is_dir) && is_dir.iter().any(|is_dir| !is_dir);
+        if mixes_directories_and_files {
+            open_visible = OpenVisible::OnlyDirectories;
+        }
+
+        if open_options.open_new_workspace.is_none() && existing.is_none() {
+            if is_dir.iter().all(|is_dir| !is_dir) {
                 cx.update(|cx| {
                     for window in local_workspace_windows(cx) {
                         if let Ok(workspace) = window.read(cx) {
@@ -5389,16 +6154,46 @@ pub fn open_paths(
             }
         }
 
-        if let Some(existing) = existing {
-            Ok((
+        let (window, items) = if let Some(existing) = existing {
+            (
                 existing,
                 existing
                     .update(&mut cx, |workspace, cx| {
                         cx.activate_window();
-                        workspace.open_paths(abs_paths, open_visible, None, cx)
+                        workspace.open_paths_inner(abs_paths, open_visible, None, !restoring, cx)
                     })?
                     .await,
-            ))
+            )
+        } else if mixes_directories_and_files {
+            let directories = abs_paths
+                .iter()
+                .zip(&is_dir)
+                .filter(|(_, is_dir)| **is_dir)
+                .map(|(path, _)| path.clone())
+                .collect();
+            let (window, _) = cx
+                .update(|cx| {
+                    Workspace::new_local(
+                        directories,
+                        app_state.clone(),
+                        open_options.replace_window,
+                        open_options.env,
+                        cx,
+                    )
+                })?
+                .await?;
+            let items = window
+                .update(&mut cx, |workspace, cx| {
+                    workspace.open_paths_inner(
+                        abs_paths,
+                        OpenVisible::OnlyDirectories,
+                        None,
+                        !restoring,
+                        cx,
+                    )
+                })?
+                .await;
+            (window, items)
         } else {
             cx.update(move |cx| {
                 Workspace::new_local(
@@ -5409,11 +6204,58 @@ pub fn open_paths(
                     cx,
                 )
             })?
-            .await
+            .await?
+        };
+        if read_only {
+            window.update(&mut cx, |workspace, cx| {
+                workspace.make_items_read_only(&items, cx)
+            })?;
         }
+        Ok((window, results_for_inputs(items, &unique_ix_by_input)))
     })
 }
 
+/// Drops the paths that name the same file as an earlier path, e.g. because a shell glob
+/// matched it twice or because of a symlink. Returns the remaining paths, and for every
+/// given path the index of the remaining path that stands for it.
+async fn deduplicate_paths(abs_paths: Vec<PathBuf>, fs: &dyn fs::Fs) -> (Vec<PathBuf>, Vec<usize>) {
+    let canonical_paths =
+        futures::future::join_all(abs_paths.iter().map(|path| fs.canonicalize(path))).await;
+    let mut unique_paths = Vec::with_capacity(abs_paths.len());
+    let mut unique_ix_by_canonical_path = HashMap::default();
+    let mut unique_ix_by_input = Vec::with_capacity(abs_paths.len());
+    for (abs_path, canonical_path) in abs_paths.into_iter().zip(canonical_paths) {
+        // Paths that don't exist yet can't be resolved, and are compared as given.
+        let canonical_path = canonical_path.unwrap_or_else(|_| abs_path.clone());
+        let unique_ix = *unique_ix_by_canonical_path
+            .entry(canonical_path)
+            .or_insert_with(|| {
+                unique_paths.push(abs_path);
+                unique_paths.len() - 1
+            });
+        unique_ix_by_input.push(unique_ix);
+    }
+    (unique_paths, unique_ix_by_input)
+}
+
+/// Hands out the result for each deduplicated path to every given path that it stands for.
+fn results_for_inputs(
+    results: Vec<Option<anyhow::Result<Box<dyn ItemHandle>>>>,
+    unique_ix_by_input: &[usize],
+) -> Vec<Option<anyhow::Result<Box<dyn ItemHandle>>>> {
+    if results.len() == unique_ix_by_input.len() {
+        return results;
+    }
+    unique_ix_by_input
+        .iter()
+        .map(|&ix| match results.get(ix)? {
+            Some(Ok(item)) => Some(Ok(item.boxed_clone())),
+            Some(Err(error)) => Some(Err(anyhow!("{error:#}"))),
+            None => None,
+        })
+        .collect()
+}
+
 pub fn open_new(
     open_options: OpenOptions,
     app_state: Arc<AppState>,
@@ -5767,11 +6609,7 @@ pub fn join_in_room_project(
 
 pub fn reload(reload: &Reload, cx: &mut AppContext) {
     let should_confirm = WorkspaceSettings::get_global(cx).confirm_quit;
-    let mut workspace_windows = cx
-        .windows()
-        .into_iter()
-        .filter_map(|window| window.downcast::<Workspace>())
-        .collect::<Vec<_>>();
+    let mut workspace_windows = workspace_windows(cx);
 
     // If multiple windows have unsaved changes, and need a save prompt,
     // prompt in the active window before switching to a different window.
@@ -5811,6 +6649,10 @@ pub fn reload(reload: &Reload, cx: &mut AppContext) {
             }
         }
 
+        if !run_quit_hooks(&mut cx).await? {
+            return Ok(());
+        }
+
         cx.update(|cx| cx.restart(binary_path))
     })
     .detach_and_log_err(cx);
@@ -6315,12 +7157,14 @@ mod tests {
         });
         let task = workspace.update(cx, |w, cx| w.prepare_to_close(CloseIntent::CloseWindow, cx));
         cx.executor().run_until_parked();
+        assert!(workspace.read_with(cx, |w, _| w.is_closing()));
         cx.simulate_prompt_answer(2); // cancel save all
         cx.executor().run_until_parked();
         cx.simulate_prompt_answer(2); // cancel save all
         cx.executor().run_until_parked();
         assert!(!cx.has_pending_prompt());
         assert!(!task.await.unwrap());
+        assert!(!workspace.read_with(cx, |w, _| w.is_closing()));
     }
 
     #[gpui::test]
@@ -6358,6 +7202,70 @@ mod tests {
         assert!(task.await.unwrap());
     }
 
+    #[gpui::test]
+    async fn test_duplicate_window(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "one": "" })).await;
+
+        let project = Project::test(fs, ["root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        workspace.update(cx, |workspace, _| {
+            workspace.database_id = Some(WorkspaceId(1));
+        });
+
+        cx.dispatch_action(DuplicateWindow);
+        cx.executor().run_until_parked();
+
+        let windows = cx.windows();
+        assert_eq!(windows.len(), 2);
+        let duplicate = windows[1].downcast::<Workspace>().unwrap();
+        duplicate
+            .read_with(cx, |duplicate, _| {
+                assert_eq!(duplicate.project, project);
+                assert_eq!(duplicate.database_id, None);
+            })
+            .unwrap();
+
+        // Closing the original window hands its place in the session to the duplicate.
+        let task = workspace.update(cx, |w, cx| w.prepare_to_close(CloseIntent::CloseWindow, cx));
+        assert!(task.await.unwrap());
+        workspace.update(cx, |workspace, _| assert_eq!(workspace.database_id, None));
+        duplicate
+            .read_with(cx, |duplicate, _| {
+                assert_eq!(duplicate.database_id, Some(WorkspaceId(1)));
+            })
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_activate_workspace_windows(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/a", json!({ "a.txt": "" })).await;
+        fs.insert_tree("/b", json!({ "b.txt": "" })).await;
+        let project_a = Project::test(fs.clone(), ["/a".as_ref()], cx).await;
+        let project_b = Project::test(fs, ["/b".as_ref()], cx).await;
+        let window_a = cx.add_window(|cx| Workspace::test_new(project_a, cx));
+        let window_b = cx.add_window(|cx| Workspace::test_new(project_b, cx));
+
+        cx.update(|cx| {
+            assert!(activate_workspace_window_at(0, cx) == Some(window_a));
+            assert!(activate_workspace_window_at(1, cx) == Some(window_b));
+        });
+
+        // Windows that are closing aren't activated.
+        window_b
+            .update(cx, |workspace, _| workspace.closing = true)
+            .unwrap();
+        cx.update(|cx| {
+            assert!(activate_workspace_window_at(1, cx).is_none());
+            assert_eq!(workspace_windows(cx).len(), 2);
+        });
+    }
+
     #[gpui::test]
     async fn test_close_pane_items(cx: &mut TestAppContext) {
         init_test(cx);
@@ -6694,6 +7602,51 @@ mod tests {
         item.update(cx, |item, _| assert_eq!(item.save_count, 5));
     }
 
+    #[gpui::test]
+    async fn test_autosave_after_window_inactive(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let item = cx.new_view(|cx| {
+            TestItem::new(cx).with_project_items(&[TestProjectItem::new(1, "1.txt", cx)])
+        });
+        let untitled_item = cx.new_view(|cx| TestItem::new(cx).with_dirty(true));
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(untitled_item.clone()), None, true, cx);
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, true, cx);
+        });
+
+        item.update(cx, |item, cx| {
+            SettingsStore::update_global(cx, |settings, cx| {
+                settings.update_user_settings::<WorkspaceSettings>(cx, |settings| {
+                    settings.autosave =
+                        Some(AutosaveSetting::AfterWindowInactive { milliseconds: 500 });
+                })
+            });
+            item.is_dirty = true;
+        });
+
+        // Deactivating the window doesn't save the file right away.
+        cx.deactivate_window();
+        cx.executor().advance_clock(Duration::from_millis(250));
+        item.update(cx, |item, _| assert_eq!(item.save_count, 0));
+
+        // Re-activating the window before the delay expires cancels the save.
+        cx.update(|cx| cx.activate_window());
+        cx.executor().advance_clock(Duration::from_millis(500));
+        item.update(cx, |item, _| assert_eq!(item.save_count, 0));
+
+        // Staying inactive for the whole delay saves the file, but not the untitled item.
+        cx.deactivate_window();
+        cx.executor().advance_clock(Duration::from_millis(500));
+        item.update(cx, |item, _| assert_eq!(item.save_count, 1));
+        untitled_item.update(cx, |item, _| assert_eq!(item.save_count, 0));
+        assert!(!cx.has_pending_prompt());
+    }
+
     #[gpui::test]
     async fn test_pane_navigation(cx: &mut gpui::TestAppContext) {
         init_test(cx);
@@ -12,13 +12,38 @@ pub struct WorkspaceSettings {
     pub pane_split_direction_vertical: PaneSplitDirectionVertical,
     pub centered_layout: CenteredLayoutSettings,
     pub confirm_quit: bool,
+    pub confirm_quit_without_unsaved_files: bool,
+    pub quit_with_running_tasks: QuitWithRunningTasks,
+    pub confirm_opening_many_files: ConfirmOpeningManyFilesSettings,
+    pub large_files: LargeFilesSettings,
     pub show_call_status_icon: bool,
     pub autosave: AutosaveSetting,
     pub restore_on_startup: RestoreOnStartupBehavior,
+    pub max_restored_workspaces: Option<usize>,
+    pub max_restored_tabs: Option<usize>,
+    pub startup_view: StartupView,
+    pub show_welcome: bool,
+    pub show_startup_splash: bool,
+    pub keep_running_without_windows: bool,
     pub drop_target_size: f32,
     pub when_closing_with_no_tabs: CloseWindowWhenNoItems,
+    pub when_closing_last_tab: LastTabClosedBehavior,
     pub use_system_path_prompts: bool,
     pub command_aliases: HashMap<String, String>,
+    pub hide_contents_after_idle_minutes: Option<u64>,
+    pub idle_housekeeping: IdleHousekeepingSettings,
+    pub watch_config_files: bool,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QuitWithRunningTasks {
+    /// Ask about the running tasks of each window, along with its unsaved changes.
+    #[default]
+    PromptPerWindow,
+    /// Ask once whether to stop all running tasks and quit, and only ask about unsaved
+    /// changes per window.
+    ConfirmOnce,
 }
 
 #[derive(Copy, Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -43,6 +68,18 @@ impl CloseWindowWhenNoItems {
     }
 }
 
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LastTabClosedBehavior {
+    /// Leave the window open with no tabs.
+    #[default]
+    KeepEmpty,
+    /// Close the window.
+    CloseWindow,
+    /// Open a new untitled buffer in place of the closed tab.
+    NewFile,
+}
+
 #[derive(Copy, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RestoreOnStartupBehavior {
@@ -55,6 +92,18 @@ pub enum RestoreOnStartupBehavior {
     LastSession,
 }
 
+#[derive(Copy, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupView {
+    /// Open an empty buffer.
+    #[default]
+    EmptyBuffer,
+    /// Open the recent projects picker.
+    RecentProjects,
+    /// Open an empty workspace with no tabs.
+    EmptyWorkspace,
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct WorkspaceSettingsContent {
     /// Scale by which to zoom the active pane.
@@ -77,6 +126,23 @@ pub struct WorkspaceSettingsContent {
     ///
     /// Default: false
     pub confirm_quit: Option<bool>,
+    /// When `confirm_quit` is enabled, whether to still prompt if there are
+    /// no unsaved files.
+    ///
+    /// Default: true
+    pub confirm_quit_without_unsaved_files: Option<bool>,
+    /// How to confirm quitting while tasks are still running.
+    ///
+    /// Default: prompt_per_window
+    pub quit_with_running_tasks: Option<QuitWithRunningTasks>,
+    /// Whether to confirm before opening many files from the command line at once.
+    ///
+    /// Default: {"threshold": 100, "prompt": true}
+    pub confirm_opening_many_files: Option<ConfirmOpeningManyFilesSettings>,
+    /// How to open files that are too large or binary to edit as text.
+    ///
+    /// Default: {"size_threshold_mb": 20, "refuse_binary": true}
+    pub large_files: Option<LargeFilesSettings>,
     /// Whether or not to show the call status icon in the status bar.
     ///
     /// Default: true
@@ -86,9 +152,43 @@ pub struct WorkspaceSettingsContent {
     /// Default: off
     pub autosave: Option<AutosaveSetting>,
     /// Controls previous session restoration in freshly launched Zed instance.
+    /// Workspaces marked with the `ToggleAlwaysRestore` action are reopened in any case.
     /// Values: none, last_workspace, last_session
     /// Default: last_session
     pub restore_on_startup: Option<RestoreOnStartupBehavior>,
+    /// The maximum number of windows to reopen at startup when restoring the last session.
+    /// The most recently used ones are reopened, and the others can be restored from a notification.
+    /// Always restored workspaces don't count towards the limit. `null` reopens all of them.
+    ///
+    /// Default: null
+    pub max_restored_workspaces: Option<usize>,
+    /// The maximum number of tabs to load when restoring a workspace. The active tab of each
+    /// pane and the tabs next to it are loaded first; the others are loaded when activated.
+    /// `null` loads all of them.
+    ///
+    /// Default: null
+    pub max_restored_tabs: Option<usize>,
+    /// What to show when a fresh Zed instance has no workspace to restore.
+    /// Values: empty_buffer, recent_projects, empty_workspace
+    /// Default: empty_buffer
+    pub startup_view: Option<StartupView>,
+    /// Whether to show the welcome view on first launch. When disabled, the first launch opens
+    /// the `startup_view` instead, and is still remembered as having happened.
+    ///
+    /// Default: true
+    pub show_welcome: Option<bool>,
+    /// Whether to show a small loading window while Zed starts, until the first
+    /// workspace window opens.
+    ///
+    /// Default: false
+    pub show_startup_splash: Option<bool>,
+    /// Whether Zed keeps running in the background after its last window is closed,
+    /// so that launching it again opens a window instantly. Only affects Linux, as
+    /// applications on macOS always keep running. There is no tray icon, so the instance
+    /// can only be reached by launching Zed again, or quit with `zed --quit`.
+    ///
+    /// Default: false
+    pub keep_running_without_windows: Option<bool>,
     /// The size of the workspace split drop targets on the outer edges.
     /// Given as a fraction that will be multiplied by the smaller dimension of the workspace.
     ///
@@ -98,6 +198,12 @@ pub struct WorkspaceSettingsContent {
     ///
     /// Default: auto ("on" on macOS, "off" otherwise)
     pub when_closing_with_no_tabs: Option<CloseWindowWhenNoItems>,
+    /// What to do when the last tab of a window is closed. Closing a tab again once the window
+    /// has no tabs is handled by `when_closing_with_no_tabs`, which never applies with
+    /// `new_file`.
+    ///
+    /// Default: keep_empty
+    pub when_closing_last_tab: Option<LastTabClosedBehavior>,
     /// Whether to use the system provided dialogs for Open and Save As.
     /// When set to false, Zed will use the built-in keyboard-first pickers.
     ///
@@ -108,6 +214,68 @@ pub struct WorkspaceSettingsContent {
     ///
     /// Default: true
     pub command_aliases: Option<HashMap<String, String>>,
+    /// Hide the contents of a window after it has received no keyboard or mouse input
+    /// for this many minutes, until the window is clicked or a key is pressed.
+    /// Windows with running tasks are never hidden. `null` disables hiding.
+    ///
+    /// Default: null
+    pub hide_contents_after_idle_minutes: Option<u64>,
+    /// Stop the language servers that no open buffer is using once a window has received no
+    /// keyboard or mouse input for a while.
+    ///
+    /// Default: {"after_minutes": null, "stop_unused_language_servers": true}
+    pub idle_housekeeping: Option<IdleHousekeepingSettings>,
+    /// Whether to watch the settings, keymap, tasks and theme files for changes. This only
+    /// applies at startup; the watchers can be paused and resumed with `zed: toggle file
+    /// watchers`.
+    ///
+    /// Default: true
+    pub watch_config_files: Option<bool>,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct IdleHousekeepingSettings {
+    /// The number of minutes without input after which housekeeping runs, or `null` to never
+    /// run it.
+    ///
+    /// Default: null
+    pub after_minutes: Option<u64>,
+    /// Whether to stop the language servers that no open buffer is using.
+    ///
+    /// Default: true
+    #[serde(default = "util::serde::default_true")]
+    pub stop_unused_language_servers: bool,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ConfirmOpeningManyFilesSettings {
+    /// The number of files above which opening them needs confirming, or `null` to never
+    /// confirm.
+    ///
+    /// Default: 100
+    pub threshold: Option<usize>,
+    /// Whether to ask in a prompt. When disabled, the files are only opened if `--yes` is passed
+    /// on the command line.
+    ///
+    /// Default: true
+    #[serde(default = "util::serde::default_true")]
+    pub prompt: bool,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LargeFilesSettings {
+    /// The size in megabytes above which opening a file needs confirming. Such files are opened
+    /// read-only, without syntax highlighting or language servers. `null` opens files of any
+    /// size normally.
+    ///
+    /// Default: 20
+    pub size_threshold_mb: Option<u64>,
+    /// Whether to refuse opening files that look binary. Images are still opened in the image
+    /// viewer.
+    ///
+    /// Default: true
+    #[serde(default = "util::serde::default_true")]
+    pub refuse_binary: bool,
 }
 
 #[derive(Deserialize)]
@@ -139,6 +307,8 @@ pub enum AutosaveSetting {
     OnFocusChange,
     /// Autosave when the active window changes.
     OnWindowChange,
+    /// Autosave once the window has been inactive for `milliseconds`.
+    AfterWindowInactive { milliseconds: u64 },
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
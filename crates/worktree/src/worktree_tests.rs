@@ -3,7 +3,7 @@ use crate::{
     WorktreeModelHandle,
 };
 use anyhow::Result;
-use fs::{FakeFs, Fs, RealFs, RemoveOptions};
+use fs::{FakeFs, FileFormat, Fs, RealFs, RemoveOptions};
 use git::{repository::GitFileStatus, GITIGNORE};
 use gpui::{BorrowAppContext, ModelContext, Task, TestAppContext};
 use parking_lot::Mutex;
@@ -455,7 +455,11 @@ async fn test_open_gitignored_files(cx: &mut TestAppContext) {
     let prev_read_dir_count = fs.read_dir_call_count();
     let loaded = tree
         .update(cx, |tree, cx| {
-            tree.load_file("one/node_modules/b/b1.js".as_ref(), cx)
+            tree.load_file(
+                "one/node_modules/b/b1.js".as_ref(),
+                FileFormat::default(),
+                cx,
+            )
         })
         .await
         .unwrap();
@@ -495,7 +499,11 @@ async fn test_open_gitignored_files(cx: &mut TestAppContext) {
     let prev_read_dir_count = fs.read_dir_call_count();
     let loaded = tree
         .update(cx, |tree, cx| {
-            tree.load_file("one/node_modules/a/a2.js".as_ref(), cx)
+            tree.load_file(
+                "one/node_modules/a/a2.js".as_ref(),
+                FileFormat::default(),
+                cx,
+            )
         })
         .await
         .unwrap();
@@ -854,6 +862,7 @@ async fn test_write_file(cx: &mut TestAppContext) {
             Path::new("tracked-dir/file.txt"),
             "hello".into(),
             Default::default(),
+            FileFormat::default(),
             cx,
         )
     })
@@ -864,6 +873,7 @@ async fn test_write_file(cx: &mut TestAppContext) {
             Path::new("ignored-dir/file.txt"),
             "world".into(),
             Default::default(),
+            FileFormat::default(),
             cx,
         )
     })
@@ -1732,8 +1742,13 @@ fn randomly_mutate_worktree(
                 })
             } else {
                 log::info!("overwriting file {:?} ({})", entry.path, entry.id.0);
-                let task =
-                    worktree.write_file(entry.path.clone(), "".into(), Default::default(), cx);
+                let task = worktree.write_file(
+                    entry.path.clone(),
+                    "".into(),
+                    Default::default(),
+                    FileFormat::default(),
+                    cx,
+                );
                 cx.background_executor().spawn(async move {
                     task.await?;
                     Ok(())
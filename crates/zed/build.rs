@@ -23,6 +23,11 @@ fn main() {
         println!("cargo:rustc-link-arg=-Wl,-ObjC");
     }
 
+    // Reported by `zed --print-version-json`.
+    if let Ok(target) = std::env::var("TARGET") {
+        println!("cargo:rustc-env=ZED_TARGET={target}");
+    }
+
     // Populate git sha environment variable if git is available
     println!("cargo:rerun-if-changed=../../.git/logs/HEAD");
     if let Ok(output) = Command::new("git").args(["rev-parse", "HEAD"]).output() {
@@ -4,24 +4,29 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod reliability;
+mod startup_phases;
 mod zed;
 
 use anyhow::{anyhow, Context as _, Result};
 use assistant::PromptBuilder;
 use chrono::Offset;
-use clap::{command, Parser};
+use clap::{command, Parser, ValueEnum};
 use cli::FORCE_CLI_MODE_ENV_VAR_NAME;
-use client::{parse_zed_link, Client, DevServerToken, ProxySettings, UserStore};
+use client::{
+    parse_zed_link, Client, ClientSettings, DevServerToken, ProxySettings, TelemetrySettings,
+    UserStore,
+};
 use collab_ui::channel_view::ChannelView;
-use db::kvp::{GLOBAL_KEY_VALUE_STORE, KEY_VALUE_STORE};
+use db::kvp::{DataMigration, KeyValueStore, GLOBAL_KEY_VALUE_STORE, KEY_VALUE_STORE};
 use editor::Editor;
 use env_logger::Builder;
-use fs::{Fs, RealFs};
-use futures::{future, StreamExt};
+use extension::ExtensionStore;
+use fs::{Encoding, Fs, RealFs};
+use futures::{channel::mpsc, future, StreamExt};
 use git::GitHostingProviderRegistry;
 use gpui::{
-    Action, App, AppContext, AsyncAppContext, Context, DismissEvent, Global, Task,
-    UpdateGlobal as _, VisualContext,
+    font, Action, App, AppContext, AsyncAppContext, Context, DismissEvent, Global, Pixels,
+    SharedString, Task, UpdateGlobal as _, ViewContext, VisualContext,
 };
 use http_client::{read_proxy_from_env, Uri};
 use isahc_http_client::IsahcHttpClient;
@@ -32,37 +37,50 @@ use assets::Assets;
 use node_runtime::{NodeBinaryOptions, NodeRuntime};
 use parking_lot::Mutex;
 use project::project_settings::ProjectSettings;
-use recent_projects::open_ssh_project;
-use release_channel::{AppCommitSha, AppVersion};
+use recent_projects::{open_ssh_project, RecentProjects};
+use release_channel::{AppCommitSha, AppVersion, RELEASE_CHANNEL};
 use session::{AppSession, Session};
 use settings::{
-    handle_settings_file_changes, watch_config_file, InvalidSettingsError, Settings, SettingsStore,
+    handle_settings_file_changes, watch_config_file, InvalidSettingsError, KeymapConflict,
+    Settings, SettingsStore,
 };
 use simplelog::ConfigBuilder;
 use smol::process::Command;
 use std::{
+    cell::Cell,
     env,
-    fs::OpenOptions,
-    io::{IsTerminal, Write},
+    fs::{File, OpenOptions},
+    io::{IsTerminal, Read, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
     process,
-    sync::Arc,
+    rc::Rc,
+    sync::{Arc, OnceLock},
+    time::{Duration, Instant},
 };
 use theme::{ActiveTheme, SystemAppearance, ThemeRegistry, ThemeSettings};
 use time::UtcOffset;
-use util::{maybe, parse_env_output, ResultExt, TryFutureExt};
+use util::{
+    maybe, parse_env_file, parse_env_output, paths::PathWithPosition, ResultExt, TryFutureExt,
+};
 use uuid::Uuid;
-use welcome::{show_welcome_view, BaseKeymap, FIRST_OPEN};
+use vim::VimModeSetting;
+use welcome::{
+    should_show_welcome_view, show_welcome_view, BaseKeymap, WelcomeChoice, WelcomeChoices,
+};
 use workspace::{
+    dock::DockPosition,
     notifications::{simple_message_notification::MessageNotification, NotificationId},
-    AppState, WorkspaceSettings, WorkspaceStore,
+    AppState, StartupView, Workspace, WorkspaceSettings, WorkspaceStore,
 };
 use zed::{
-    app_menus, build_window_options, handle_cli_connection, handle_keymap_file_changes,
-    initialize_workspace, open_paths_with_positions, OpenListener, OpenRequest,
+    app_menus, build_window_options, diff_against_git_ref, handle_cli_connection,
+    handle_keymap_file_changes, initialize_workspace, open_paths_with_positions, parse_dock_size,
+    set_file_format_overrides, watch_themes, OpenListener, OpenRequest, StartupLayout, StartupPanel,
+    StartupSplit,
 };
 
 use crate::zed::inline_completion_registry;
+use crate::zed::remote_keymap::RemoteKeymap;
 
 #[cfg(feature = "mimalloc")]
 #[global_allocator]
@@ -134,29 +152,80 @@ fn fail_to_open_window(e: anyhow::Error, _cx: &mut AppContext) {
     }
 }
 
+/// What this process runs as, decided by whichever of `init_ui` and `init_headless` runs first.
+/// The subsystems they initialize can't coexist, so the other one fails afterwards.
 enum AppMode {
+    /// Serving projects as a dev server, see `--dev-server-token`.
     Headless(DevServerToken),
+    /// Showing workspace windows.
     Ui,
 }
+
 impl Global for AppMode {}
 
+impl AppMode {
+    fn is_ui(cx: &AppContext) -> bool {
+        matches!(cx.try_global::<Self>(), Some(Self::Ui))
+    }
+
+    /// Switches the app into `mode`. Returns false if it's already in that mode, in which case
+    /// initialization has already happened and shouldn't run again, e.g. for a second open
+    /// request.
+    fn enter(mode: Self, cx: &mut AppContext) -> Result<bool> {
+        match (cx.try_global::<Self>(), &mode) {
+            (None, _) => {
+                cx.set_global(mode);
+                Ok(true)
+            }
+            (Some(Self::Ui), Self::Ui) => Ok(false),
+            (Some(Self::Headless(token)), Self::Headless(new_token)) if token == new_token => {
+                Ok(false)
+            }
+            (Some(Self::Headless(_)), _) => Err(anyhow!(
+                "zed is already running in headless mode. Use `kill {}` to stop it",
+                process::id()
+            )),
+            (Some(Self::Ui), _) => Err(anyhow!(
+                "zed is already running. Use `kill {}` to stop it",
+                process::id()
+            )),
+        }
+    }
+}
+
+/// Present when Zed was launched with `--safe-mode`.
+struct SafeMode;
+
+impl Global for SafeMode {}
+
+/// Present when Zed was launched with `--no-restore`.
+struct NoRestore;
+
+impl Global for NoRestore {}
+
+/// The font families from the settings that were last validated, see `validate_configured_fonts`.
+#[derive(Default)]
+struct ConfiguredFonts {
+    /// The UI and buffer font families as configured.
+    configured: Option<[SharedString; 2]>,
+    /// The UI and buffer font families in use, with the missing ones replaced.
+    applied: Option<[SharedString; 2]>,
+    /// The missing families to notify about once a workspace opens.
+    pending_notification: Option<Vec<SharedString>>,
+}
+
+impl Global for ConfiguredFonts {}
+
 fn init_headless(
     dev_server_token: DevServerToken,
     app_state: Arc<AppState>,
     cx: &mut AppContext,
 ) -> Task<Result<()>> {
-    match cx.try_global::<AppMode>() {
-        Some(AppMode::Headless(token)) if token == &dev_server_token => return Task::ready(Ok(())),
-        Some(_) => {
-            return Task::ready(Err(anyhow!(
-                "zed is already running. Use `kill {}` to stop it",
-                process::id()
-            )))
-        }
-        None => {
-            cx.set_global(AppMode::Headless(dev_server_token.clone()));
-        }
-    };
+    match AppMode::enter(AppMode::Headless(dev_server_token.clone()), cx) {
+        Ok(true) => {}
+        Ok(false) => return Task::ready(Ok(())),
+        Err(error) => return Task::ready(Err(error)),
+    }
     let client = app_state.client.clone();
     client.set_dev_server_token(dev_server_token);
     headless::init(
@@ -204,14 +273,16 @@ fn init_common(app_state: Arc<AppState>, cx: &mut AppContext) -> Arc<PromptBuild
         app_state.client.telemetry().clone(),
         cx,
     );
-    extension::init(
-        app_state.fs.clone(),
-        app_state.client.clone(),
-        app_state.node_runtime.clone(),
-        app_state.languages.clone(),
-        ThemeRegistry::global(cx),
-        cx,
-    );
+    if !cx.has_global::<SafeMode>() {
+        extension::init(
+            app_state.fs.clone(),
+            app_state.client.clone(),
+            app_state.node_runtime.clone(),
+            app_state.languages.clone(),
+            ThemeRegistry::global(cx),
+            cx,
+        );
+    }
     prompt_builder
 }
 
@@ -220,20 +291,11 @@ fn init_ui(
     prompt_builder: Arc<PromptBuilder>,
     cx: &mut AppContext,
 ) -> Result<()> {
-    match cx.try_global::<AppMode>() {
-        Some(AppMode::Headless(_)) => {
-            return Err(anyhow!(
-                "zed is already running in headless mode. Use `kill {}` to stop it",
-                process::id()
-            ))
-        }
-        Some(AppMode::Ui) => return Ok(()),
-        None => {
-            cx.set_global(AppMode::Ui);
-        }
-    };
+    if !AppMode::enter(AppMode::Ui, cx)? {
+        return Ok(());
+    }
 
-    load_embedded_fonts(cx);
+    startup_phases::measure("load_embedded_fonts", || load_embedded_fonts(cx));
 
     #[cfg(target_os = "linux")]
     crate::zed::linux_prompts::init(cx);
@@ -271,23 +333,77 @@ fn init_ui(
     markdown_preview::init(cx);
     welcome::init(cx);
     settings_ui::init(cx);
-    extensions_ui::init(cx);
+
+    // Closing the last window quits on Linux unless the user opted out. Launching Zed again then
+    // reopens a window of the running instance, see `reopen_running_instance`.
+    #[cfg(target_os = "linux")]
+    {
+        cx.set_quit_when_last_window_closes(
+            !WorkspaceSettings::get_global(cx).keep_running_without_windows,
+        );
+        cx.observe_global::<SettingsStore>(|cx| {
+            cx.set_quit_when_last_window_closes(
+                !WorkspaceSettings::get_global(cx).keep_running_without_windows,
+            );
+        })
+        .detach();
+    }
+
+    settings::on_settings_ready(cx, validate_configured_fonts);
+    cx.observe_global::<SettingsStore>(validate_configured_fonts)
+        .detach();
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        let missing = cx
+            .try_global::<ConfiguredFonts>()
+            .and_then(|fonts| fonts.pending_notification.as_ref())
+            .cloned();
+        if let Some(missing) = missing {
+            cx.global_mut::<ConfiguredFonts>().pending_notification = None;
+            show_missing_fonts_notification(workspace, &missing, cx);
+        }
+    })
+    .detach();
+
+    let safe_mode = cx.has_global::<SafeMode>();
+    if safe_mode {
+        cx.observe_new_views(|workspace: &mut Workspace, cx| {
+            show_safe_mode_notification(workspace, cx)
+        })
+        .detach();
+    } else {
+        extensions_ui::init(cx);
+    }
+
+    if ClientSettings::get_global(cx).accept_invalid_server_certificate {
+        cx.observe_new_views(show_insecure_server_notification)
+            .detach();
+    }
 
     cx.observe_global::<SettingsStore>({
         let languages = app_state.languages.clone();
         let http = app_state.client.http_client();
         let client = app_state.client.clone();
+        let mut old_theme = cx.theme().clone();
+        let mut old_background_appearance = cx.theme().window_background_appearance();
 
         move |cx| {
-            for &mut window in cx.windows().iter_mut() {
-                let background_appearance = cx.theme().window_background_appearance();
-                window
-                    .update(cx, |_, cx| {
-                        cx.set_background_appearance(background_appearance)
-                    })
-                    .ok();
+            // Most settings changes affect neither of these, so avoid touching every window
+            // and re-highlighting every language on each one.
+            let new_background_appearance = cx.theme().window_background_appearance();
+            if new_background_appearance != old_background_appearance {
+                old_background_appearance = new_background_appearance;
+                for &mut window in cx.windows().iter_mut() {
+                    window
+                        .update(cx, |_, cx| {
+                            cx.set_background_appearance(new_background_appearance)
+                        })
+                        .ok();
+                }
+            }
+            if !Arc::ptr_eq(cx.theme(), &old_theme) {
+                old_theme = cx.theme().clone();
+                languages.set_theme(old_theme.clone());
             }
-            languages.set_theme(cx.theme().clone());
             let new_host = &client::ClientSettings::get_global(cx).server_url;
             if &http.base_url() != new_host {
                 http.set_base_url(new_host);
@@ -304,15 +420,32 @@ fn init_ui(
     telemetry.flush_events();
 
     let fs = app_state.fs.clone();
-    load_user_themes_in_background(fs.clone(), cx);
-    watch_themes(fs.clone(), cx);
+    if !safe_mode {
+        load_user_themes_in_background(fs.clone(), cx);
+        watch_themes(fs.clone(), paths::themes_dir(), cx);
+    }
     watch_languages(fs.clone(), app_state.languages.clone(), cx);
     watch_file_types(fs.clone(), cx);
+    // Paused watchers can't notice the setting being turned back on, so it only applies at
+    // startup, and `zed: toggle file watchers` resumes them.
+    settings::on_settings_ready(cx, |cx| {
+        if !WorkspaceSettings::get_global(cx).watch_config_files {
+            settings::set_file_watchers_paused(true);
+        }
+    });
 
     cx.set_menus(app_menus());
     initialize_workspace(app_state.clone(), prompt_builder, cx);
 
-    cx.activate(true);
+    // Extensions set themselves up once all of the built-in subsystems have been initialized.
+    if !safe_mode {
+        extension::run_extension_init_hooks(cx);
+    }
+
+    // With `--background`, the app is only brought forward once it's reopened.
+    if !cx.window_activation_suppressed() {
+        cx.activate(true);
+    }
 
     cx.spawn(|cx| async move { authenticate(app_state.client.clone(), &cx).await })
         .detach_and_log_err(cx);
@@ -321,24 +454,85 @@ fn init_ui(
 }
 
 fn main() {
+    let launch_time = Instant::now();
     menu::init();
     zed_actions::init();
 
+    let args = Args::parse();
+    if args.print_paths {
+        print_paths();
+        return;
+    }
+    if args.print_version_json {
+        print_version_json();
+        return;
+    }
+    if args.tail_log {
+        process::exit(tail_log());
+    }
+    if args.insecure {
+        client::accept_invalid_server_certificate();
+    }
+
     if let Err(e) = init_paths() {
         fail_to_launch(e);
         return;
     }
 
+    if args.health_check {
+        process::exit(health_check());
+    }
+    if args.quit {
+        process::exit(quit_running_instance());
+    }
+
     init_logger();
 
     log::info!("========== starting zed ==========");
+    for (legacy_dir, dir) in paths::legacy_xdg_dirs() {
+        log::warn!("using {legacy_dir:?}, since {dir:?} doesn't exist yet");
+    }
+
+    // When restarting, the previous instance still holds the single-instance lock until it exits.
+    if let Some(pid) = args.relaunch_after {
+        wait_for_process_exit(pid);
+    }
+
+    let renderer = args.renderer.or_else(|| {
+        let name = env::var("ZED_RENDERER").ok()?;
+        Renderer::from_str(&name, true)
+            .map_err(|error| log::error!("invalid ZED_RENDERER {name:?}: {error}"))
+            .ok()
+    });
+    if let Some(renderer) = renderer {
+        select_renderer(renderer);
+    }
+
+    let env_file_variables = match args.env_file.as_deref().map(load_env_file).transpose() {
+        Ok(variables) => variables.unwrap_or_default(),
+        Err(error) => {
+            eprintln!("{error:?}");
+            process::exit(1);
+        }
+    };
+    for (key, value) in &env_file_variables {
+        env::set_var(key, value);
+    }
 
     let app = App::new()
         .with_assets(Assets)
         .with_http_client(IsahcHttpClient::new(None, None));
 
     let system_id = app.background_executor().block(system_id()).ok();
-    let installation_id = app.background_executor().block(installation_id()).ok();
+    let installation_id = match app.background_executor().block(installation_id()) {
+        Ok(installation_id) => Some(installation_id),
+        Err(error) => {
+            // Without the id we can't tell whether this is the first launch, so this launch
+            // is reported as neither a first open nor an open.
+            log::error!("failed to read or create the installation id: {error:#}");
+            None
+        }
+    };
     let session_id = Uuid::new_v4().to_string();
     let session = app.background_executor().block(Session::new());
     let app_version = AppVersion::init(env!("CARGO_PKG_VERSION"));
@@ -352,10 +546,24 @@ fn main() {
 
     let (open_listener, mut open_rx) = OpenListener::new();
 
+    // These commands don't open any windows, so they can run next to another instance. Except
+    // for installing an extension, which would race with the running instance managing the
+    // extensions directory.
+    let is_extension_command = args.list_extensions || args.install_extension.is_some();
+    let is_command = is_extension_command || args.print_config;
+    if args.install_extension.is_some() && is_instance_running() {
+        eprintln!("Zed is running, quit it before installing extensions with --install-extension");
+        process::exit(1);
+    }
+
     #[cfg(target_os = "linux")]
-    {
+    if !is_command {
         if env::var("ZED_STATELESS").is_err() {
             if crate::zed::listen_for_cli_connections(open_listener.clone()).is_err() {
+                warn_about_args_ignored_by_running_instance(&args);
+                if args.paths_or_urls.is_empty() && zed::reopen_running_instance().is_ok() {
+                    return;
+                }
                 println!("zed is already running");
                 return;
             }
@@ -363,18 +571,20 @@ fn main() {
     }
 
     #[cfg(target_os = "windows")]
-    {
+    if !is_command {
         use zed::windows_only_instance::*;
         if !check_single_instance() {
+            warn_about_args_ignored_by_running_instance(&args);
             println!("zed is already running");
             return;
         }
     }
 
     #[cfg(target_os = "macos")]
-    {
+    if !is_command {
         use zed::mac_only_instance::*;
-        if ensure_only_instance() != IsOnlyInstance::Yes {
+        if ensure_only_instance(open_listener.clone()) != IsOnlyInstance::Yes {
+            warn_about_args_ignored_by_running_instance(&args);
             println!("zed is already running");
             return;
         }
@@ -395,25 +605,62 @@ fn main() {
         git_hosting_provider_registry.clone(),
         git_binary_path,
     ));
-    let user_settings_file_rx = watch_config_file(
-        &app.background_executor(),
-        fs.clone(),
-        paths::settings_file().clone(),
-    );
-    let user_keymap_file_rx = watch_config_file(
-        &app.background_executor(),
-        fs.clone(),
-        paths::keymap_file().clone(),
-    );
+    if args.reset_settings {
+        if let Err(error) = reset_settings_file() {
+            eprintln!("{error:?}");
+            process::exit(1);
+        }
+    }
+
+    let mut remote_keymap = None;
+    let (user_settings_file_rx, user_keymap_file_rx) = if args.safe_mode {
+        log::info!("starting in safe mode, ignoring user settings, keymap, themes and extensions");
+        (empty_config_file(), empty_config_file())
+    } else {
+        let user_keymap_file_rx = match args.keymap_url.clone() {
+            Some(url) => {
+                let (keymap, keymap_rx) = RemoteKeymap::new(url);
+                remote_keymap = Some(keymap);
+                keymap_rx
+            }
+            None => watch_config_file(
+                &app.background_executor(),
+                fs.clone(),
+                paths::keymap_file().clone(),
+            ),
+        };
+        (
+            watch_config_file(
+                &app.background_executor(),
+                fs.clone(),
+                paths::settings_file().clone(),
+            ),
+            user_keymap_file_rx,
+        )
+    };
 
     if !stdout_is_a_pty() {
+        let shell_env_dir = if args.shell_env_from_project {
+            first_path_dir(&args.paths_or_urls)
+        } else {
+            None
+        };
         app.background_executor()
-            .spawn(async {
+            .spawn(async move {
                 #[cfg(unix)]
                 {
                     load_shell_from_passwd().await.log_err();
                 }
-                load_login_shell_environment().await.log_err();
+                startup_phases::measure_async(
+                    "load_login_shell_environment",
+                    load_login_shell_environment(shell_env_dir),
+                )
+                .await
+                .log_err();
+                // Variables from `--env-file` take precedence over the login shell's.
+                for (key, value) in env_file_variables {
+                    env::set_var(key, value);
+                }
             })
             .detach()
     };
@@ -425,33 +672,36 @@ fn main() {
     app.on_reopen(move |cx| {
         if let Some(app_state) = AppState::try_global(cx).and_then(|app_state| app_state.upgrade())
         {
-            let ui_has_launched = cx
-                .try_global::<AppMode>()
-                .map(|mode| matches!(mode, AppMode::Ui))
-                .unwrap_or(false);
-
-            if ui_has_launched {
-                cx.spawn({
-                    let app_state = app_state.clone();
-                    |mut cx| async move {
-                        if let Err(e) = restore_or_create_workspace(app_state, &mut cx).await {
-                            fail_to_open_window_async(e, &mut cx)
-                        }
-                    }
-                })
-                .detach();
+            if AppMode::is_ui(cx) {
+                cx.activate(true);
+                reopen_workspace(app_state, cx);
             }
         }
     });
 
     app.run(move |cx| {
+        if args.safe_mode {
+            cx.set_global(SafeMode);
+        }
+        if args.background {
+            cx.suppress_window_activation();
+        }
+        if args.no_restore {
+            cx.set_global(NoRestore);
+        }
         release_channel::init(app_version, cx);
         if let Some(build_sha) = option_env!("ZED_COMMIT_SHA") {
             AppCommitSha::set_global(AppCommitSha(build_sha.into()), cx);
         }
         settings::init(cx);
+        cx.background_executor()
+            .block(KEY_VALUE_STORE.run_data_migrations(DATA_MIGRATIONS))
+            .log_err();
         handle_settings_file_changes(user_settings_file_rx, cx, handle_settings_changed);
         handle_keymap_file_changes(user_keymap_file_rx, cx, handle_keymap_changed);
+        if args.print_config {
+            process::exit(print_config(cx));
+        }
         client::init_settings(cx);
         let user_agent = format!(
             "Zed/{} ({}; {})",
@@ -469,7 +719,18 @@ fn main() {
                     .ok()
             })
             .or_else(read_proxy_from_env);
-        let http = IsahcHttpClient::new(proxy_url, Some(user_agent));
+        let client_settings = ClientSettings::get_global(cx);
+        let insecure_host = client_settings
+            .accept_invalid_server_certificate
+            .then(|| client_settings.server_url.parse::<Uri>().ok())
+            .flatten()
+            .and_then(|uri| uri.host().map(str::to_owned));
+        let http = if let Some(host) = insecure_host {
+            log::warn!("not verifying the TLS certificate of {host}");
+            IsahcHttpClient::new_insecure_for_host(proxy_url, Some(user_agent), host)
+        } else {
+            IsahcHttpClient::new(proxy_url, Some(user_agent))
+        };
         cx.set_http_client(http);
 
         <dyn Fs>::set_global(fs.clone(), cx);
@@ -481,6 +742,9 @@ fn main() {
 
         let client = Client::production(cx);
         cx.set_http_client(client.http_client().clone());
+        if let Some(remote_keymap) = remote_keymap {
+            remote_keymap.start(cx);
+        }
         let mut languages = LanguageRegistry::new(cx.background_executor().clone());
         languages.set_language_server_download_dir(paths::languages_dir().clone());
         let languages = Arc::new(languages);
@@ -529,6 +793,7 @@ fn main() {
             session_id,
             cx,
         );
+        startup_phases::report_to_telemetry(telemetry.clone());
         if let (Some(system_id), Some(installation_id)) = (&system_id, &installation_id) {
             match (&system_id, &installation_id) {
                 (IdType::New(_), IdType::New(_)) => {
@@ -564,8 +829,26 @@ fn main() {
             cx,
         );
         let prompt_builder = init_common(app_state.clone(), cx);
+        if is_extension_command {
+            run_extension_command(&args, cx);
+            return;
+        }
+        zed::unsaved_changes::init(app_state.fs.clone(), cx);
+        apply_launch_overrides(&args, app_state.fs.clone(), cx);
+        report_ready_after_first_draw(launch_time, cx);
+        report_time_to_interactive(launch_time, cx);
+        if args.dev_server_token.is_none() && !args.background {
+            zed::splash::open_splash_window(cx);
+        }
+
+        // The flags below take precedence over the settings for the files they're given.
+        set_file_format_overrides(
+            app_state.fs.as_ref(),
+            &args.paths_or_urls,
+            args.encoding,
+            args.line_endings,
+        );
 
-        let args = Args::parse();
         let urls: Vec<_> = args
             .paths_or_urls
             .iter()
@@ -582,7 +865,17 @@ fn main() {
             .flatten()
             .and_then(|urls| OpenRequest::parse(urls, cx).log_err())
         {
-            Some(request) => {
+            Some(mut request) => {
+                request.focus_panel = args.panel;
+                request.run_action = args.command.clone();
+                request.show_blame = args.blame;
+                request.diff_git_ref = args.diff_git.clone();
+                request.read_only = args.read_only;
+                request.split = args.split;
+                request.layout = StartupLayout {
+                    split_ratios: args.split_ratios.clone(),
+                    dock_sizes: args.dock_size.clone(),
+                };
                 handle_open_request(request, app_state.clone(), prompt_builder.clone(), cx);
             }
             None => {
@@ -599,11 +892,48 @@ fn main() {
                     })
                     .detach();
                 } else {
-                    init_ui(app_state.clone(), prompt_builder.clone(), cx).unwrap();
+                    startup_phases::measure("init_ui", || {
+                        init_ui(app_state.clone(), prompt_builder.clone(), cx)
+                    })
+                    .unwrap();
                     cx.spawn({
                         let app_state = app_state.clone();
+                        let focus_panel = args.panel;
+                        let layout = StartupLayout {
+                            split_ratios: args.split_ratios.clone(),
+                            dock_sizes: args.dock_size.clone(),
+                        };
+                        let run_action = args.command.clone();
                         |mut cx| async move {
-                            if let Err(e) = restore_or_create_workspace(app_state, &mut cx).await {
+                            let result = maybe!(async {
+                                cx.update(|cx| {
+                                    zed::splash::set_splash_status("Restoring workspaces…", cx)
+                                })?;
+                                startup_phases::measure_async(
+                                    "restore_workspaces",
+                                    restore_or_create_workspace(app_state.clone(), &mut cx),
+                                )
+                                .await?;
+                                if let Some(panel) = focus_panel {
+                                    focus_panel_in_active_workspace(
+                                        panel,
+                                        app_state.clone(),
+                                        &mut cx,
+                                    )
+                                    .await?;
+                                }
+                                if !layout.is_empty() {
+                                    apply_startup_layout(layout, app_state.clone(), &mut cx)
+                                        .await?;
+                                }
+                                if let Some(action_name) = run_action {
+                                    run_action_in_active_workspace(action_name, app_state, &mut cx)
+                                        .await?;
+                                }
+                                anyhow::Ok(())
+                            })
+                            .await;
+                            if let Err(e) = result {
                                 fail_to_open_window_async(e, &mut cx)
                             }
                         }
@@ -629,127 +959,527 @@ fn main() {
     });
 }
 
-fn handle_keymap_changed(error: Option<anyhow::Error>, cx: &mut AppContext) {
-    struct KeymapParseErrorNotification;
-    let id = NotificationId::unique::<KeymapParseErrorNotification>();
-
-    for workspace in workspace::local_workspace_windows(cx) {
-        workspace
-            .update(cx, |workspace, cx| match &error {
-                Some(error) => {
-                    workspace.show_notification(id.clone(), cx, |cx| {
-                        cx.new_view(|_| {
-                            MessageNotification::new(format!("Invalid keymap file\n{error}"))
-                                .with_click_message("Open keymap file")
-                                .on_click(|cx| {
-                                    cx.dispatch_action(zed_actions::OpenKeymap.boxed_clone());
-                                    cx.emit(DismissEvent);
-                                })
-                        })
-                    });
+/// Runs `--list-extensions` or `--install-extension`, then exits with a non-zero
+/// status if it failed.
+fn run_extension_command(args: &Args, cx: &mut AppContext) {
+    let store = ExtensionStore::global(cx);
+    let install_extension = args.install_extension.clone();
+    cx.spawn(|mut cx| async move {
+        let result = maybe!(async {
+            match install_extension {
+                Some(extension_id) => {
+                    store
+                        .update(&mut cx, |store, cx| {
+                            store.install_latest_extension_task(extension_id.as_str().into(), cx)
+                        })?
+                        .await?;
+                    println!("Installed {extension_id}");
                 }
-                None => workspace.dismiss_notification(&id, cx),
-            })
-            .log_err();
-    }
-}
-
-fn handle_settings_changed(error: Option<anyhow::Error>, cx: &mut AppContext) {
-    struct SettingsParseErrorNotification;
-    let id = NotificationId::unique::<SettingsParseErrorNotification>();
-
-    for workspace in workspace::local_workspace_windows(cx) {
-        workspace
-            .update(cx, |workspace, cx| {
-                match error.as_ref() {
-                    Some(error) => {
-                        if let Some(InvalidSettingsError::LocalSettings { .. }) =
-                            error.downcast_ref::<InvalidSettingsError>()
-                        {
-                            // Local settings will be displayed by the projects
-                        } else {
-                            workspace.show_notification(id.clone(), cx, |cx| {
-                                cx.new_view(|_| {
-                                    MessageNotification::new(format!(
-                                        "Invalid user settings file\n{error}"
-                                    ))
-                                    .with_click_message("Open settings file")
-                                    .on_click(|cx| {
-                                        cx.dispatch_action(zed_actions::OpenSettings.boxed_clone());
-                                        cx.emit(DismissEvent);
-                                    })
-                                })
-                            });
-                        }
+                None => store.update(&mut cx, |store, _| {
+                    for (extension_id, entry) in store.installed_extensions() {
+                        let dev = if entry.dev { " (dev)" } else { "" };
+                        println!("{extension_id}@{}{dev}", entry.manifest.version);
                     }
-                    None => workspace.dismiss_notification(&id, cx),
-                }
-            })
-            .log_err();
-    }
+                })?,
+            }
+            anyhow::Ok(())
+        })
+        .await;
+
+        match result {
+            Ok(()) => process::exit(0),
+            Err(error) => {
+                eprintln!("{error:#}");
+                process::exit(1);
+            }
+        }
+    })
+    .detach();
 }
 
-fn handle_open_request(
-    request: OpenRequest,
-    app_state: Arc<AppState>,
-    prompt_builder: Arc<PromptBuilder>,
-    cx: &mut AppContext,
-) {
-    if let Some(connection) = request.cli_connection {
-        let app_state = app_state.clone();
-        cx.spawn(move |cx| handle_cli_connection(connection, app_state, prompt_builder, cx))
+/// Applies `--zen`, `--theme` and `--keymap` for this session, without persisting them to the
+/// settings.
+///
+/// Settings changes, user themes finishing loading and system appearance changes all
+/// recompute the theme and base keymap from the settings, so the overrides are reapplied
+/// whenever the settings store changes.
+fn apply_launch_overrides(args: &Args, fs: Arc<dyn Fs>, cx: &mut AppContext) {
+    if args.zen {
+        cx.observe_new_views(|workspace: &mut Workspace, cx| workspace.set_zen_mode(true, cx))
             .detach();
-        return;
     }
 
-    if let Err(e) = init_ui(app_state.clone(), prompt_builder, cx) {
-        fail_to_open_window(e, cx);
-        return;
-    };
-
-    if let Some(connection_info) = request.ssh_connection {
-        cx.spawn(|mut cx| async move {
-            open_ssh_project(
-                connection_info,
-                request
-                    .open_paths
-                    .into_iter()
-                    .map(|path| path.path)
-                    .collect::<Vec<_>>(),
-                app_state,
-                workspace::OpenOptions::default(),
-                &mut cx,
-            )
-            .await
+    if let Some(theme_name) = args.theme.clone() {
+        if ThemeSettings::set_active_theme(&theme_name, cx).is_err() {
+            validate_launch_theme(theme_name.clone(), fs, args.safe_mode, cx);
+        }
+        cx.observe_global::<SettingsStore>(move |cx| {
+            if cx.theme().name != theme_name {
+                ThemeSettings::set_active_theme(&theme_name, cx).ok();
+            }
         })
-        .detach_and_log_err(cx);
-        return;
+        .detach();
     }
 
-    let mut task = None;
-    if !request.open_paths.is_empty() {
-        let app_state = app_state.clone();
-        task = Some(cx.spawn(|mut cx| async move {
-            let (_window, results) = open_paths_with_positions(
-                &request.open_paths,
-                app_state,
-                workspace::OpenOptions::default(),
-                &mut cx,
-            )
-            .await?;
-            for result in results.into_iter().flatten() {
-                if let Err(err) = result {
-                    log::error!("Error opening path: {err}",);
-                }
+    if let Some(keymap_name) = &args.keymap {
+        let Some(base_keymap) = BaseKeymap::from_name(keymap_name) else {
+            log::warn!(
+                "--keymap: unknown base keymap {keymap_name:?}, using the configured keymap"
+            );
+            return;
+        };
+        BaseKeymap::override_global(base_keymap, cx);
+        cx.observe_global::<SettingsStore>(move |cx| {
+            if *BaseKeymap::get_global(cx) != base_keymap {
+                BaseKeymap::override_global(base_keymap, cx);
             }
-            anyhow::Ok(())
-        }));
+        })
+        .detach();
     }
+}
 
-    if !request.open_channel_notes.is_empty() || request.join_channel.is_some() {
-        cx.spawn(|mut cx| async move {
-            let result = maybe!(async {
-                if let Some(task) = task {
+/// Reports a `--theme` that names neither a bundled theme nor one in the themes directory.
+///
+/// User themes are still loading in the background at this point, so their files are read
+/// directly rather than waiting for the registry.
+fn validate_launch_theme(
+    theme_name: String,
+    fs: Arc<dyn Fs>,
+    safe_mode: bool,
+    cx: &mut AppContext,
+) {
+    cx.spawn(|mut cx| async move {
+        let mut user_themes = Vec::new();
+        if !safe_mode {
+            if let Ok(mut theme_paths) = fs.read_dir(paths::themes_dir()).await {
+                while let Some(theme_path) = theme_paths.next().await {
+                    let Ok(theme_path) = theme_path else {
+                        continue;
+                    };
+                    if let Ok(family) =
+                        ThemeRegistry::read_user_theme(&theme_path, fs.clone()).await
+                    {
+                        user_themes.extend(family.themes.into_iter().map(|theme| theme.name));
+                    }
+                }
+            }
+        }
+        if user_themes.contains(&theme_name) {
+            return;
+        }
+
+        cx.update(|cx| {
+            // Extensions may have registered it in the meantime.
+            if ThemeRegistry::global(cx).get(&theme_name).is_ok() {
+                return;
+            }
+            eprintln!("--theme: unknown theme {theme_name:?}, using the configured theme");
+            let workspaces = workspace::local_workspace_windows(cx);
+            if workspaces.is_empty() {
+                let reported = Rc::new(Cell::new(false));
+                cx.observe_new_views(move |workspace: &mut Workspace, cx| {
+                    if !reported.replace(true) {
+                        show_unknown_theme_notification(workspace, &theme_name, cx);
+                    }
+                })
+                .detach();
+            }
+            for workspace in workspaces {
+                workspace
+                    .update(cx, |workspace, cx| {
+                        show_unknown_theme_notification(workspace, &theme_name, cx)
+                    })
+                    .log_err();
+            }
+        })
+        .log_err();
+    })
+    .detach();
+}
+
+fn show_unknown_theme_notification(
+    workspace: &mut Workspace,
+    theme_name: &str,
+    cx: &mut ViewContext<Workspace>,
+) {
+    struct UnknownTheme;
+
+    let message = format!("There is no theme named \"{theme_name}\", using the configured theme.");
+    workspace.show_notification(NotificationId::unique::<UnknownTheme>(), cx, |cx| {
+        cx.new_view(|_| {
+            MessageNotification::new(message)
+                .with_click_message("Select a theme")
+                .on_click(|cx| {
+                    cx.dispatch_action(theme_selector::Toggle::default().boxed_clone());
+                    cx.emit(DismissEvent);
+                })
+        })
+    });
+}
+
+/// Stands in for a config file in safe mode, so that only the defaults apply.
+fn empty_config_file() -> mpsc::UnboundedReceiver<String> {
+    let (tx, rx) = mpsc::unbounded();
+    tx.unbounded_send(String::new()).ok();
+    rx
+}
+
+fn show_safe_mode_notification(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    workspace.show_notification(NotificationId::unique::<SafeMode>(), cx, |cx| {
+        cx.new_view(|_| {
+            MessageNotification::new(
+                "Zed is running in safe mode: user settings, keymap, themes and extensions are disabled.",
+            )
+        })
+    });
+}
+
+fn show_insecure_server_notification(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    struct InsecureServer;
+
+    let server_url = ClientSettings::get_global(cx).server_url.clone();
+    workspace.show_notification(NotificationId::unique::<InsecureServer>(), cx, |cx| {
+        cx.new_view(|_| {
+            MessageNotification::new(format!(
+                "The TLS certificate of {server_url} isn't verified, so anyone on the network \
+                could impersonate it. Only use this to evaluate a self-hosted server."
+            ))
+        })
+    });
+}
+
+/// Replaces the configured UI and buffer font families with the bundled fonts when they aren't
+/// available, and notifies about it, rather than silently rendering with another font.
+fn validate_configured_fonts(cx: &mut AppContext) {
+    const FALLBACK_FAMILIES: [&str; 2] = ["Zed Plex Sans", "Zed Plex Mono"];
+
+    let theme_settings = ThemeSettings::get_global(cx);
+    let configured = [
+        theme_settings.ui_font.family.clone(),
+        theme_settings.buffer_font.family.clone(),
+    ];
+    let fonts = cx.default_global::<ConfiguredFonts>();
+    // Replacing the families below notifies this observer again.
+    if fonts.applied.as_ref() == Some(&configured) {
+        return;
+    }
+    // Reloading other settings restores the configured families, which only need replacing again.
+    let notify = fonts.configured.as_ref() != Some(&configured);
+
+    let available = cx.text_system().all_font_names();
+    let mut missing = Vec::new();
+    let mut applied = configured.clone();
+    for (family, fallback) in applied.iter_mut().zip(FALLBACK_FAMILIES) {
+        if !available
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(family))
+        {
+            log::warn!("font family {family:?} isn't available, using {fallback:?} instead");
+            if !missing.contains(family) {
+                missing.push(family.clone());
+            }
+            *family = fallback.into();
+        }
+    }
+
+    let fonts = cx.global_mut::<ConfiguredFonts>();
+    fonts.configured = Some(configured.clone());
+    fonts.applied = Some(applied.clone());
+    if applied != configured {
+        let mut theme_settings = ThemeSettings::get_global(cx).clone();
+        theme_settings.ui_font.family = applied[0].clone();
+        theme_settings.buffer_font.family = applied[1].clone();
+        ThemeSettings::override_global(theme_settings, cx);
+    }
+    if !notify {
+        return;
+    }
+
+    let workspaces = workspace::local_workspace_windows(cx);
+    if workspaces.is_empty() {
+        cx.global_mut::<ConfiguredFonts>().pending_notification =
+            (!missing.is_empty()).then_some(missing);
+        return;
+    }
+    for workspace in workspaces {
+        workspace
+            .update(cx, |workspace, cx| {
+                if missing.is_empty() {
+                    workspace
+                        .dismiss_notification(&NotificationId::unique::<ConfiguredFonts>(), cx);
+                } else {
+                    show_missing_fonts_notification(workspace, &missing, cx);
+                }
+            })
+            .log_err();
+    }
+}
+
+fn show_missing_fonts_notification(
+    workspace: &mut Workspace,
+    missing: &[SharedString],
+    cx: &mut ViewContext<Workspace>,
+) {
+    let families = missing
+        .iter()
+        .map(|family| format!("\"{family}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    workspace.show_notification(NotificationId::unique::<ConfiguredFonts>(), cx, |cx| {
+        cx.new_view(|_| {
+            MessageNotification::new(format!(
+                "Couldn't find font {families}, using the bundled fonts instead."
+            ))
+            .with_click_message("Open settings file")
+            .on_click(|cx| {
+                cx.dispatch_action(zed_actions::OpenSettings.boxed_clone());
+                cx.emit(DismissEvent);
+            })
+        })
+    });
+}
+
+fn handle_keymap_changed(
+    error: Option<anyhow::Error>,
+    conflicts: Vec<KeymapConflict>,
+    cx: &mut AppContext,
+) {
+    struct KeymapParseErrorNotification;
+    struct KeymapConflictNotification;
+    let id = NotificationId::unique::<KeymapParseErrorNotification>();
+    let conflict_id = NotificationId::unique::<KeymapConflictNotification>();
+
+    for conflict in &conflicts {
+        log::warn!("keymap conflict: {conflict}");
+    }
+    let conflicts_message = (!conflicts.is_empty()).then(|| {
+        let conflicts = conflicts
+            .iter()
+            .map(|conflict| conflict.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("Conflicting bindings in keymap file, the later ones take precedence\n{conflicts}")
+    });
+
+    for workspace in workspace::local_workspace_windows(cx) {
+        workspace
+            .update(cx, |workspace, cx| {
+                match &error {
+                    Some(error) => {
+                        workspace.show_notification(id.clone(), cx, |cx| {
+                            cx.new_view(|_| {
+                                MessageNotification::new(format!("Invalid keymap file\n{error}"))
+                                    .with_click_message("Open keymap file")
+                                    .on_click(|cx| {
+                                        cx.dispatch_action(zed_actions::OpenKeymap.boxed_clone());
+                                        cx.emit(DismissEvent);
+                                    })
+                            })
+                        });
+                    }
+                    None => workspace.dismiss_notification(&id, cx),
+                }
+
+                match &conflicts_message {
+                    Some(message) => {
+                        workspace.show_notification(conflict_id.clone(), cx, |cx| {
+                            cx.new_view(|_| {
+                                MessageNotification::new(message.clone())
+                                    .with_click_message("Open keymap file")
+                                    .on_click(|cx| {
+                                        cx.dispatch_action(zed_actions::OpenKeymap.boxed_clone());
+                                        cx.emit(DismissEvent);
+                                    })
+                            })
+                        });
+                    }
+                    None if error.is_none() => workspace.dismiss_notification(&conflict_id, cx),
+                    None => {}
+                }
+            })
+            .log_err();
+    }
+}
+
+fn handle_settings_changed(error: Option<anyhow::Error>, cx: &mut AppContext) {
+    struct SettingsParseErrorNotification;
+    let id = NotificationId::unique::<SettingsParseErrorNotification>();
+
+    for workspace in workspace::local_workspace_windows(cx) {
+        workspace
+            .update(cx, |workspace, cx| {
+                match error.as_ref() {
+                    Some(error) => {
+                        if let Some(InvalidSettingsError::LocalSettings { .. }) =
+                            error.downcast_ref::<InvalidSettingsError>()
+                        {
+                            // Local settings will be displayed by the projects
+                        } else {
+                            workspace.show_notification(id.clone(), cx, |cx| {
+                                cx.new_view(|_| {
+                                    MessageNotification::new(format!(
+                                        "Invalid user settings file\n{error}"
+                                    ))
+                                    .with_click_message("Open settings file")
+                                    .on_click(|cx| {
+                                        cx.dispatch_action(zed_actions::OpenSettings.boxed_clone());
+                                        cx.emit(DismissEvent);
+                                    })
+                                })
+                            });
+                        }
+                    }
+                    None => workspace.dismiss_notification(&id, cx),
+                }
+            })
+            .log_err();
+    }
+}
+
+fn handle_open_request(
+    mut request: OpenRequest,
+    app_state: Arc<AppState>,
+    prompt_builder: Arc<PromptBuilder>,
+    cx: &mut AppContext,
+) {
+    #[cfg(unix)]
+    if let Some(reply_path) = request.ping_reply_path {
+        zed::reply_to_ping(&reply_path);
+        return;
+    }
+
+    if request.quit {
+        cx.dispatch_action(&zed_actions::Quit);
+        return;
+    }
+
+    if let Some(connection) = request.cli_connection {
+        // Opening files from the CLI brings an app started with `--background` forward.
+        cx.activate(true);
+        let app_state = app_state.clone();
+        cx.spawn(move |cx| handle_cli_connection(connection, app_state, prompt_builder, cx))
+            .detach();
+        return;
+    }
+
+    if let Err(e) =
+        startup_phases::measure("init_ui", || init_ui(app_state.clone(), prompt_builder, cx))
+    {
+        fail_to_open_window(e, cx);
+        return;
+    };
+
+    if request.reopen {
+        cx.activate(true);
+        if workspace::activate_workspace_window_at(0, cx).is_none() {
+            reopen_workspace(app_state, cx);
+        }
+        return;
+    }
+
+    if let Some(connection_info) = request.ssh_connection {
+        cx.spawn(|mut cx| async move {
+            open_ssh_project(
+                connection_info,
+                request
+                    .open_paths
+                    .into_iter()
+                    .map(|path| path.path)
+                    .collect::<Vec<_>>(),
+                app_state,
+                workspace::OpenOptions::default(),
+                &mut cx,
+            )
+            .await
+        })
+        .detach_and_log_err(cx);
+        return;
+    }
+
+    let mut task = None;
+    if !request.open_paths.is_empty() {
+        let app_state = app_state.clone();
+        let show_blame = request.show_blame;
+        let diff_git_ref = request.diff_git_ref.take();
+        let split = request.split;
+        let open_options = workspace::OpenOptions {
+            read_only: request.read_only,
+            ..Default::default()
+        };
+        task = Some(cx.spawn(|mut cx| async move {
+            let (window, results) = open_paths_with_positions(
+                &request.open_paths,
+                &request.open_path_targets,
+                app_state,
+                open_options,
+                &mut cx,
+            )
+            .await?;
+            let mut opened_items = Vec::new();
+            for result in results.into_iter().flatten() {
+                match result {
+                    Ok(item) => {
+                        opened_items.push(item.boxed_clone());
+                        let Some(editor) = item.downcast::<Editor>() else {
+                            continue;
+                        };
+                        if show_blame {
+                            // Files outside of a git repository open normally, and blame shows
+                            // a notification explaining why it's unavailable.
+                            editor
+                                .update(&mut cx, |editor, cx| editor.set_show_git_blame(true, cx))
+                                .log_err();
+                        }
+                        if let Some(git_ref) = diff_git_ref.clone() {
+                            if let Err(err) =
+                                diff_against_git_ref(window, editor, git_ref, &mut cx).await
+                            {
+                                log::error!("Error diffing against git ref: {err}");
+                                window
+                                    .update(&mut cx, |workspace, cx| workspace.show_error(&err, cx))
+                                    .log_err();
+                            }
+                        }
+                    }
+                    Err(err) => log::error!("Error opening path: {err}",),
+                }
+            }
+            if let Some(split) = split {
+                window.update(&mut cx, |workspace, cx| {
+                    split.arrange(&opened_items, workspace, cx)
+                })?;
+            }
+            anyhow::Ok(())
+        }));
+    }
+
+    let focus_panel = request.focus_panel.take();
+    let layout = std::mem::take(&mut request.layout);
+    let run_action = request.run_action.take();
+    if focus_panel.is_some() || !layout.is_empty() || run_action.is_some() {
+        let app_state = app_state.clone();
+        let open_paths = task.take();
+        task = Some(cx.spawn(|mut cx| async move {
+            if let Some(open_paths) = open_paths {
+                open_paths.await?;
+            }
+            if let Some(panel) = focus_panel {
+                focus_panel_in_active_workspace(panel, app_state.clone(), &mut cx).await?;
+            }
+            if !layout.is_empty() {
+                apply_startup_layout(layout, app_state.clone(), &mut cx).await?;
+            }
+            if let Some(action_name) = run_action {
+                run_action_in_active_workspace(action_name, app_state, &mut cx).await?;
+            }
+            anyhow::Ok(())
+        }));
+    }
+
+    if !request.open_channel_notes.is_empty() || request.join_channel.is_some() {
+        cx.spawn(|mut cx| async move {
+            let result = maybe!(async {
+                if let Some(task) = task {
                     task.await?;
                 }
                 let client = app_state.client.clone();
@@ -804,6 +1534,81 @@ fn handle_open_request(
     }
 }
 
+/// Dispatches an action by name, the way the command palette does, once a workspace is open.
+async fn run_action_in_active_workspace(
+    action_name: String,
+    app_state: Arc<AppState>,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    let workspace_window = workspace::get_any_active_workspace(app_state, cx.clone()).await?;
+    workspace_window.update(cx, |workspace, cx| {
+        match cx.build_action(&action_name, None) {
+            Ok(action) => cx.dispatch_action(action),
+            Err(error) => {
+                let error =
+                    error.context(format!("Unknown action passed to --command: {action_name}"));
+                log::error!("{error:#}");
+                workspace.show_error(&error, cx);
+            }
+        }
+    })
+}
+
+/// Focuses the given panel in the active workspace, waiting for the panels to load.
+async fn focus_panel_in_active_workspace(
+    panel: StartupPanel,
+    app_state: Arc<AppState>,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    let workspace_window = workspace::get_any_active_workspace(app_state, cx.clone()).await?;
+    let start = Instant::now();
+    while !workspace_window.update(cx, |workspace, cx| panel.focus(workspace, cx))? {
+        if start.elapsed() > TIMEOUT {
+            return Err(anyhow!("timed out waiting for the {panel:?} panel to load"));
+        }
+        cx.background_executor().timer(POLL_INTERVAL).await;
+    }
+    Ok(())
+}
+
+/// Applies the pane and dock sizes given on the command line to the active workspace, waiting
+/// for the docks' panels to load.
+async fn apply_startup_layout(
+    layout: StartupLayout,
+    app_state: Arc<AppState>,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    let workspace_window = workspace::get_any_active_workspace(app_state, cx.clone()).await?;
+    if !layout.split_ratios.is_empty() {
+        workspace_window.update(cx, |workspace, cx| {
+            workspace.set_center_split_ratios(&layout.split_ratios, cx)
+        })??;
+    }
+    let mut pending_dock_sizes = layout.dock_sizes;
+    let start = Instant::now();
+    loop {
+        workspace_window.update(cx, |workspace, cx| {
+            pending_dock_sizes
+                .retain(|(position, size)| !workspace.resize_dock(*position, *size, cx))
+        })?;
+        let Some((position, _)) = pending_dock_sizes.first() else {
+            return Ok(());
+        };
+        if start.elapsed() > TIMEOUT {
+            return Err(anyhow!(
+                "timed out waiting for panels to load in the {position:?} dock"
+            ));
+        }
+        cx.background_executor().timer(POLL_INTERVAL).await;
+    }
+}
+
 async fn authenticate(client: Arc<Client>, cx: &AsyncAppContext) -> Result<()> {
     if stdout_is_a_pty() {
         if *client::ZED_DEVELOPMENT_AUTH {
@@ -833,20 +1638,57 @@ async fn system_id() -> Result<IdType> {
     Ok(IdType::New(system_id))
 }
 
-async fn installation_id() -> Result<IdType> {
-    let legacy_key_name = "device_id".to_string();
-    let key_name = "installation_id".to_string();
+const INSTALLATION_ID_KEY: &str = "installation_id";
+const LEGACY_INSTALLATION_ID_KEY: &str = "device_id";
+
+/// Migrations of the data in the key-value store, applied once each at startup.
+///
+/// Only ever append to this list.
+const DATA_MIGRATIONS: &[DataMigration] = &[DataMigration {
+    name: "rename device_id to installation_id",
+    run: |db| Box::pin(migrate_legacy_installation_id(db)),
+}];
 
-    // Migrate legacy key to new key
-    if let Ok(Some(installation_id)) = KEY_VALUE_STORE.read_kvp(&legacy_key_name) {
-        KEY_VALUE_STORE
-            .write_kvp(key_name, installation_id.clone())
+async fn migrate_legacy_installation_id(db: &KeyValueStore) -> Result<()> {
+    if let Some(installation_id) = db.read_kvp(LEGACY_INSTALLATION_ID_KEY)? {
+        db.write_kvp(INSTALLATION_ID_KEY.to_string(), installation_id)
             .await?;
-        KEY_VALUE_STORE.delete_kvp(legacy_key_name).await?;
+        db.delete_kvp(LEGACY_INSTALLATION_ID_KEY.to_string())
+            .await?;
+    }
+    Ok(())
+}
+
+const KVP_READ_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Reads a key from the key-value store, retrying once if the read fails, since the database
+/// can be briefly unavailable at startup (e.g. while another instance is shutting down).
+async fn read_kvp_with_retry(key: &str) -> Result<Option<String>> {
+    match KEY_VALUE_STORE.read_kvp(key) {
+        Ok(value) => Ok(value),
+        Err(error) => {
+            log::warn!("failed to read {key} from the database, retrying: {error:#}");
+            smol::Timer::after(KVP_READ_RETRY_DELAY).await;
+            KEY_VALUE_STORE
+                .read_kvp(key)
+                .with_context(|| format!("failed to read {key} from the database"))
+        }
+    }
+}
+
+/// Returns the id of this installation, creating it on the first launch.
+///
+/// A failed read is an error rather than a first launch: generating a new id would overwrite
+/// the existing one and report every later launch of this installation as a new one.
+async fn installation_id() -> Result<IdType> {
+    let key_name = INSTALLATION_ID_KEY.to_string();
+
+    if let Some(installation_id) = read_kvp_with_retry(&key_name).await? {
         return Ok(IdType::Existing(installation_id));
     }
 
-    if let Ok(Some(installation_id)) = KEY_VALUE_STORE.read_kvp(&key_name) {
+    // This runs before the data migrations, which move the legacy key to the new one.
+    if let Some(installation_id) = read_kvp_with_retry(LEGACY_INSTALLATION_ID_KEY).await? {
         return Ok(IdType::Existing(installation_id));
     }
 
@@ -859,28 +1701,90 @@ async fn installation_id() -> Result<IdType> {
     Ok(IdType::New(installation_id))
 }
 
+fn reopen_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
+    cx.spawn(|mut cx| async move {
+        if let Err(e) = restore_or_create_workspace(app_state, &mut cx).await {
+            fail_to_open_window_async(e, &mut cx)
+        }
+    })
+    .detach();
+}
+
+/// Applies the defaults chosen on the welcome page as soon as they're chosen, so that e.g. vim
+/// mode is on for the next keystroke rather than once the settings file has been reloaded.
+pub(crate) fn apply_welcome_choices(mut choices: WelcomeChoices, cx: &mut AppContext) {
+    cx.spawn(|mut cx| async move {
+        while let Some(choice) = choices.next().await {
+            log::info!("welcome page: chose {choice:?}");
+            cx.update(|cx| match choice {
+                WelcomeChoice::Theme(theme_name) => {
+                    ThemeSettings::set_active_theme(&theme_name, cx).log_err();
+                }
+                WelcomeChoice::BaseKeymap(base_keymap) => {
+                    BaseKeymap::override_global(base_keymap, cx);
+                }
+                WelcomeChoice::VimMode(enabled) => {
+                    VimModeSetting::override_global(VimModeSetting(enabled), cx);
+                }
+                WelcomeChoice::MetricTelemetry(enabled) => {
+                    let mut settings = *TelemetrySettings::get_global(cx);
+                    settings.metrics = enabled;
+                    TelemetrySettings::override_global(settings, cx);
+                }
+                WelcomeChoice::DiagnosticTelemetry(enabled) => {
+                    let mut settings = *TelemetrySettings::get_global(cx);
+                    settings.diagnostics = enabled;
+                    TelemetrySettings::override_global(settings, cx);
+                }
+            })
+            .ok();
+        }
+    })
+    .detach();
+}
+
 async fn restore_or_create_workspace(
     app_state: Arc<AppState>,
     cx: &mut AsyncAppContext,
 ) -> Result<()> {
     if let Some(locations) = restorable_workspace_locations(cx, &app_state).await {
+        let max_restored_workspaces =
+            cx.update(|cx| WorkspaceSettings::get_global(cx).max_restored_workspaces)?;
+        let (locations, deferred_locations) =
+            workspace::defer_restored_workspaces(locations, max_restored_workspaces);
         for location in locations {
             cx.update(|cx| {
                 workspace::open_paths(
                     location.paths().as_ref(),
                     app_state.clone(),
-                    workspace::OpenOptions::default(),
+                    workspace::OpenOptions {
+                        restoring: true,
+                        ..Default::default()
+                    },
                     cx,
                 )
             })?
             .await?;
         }
-    } else if matches!(KEY_VALUE_STORE.read_kvp(FIRST_OPEN), Ok(None)) {
-        cx.update(|cx| show_welcome_view(app_state, cx))?.await?;
+        if !deferred_locations.is_empty() {
+            cx.update(|cx| {
+                show_deferred_workspaces_notification(deferred_locations, app_state, cx)
+            })?;
+        }
+    } else if cx.update(should_show_welcome_view)? {
+        let choices = cx.update(|cx| show_welcome_view(app_state, cx))?.await?;
+        cx.update(|cx| apply_welcome_choices(choices, cx))?;
     } else {
+        let startup_view = cx.update(|cx| WorkspaceSettings::get(None, cx).startup_view)?;
         cx.update(|cx| {
-            workspace::open_new(Default::default(), app_state, cx, |workspace, cx| {
-                Editor::new_file(workspace, &Default::default(), cx)
+            workspace::open_new(Default::default(), app_state, cx, move |workspace, cx| {
+                match startup_view {
+                    StartupView::EmptyBuffer => {
+                        Editor::new_file(workspace, &Default::default(), cx)
+                    }
+                    StartupView::RecentProjects => RecentProjects::open(workspace, false, cx),
+                    StartupView::EmptyWorkspace => {}
+                }
             })
         })?
         .await?;
@@ -889,10 +1793,73 @@ async fn restore_or_create_workspace(
     Ok(())
 }
 
+/// Offers to open the workspaces from the last session that weren't restored because of
+/// the `max_restored_workspaces` setting.
+fn show_deferred_workspaces_notification(
+    locations: Vec<workspace::LocalPaths>,
+    app_state: Arc<AppState>,
+    cx: &mut AppContext,
+) {
+    struct DeferredWorkspacesNotification;
+
+    let Some(workspace) = cx
+        .active_window()
+        .and_then(|window| window.downcast::<Workspace>())
+        .or_else(|| workspace::local_workspace_windows(cx).last().copied())
+    else {
+        return;
+    };
+    let message = match locations.len() {
+        1 => "1 more window from your last session wasn't restored".to_string(),
+        count => format!("{count} more windows from your last session weren't restored"),
+    };
+    workspace
+        .update(cx, |workspace, cx| {
+            workspace.show_notification(
+                NotificationId::unique::<DeferredWorkspacesNotification>(),
+                cx,
+                |cx| {
+                    cx.new_view(|_| {
+                        MessageNotification::new(message)
+                            .with_click_message("Restore them")
+                            .on_click(move |cx| {
+                                let locations = locations.clone();
+                                let app_state = app_state.clone();
+                                cx.spawn(|_, mut cx| async move {
+                                    for location in locations {
+                                        cx.update(|cx| {
+                                            workspace::open_paths(
+                                                location.paths().as_ref(),
+                                                app_state.clone(),
+                                                workspace::OpenOptions {
+                                                    restoring: true,
+                                                    ..Default::default()
+                                                },
+                                                cx,
+                                            )
+                                        })?
+                                        .await?;
+                                    }
+                                    anyhow::Ok(())
+                                })
+                                .detach_and_log_err(cx);
+                                cx.emit(DismissEvent);
+                            })
+                    })
+                },
+            );
+        })
+        .log_err();
+}
+
 pub(crate) async fn restorable_workspace_locations(
     cx: &mut AsyncAppContext,
     app_state: &Arc<AppState>,
 ) -> Option<Vec<workspace::LocalPaths>> {
+    if cx.update(|cx| cx.has_global::<NoRestore>()).ok()? {
+        return None;
+    }
+
     let mut restore_behavior = cx
         .update(|cx| WorkspaceSettings::get(None, cx).restore_on_startup)
         .ok()?;
@@ -918,7 +1885,7 @@ pub(crate) async fn restorable_workspace_locations(
         restore_behavior = workspace::RestoreOnStartupBehavior::LastWorkspace;
     }
 
-    match restore_behavior {
+    let mut locations = match restore_behavior {
         workspace::RestoreOnStartupBehavior::LastWorkspace => {
             workspace::last_opened_workspace_paths()
                 .await
@@ -948,10 +1915,33 @@ pub(crate) async fn restorable_workspace_locations(
             }
         }
         _ => None,
+    };
+
+    // Open the workspaces that are always restored first, so that the last session's
+    // frontmost window stays in front.
+    for paths in workspace::always_restored_workspaces() {
+        let locations = locations.get_or_insert_with(Vec::new);
+        if !locations.contains(&paths) {
+            locations.insert(0, paths);
+        }
     }
+    locations
 }
 
 fn init_paths() -> anyhow::Result<()> {
+    // Without a single-instance socket elsewhere, there's no telling whether another instance
+    // still uses the legacy directories, so they're only migrated on Linux.
+    #[cfg(target_os = "linux")]
+    match paths::migrate_legacy_xdg_dirs(zed::is_instance_listening_in) {
+        // The logger isn't initialized yet.
+        Ok(copied) => {
+            for (from, to) in copied {
+                eprintln!("Copied {from:?} to {to:?}, which Zed uses from now on");
+            }
+        }
+        Err(e) => eprintln!("Could not copy Zed's directories to their XDG locations: {e}"),
+    }
+
     for path in [
         paths::config_dir(),
         paths::extensions_dir(),
@@ -968,6 +1958,215 @@ fn init_paths() -> anyhow::Result<()> {
     Ok(())
 }
 
+fn print_paths() {
+    for (name, path) in paths::named_paths() {
+        println!("{name:<12}{}", path.display());
+    }
+    for (legacy_dir, dir) in paths::legacy_xdg_dirs() {
+        eprintln!("Using {legacy_dir:?}, since {dir:?} doesn't exist yet");
+    }
+}
+
+/// Prints the version, commit, release channel and target of this build for update and packaging
+/// tooling, see `--print-version-json`.
+fn print_version_json() {
+    let version = serde_json::json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "commit": option_env!("ZED_COMMIT_SHA"),
+        "channel": RELEASE_CHANNEL.dev_name(),
+        "target": option_env!("ZED_TARGET"),
+    });
+    println!("{version}");
+}
+
+/// Streams the log file to stdout until interrupted, see `--tail-log`.
+///
+/// The file is reopened on every poll, so that following it continues from the start
+/// of the new log once Zed rotates the old one away on launch.
+fn tail_log() -> i32 {
+    const POLL_INTERVAL: Duration = Duration::from_millis(250);
+    const INITIAL_BYTES: u64 = 8 * 1024;
+
+    let log_path = paths::log_file();
+    let mut stdout = std::io::stdout().lock();
+    let mut position = None;
+    let mut identity = None;
+    let mut chunk = Vec::new();
+    loop {
+        if let Ok(mut log_file) = File::open(log_path) {
+            let metadata = log_file.metadata().ok();
+            let len = metadata.as_ref().map_or(0, |metadata| metadata.len());
+            let new_identity = metadata.as_ref().and_then(file_identity);
+            // A log that was replaced by a new file, or that shrank, was rotated.
+            let rotated = identity.is_some() && new_identity != identity;
+            identity = new_identity;
+            let start = match position {
+                None => len.saturating_sub(INITIAL_BYTES),
+                Some(position) if rotated || len < position => 0,
+                Some(position) => position,
+            };
+            chunk.clear();
+            if log_file.seek(SeekFrom::Start(start)).is_ok()
+                && log_file.read_to_end(&mut chunk).is_ok()
+            {
+                position = Some(start + chunk.len() as u64);
+                // Stop quietly once stdout is closed, e.g. when piped into `head`.
+                if stdout
+                    .write_all(&chunk)
+                    .and_then(|_| stdout.flush())
+                    .is_err()
+                {
+                    return 0;
+                }
+            }
+        } else if position.is_some() {
+            // The log was moved away and a new one hasn't been created yet.
+            position = Some(0);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Distinguishes the file at a path from one that later replaces it.
+fn file_identity(metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some((metadata.dev(), metadata.ino()))
+    }
+    #[cfg(not(unix))]
+    {
+        let created = metadata
+            .created()
+            .ok()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?;
+        Some((created.as_secs(), created.subsec_nanos().into()))
+    }
+}
+
+/// When `ZED_REPORT_READY` is set, prints `ZED_READY <milliseconds since launch>` to stdout once
+/// the first workspace window has been drawn, so that scripts can wait for Zed to be usable.
+fn report_ready_after_first_draw(launch_time: Instant, cx: &mut AppContext) {
+    let enabled = env::var("ZED_REPORT_READY")
+        .map(|value| value == "1" || value == "true")
+        .unwrap_or(false);
+    if !enabled {
+        return;
+    }
+
+    let reported = Rc::new(Cell::new(false));
+    cx.observe_new_views(move |_: &mut Workspace, cx| {
+        if reported.replace(true) {
+            return;
+        }
+        // Frame callbacks run before the frame is drawn, so wait for the one after the first.
+        cx.on_next_frame(move |_, cx| {
+            cx.on_next_frame(move |_, _| {
+                println!("ZED_READY {}", launch_time.elapsed().as_millis());
+                std::io::stdout().flush().ok();
+            });
+            cx.refresh();
+        });
+    })
+    .detach();
+}
+
+/// When `ZED_REPORT_TIME_TO_INTERACTIVE` is set, logs the time from launch until the first
+/// workspace is usable: its worktrees have been scanned and the window has been drawn since.
+/// For large restored projects this is a more honest startup metric than the first draw.
+fn report_time_to_interactive(launch_time: Instant, cx: &mut AppContext) {
+    let enabled = env::var("ZED_REPORT_TIME_TO_INTERACTIVE")
+        .map(|value| value == "1" || value == "true")
+        .unwrap_or(false);
+    if !enabled {
+        return;
+    }
+
+    let reported = Rc::new(Cell::new(false));
+    cx.observe_new_views(move |workspace: &mut Workspace, cx| {
+        if reported.replace(true) {
+            return;
+        }
+        let scans = workspace
+            .project()
+            .read(cx)
+            .worktrees(cx)
+            .filter_map(|worktree| Some(worktree.read(cx).as_local()?.scan_complete()))
+            .collect::<Vec<_>>();
+        cx.spawn(|workspace, mut cx| async move {
+            future::join_all(scans).await;
+            let scanned_in = launch_time.elapsed();
+            workspace.update(&mut cx, |_, cx| {
+                cx.on_next_frame(move |_, cx| {
+                    cx.on_next_frame(move |_, _| {
+                        log::info!(
+                            "time to interactive: {:?} (worktrees scanned after {scanned_in:?})",
+                            launch_time.elapsed()
+                        );
+                    });
+                    cx.refresh();
+                });
+                cx.refresh();
+            })
+        })
+        .detach_and_log_err(cx);
+    })
+    .detach();
+}
+
+/// Moves the user settings file aside, see `--reset-settings`.
+fn reset_settings_file() -> Result<()> {
+    let settings_file = paths::settings_file();
+    if !settings_file.exists() {
+        eprintln!(
+            "There is no settings file at {}, starting with the default settings",
+            settings_file.display()
+        );
+        return Ok(());
+    }
+    let timestamp = chrono::Local::now().format("%Y-%m-%d-%H%M%S");
+    let backup = settings_file.with_file_name(format!("settings.json.{timestamp}.bak"));
+    std::fs::rename(settings_file, &backup)
+        .with_context(|| format!("failed to back up the settings file {settings_file:?}"))?;
+    log::info!("moved the settings file to {backup:?}");
+    eprintln!(
+        "Your settings were backed up to {}, starting with the default settings",
+        backup.display()
+    );
+    Ok(())
+}
+
+/// Prints the settings that result from merging the user settings file into the defaults,
+/// and returns the exit status.
+fn print_config(cx: &mut AppContext) -> i32 {
+    let user_settings = std::fs::read_to_string(paths::settings_file()).unwrap_or_default();
+    SettingsStore::update_global(cx, |store, cx| {
+        if let Err(error) = store.set_user_settings(&user_settings, cx) {
+            eprintln!(
+                "warning: ignoring invalid settings file {}: {error}",
+                paths::settings_file().display()
+            );
+            store.set_user_settings("{}", cx).log_err();
+        }
+    });
+    let store = SettingsStore::global(cx);
+    let settings = store.effective_settings().unwrap_or_else(|error| {
+        eprintln!("warning: failed to merge settings, printing the defaults: {error}");
+        store.raw_default_settings().clone()
+    });
+    match serde_json::to_string_pretty(&settings) {
+        Ok(settings) => {
+            println!("{settings}");
+            0
+        }
+        Err(error) => {
+            eprintln!("failed to serialize settings: {error}");
+            1
+        }
+    }
+}
+
 fn init_logger() {
     if stdout_is_a_pty() {
         init_stdout_logger();
@@ -990,12 +2189,20 @@ fn init_logger() {
             .open(paths::log_file())
         {
             Ok(log_file) => {
+                if let Ok(log_file) = log_file.try_clone() {
+                    LOG_FILE.set(log_file).ok();
+                }
+
                 let mut config_builder = ConfigBuilder::new();
 
                 config_builder.set_time_format_rfc3339();
-                let local_offset = chrono::Local::now().offset().fix().local_minus_utc();
-                if let Ok(offset) = UtcOffset::from_whole_seconds(local_offset) {
-                    config_builder.set_time_offset(offset);
+                if log_in_utc() {
+                    config_builder.set_time_offset(UtcOffset::UTC);
+                } else {
+                    let local_offset = chrono::Local::now().offset().fix().local_minus_utc();
+                    if let Ok(offset) = UtcOffset::from_whole_seconds(local_offset) {
+                        config_builder.set_time_offset(offset);
+                    }
                 }
 
                 #[cfg(target_os = "linux")]
@@ -1020,19 +2227,46 @@ fn init_logger() {
     }
 }
 
+/// A handle to the file that `init_logger` logs to, used to sync it when panicking.
+static LOG_FILE: OnceLock<File> = OnceLock::new();
+
+/// Flushes the logger and syncs the log file to disk, so that the lines logged
+/// right before the process exits are not lost.
+fn flush_logger() {
+    log::logger().flush();
+    if let Some(log_file) = LOG_FILE.get() {
+        log_file.sync_data().ok();
+    }
+}
+
+/// Whether log timestamps should be in UTC rather than local time, see `ZED_LOG_TZ`.
+fn log_in_utc() -> bool {
+    match env::var("ZED_LOG_TZ") {
+        Ok(timezone) if timezone.eq_ignore_ascii_case("utc") => true,
+        Ok(timezone) if timezone.is_empty() || timezone.eq_ignore_ascii_case("local") => false,
+        Ok(timezone) => {
+            eprintln!("unknown ZED_LOG_TZ {timezone:?}, expected \"utc\" or \"local\"");
+            false
+        }
+        Err(_) => false,
+    }
+}
+
 fn init_stdout_logger() {
+    let utc = log_in_utc();
     Builder::new()
         .parse_default_env()
-        .format(|buf, record| {
+        .format(move |buf, record| {
             use env_logger::fmt::style::{AnsiColor, Style};
 
             let subtle = Style::new().fg_color(Some(AnsiColor::BrightBlack.into()));
             write!(buf, "{subtle}[{subtle:#}")?;
-            write!(
-                buf,
-                "{} ",
-                chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%:z")
-            )?;
+            const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%:z";
+            if utc {
+                write!(buf, "{} ", chrono::Utc::now().format(TIME_FORMAT))?;
+            } else {
+                write!(buf, "{} ", chrono::Local::now().format(TIME_FORMAT))?;
+            }
             let level_style = buf.default_level_style(record.level());
             write!(buf, "{level_style}{:<5}{level_style:#}", record.level())?;
             if let Some(path) = record.module_path() {
@@ -1093,7 +2327,44 @@ async fn load_shell_from_passwd() -> Result<()> {
     Ok(())
 }
 
-async fn load_login_shell_environment() -> Result<()> {
+/// Reads the variables from a dotenv-style file, see `--env-file`.
+fn load_env_file(path: &Path) -> Result<Vec<(String, String)>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read the environment file {path:?}"))?;
+    let mut variables = Vec::new();
+    parse_env_file(&content, |key, value| variables.push((key, value)));
+    log::info!(
+        "set {} environment variables from {path:?}",
+        variables.len()
+    );
+    Ok(variables)
+}
+
+/// The environment of a running instance can't be changed, so `--env-file` only applies when it
+/// starts Zed.
+fn warn_about_args_ignored_by_running_instance(args: &Args) {
+    if let Some(env_file) = &args.env_file {
+        eprintln!(
+            "warning: ignoring --env-file {env_file:?}, as it only applies when Zed isn't already running"
+        );
+    }
+}
+
+/// Returns the directory of the first path argument that exists, for `--shell-env-from-project`.
+fn first_path_dir(paths_or_urls: &[String]) -> Option<PathBuf> {
+    paths_or_urls.iter().find_map(|arg| {
+        let path = std::fs::canonicalize(PathWithPosition::parse_str(arg).path).ok()?;
+        if path.is_dir() {
+            Some(path)
+        } else {
+            path.parent().map(Path::to_path_buf)
+        }
+    })
+}
+
+/// Sets the environment variables of a login shell started in `dir`, or in the home
+/// directory if it's `None`.
+async fn load_login_shell_environment(dir: Option<PathBuf>) -> Result<()> {
     let marker = "ZED_LOGIN_SHELL_START";
     let shell = env::var("SHELL").context(
         "SHELL environment variable is not assigned so we can't source login environment variables",
@@ -1103,10 +2374,12 @@ async fn load_login_shell_environment() -> Result<()> {
     // such as direnv, asdf, mise, ... to adjust the PATH. These tools often hook
     // into shell's `cd` command (and hooks) to manipulate env.
     // We do this so that we get the env a user would have when spawning a shell
-    // in home directory.
-    let shell_cmd_prefix = std::env::var_os("HOME")
-        .and_then(|home| home.into_string().ok())
-        .map(|home| format!("cd '{home}';"));
+    // in home directory, or in the project with `--shell-env-from-project`.
+    let shell_cmd_prefix = dir
+        .map(|dir| dir.into_os_string())
+        .or_else(|| std::env::var_os("HOME"))
+        .and_then(|dir| dir.into_string().ok())
+        .map(|dir| format!("cd '{}';", dir.replace('\'', r"'\''")));
 
     // The `exit 0` is the result of hours of debugging, trying to find out
     // why running this command here, without `exit 0`, would mess
@@ -1145,7 +2418,81 @@ async fn load_login_shell_environment() -> Result<()> {
     Ok(())
 }
 
+fn health_check() -> i32 {
+    match check_running_instance() {
+        Ok(()) => {
+            println!("OK");
+            0
+        }
+        Err(error) => {
+            eprintln!("Zed is not healthy: {error:#}");
+            1
+        }
+    }
+}
+
+fn check_running_instance() -> Result<()> {
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    {
+        zed::ping_running_instance(std::time::Duration::from_secs(1))
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        Err(anyhow!("--health-check is not supported on this platform"))
+    }
+}
+
+/// Whether another instance of this release channel is running, without becoming the single
+/// instance.
+fn is_instance_running() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        zed::is_instance_running()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        zed::mac_only_instance::is_instance_running()
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        zed::windows_only_instance::is_instance_running()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        false
+    }
+}
+
+fn quit_running_instance() -> i32 {
+    #[cfg(target_os = "linux")]
+    let result = zed::quit_running_instance();
+    #[cfg(not(target_os = "linux"))]
+    let result: Result<()> = Err(anyhow!("--quit is only supported on Linux"));
+
+    match result {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("Could not quit Zed: {error:#}");
+            1
+        }
+    }
+}
+
+/// Set to `1` to behave as if Zed was launched from the desktop (logging to a file, loading
+/// the login shell environment) even when running in a terminal. Set to `0` for the opposite.
+/// Takes precedence over `ZED_FORCE_CLI_MODE`, which the CLI sets when it launches Zed.
+const FORCE_GUI_MODE_ENV_VAR_NAME: &str = "ZED_FORCE_GUI_MODE";
+
 fn stdout_is_a_pty() -> bool {
+    match std::env::var(FORCE_GUI_MODE_ENV_VAR_NAME).as_deref() {
+        Ok("1" | "true") => return false,
+        Ok("0" | "false") => return true,
+        _ => {}
+    }
     std::env::var(FORCE_CLI_MODE_ENV_VAR_NAME).ok().is_none() && std::io::stdout().is_terminal()
 }
 
@@ -1158,11 +2505,269 @@ struct Args {
     /// Non-existing paths and directories will ignore `:line:row` suffix.
     ///
     /// URLs can either be `file://` or `zed://` scheme, or relative to <https://zed.dev>.
+    ///
+    /// Remote paths can be opened with `ssh://[user@]host[:port]/path`.
     paths_or_urls: Vec<String>,
 
     /// Instructs zed to run as a dev server on this machine. (not implemented)
     #[arg(long)]
     dev_server_token: Option<String>,
+
+    /// Checks whether a running instance of Zed is responsive, without opening any windows.
+    ///
+    /// Prints `OK` and exits with status 0 if it is, otherwise exits with status 1.
+    /// Only supported on Linux and macOS.
+    #[arg(long)]
+    health_check: bool,
+
+    /// Quits the running instance of Zed, asking to save unsaved changes as usual. Useful when
+    /// it keeps running without windows. Only supported on Linux.
+    #[arg(long)]
+    quit: bool,
+
+    /// Selects the graphics device to render with. Can also be set with `ZED_RENDERER`.
+    ///
+    /// Only has an effect on Linux and FreeBSD.
+    #[arg(long, value_name = "RENDERER", value_enum)]
+    renderer: Option<Renderer>,
+
+    /// Opens each of the given files in its own pane, split vertically (side by side) or
+    /// horizontally, rather than in tabs, e.g. `zed --split vertical a.rs b.rs`.
+    #[arg(long, value_name = "DIRECTION", value_enum, requires = "paths_or_urls")]
+    split: Option<StartupSplit>,
+
+    /// Focuses the named panel once the workspace has opened.
+    #[arg(long, value_name = "NAME", value_enum)]
+    panel: Option<StartupPanel>,
+
+    /// Sets the relative sizes of the panes in the outermost split once the workspace has
+    /// opened, e.g. `zed --split vertical --split-ratios 2,1 a.rs b.rs`. Sizes are clamped so
+    /// that every pane stays visible.
+    #[arg(long, value_name = "RATIOS", value_delimiter = ',')]
+    split_ratios: Vec<f32>,
+
+    /// Sets the size of a dock in pixels once the workspace has opened, e.g.
+    /// `--dock-size left=320`. Can be given once per dock. Sizes are clamped to the window.
+    #[arg(long, value_name = "DOCK=PIXELS", value_parser = parse_dock_size)]
+    dock_size: Vec<(DockPosition, Pixels)>,
+
+    /// Runs the named action, e.g. `workspace::NewTerminal`, once the workspace has opened.
+    #[arg(long, value_name = "ACTION")]
+    command: Option<String>,
+
+    /// Uses the named theme for this session, without changing the settings.
+    #[arg(long, value_name = "NAME")]
+    theme: Option<String>,
+
+    /// Uses the named base keymap (e.g. `JetBrains`) for this session, without changing the settings.
+    #[arg(long, value_name = "NAME")]
+    keymap: Option<String>,
+
+    /// Loads your keymap from the given HTTP(S) URL instead of your keymap file, e.g. to share
+    /// one keymap across a team.
+    ///
+    /// The keymap is fetched again every hour and on `zed: refresh remote keymap`. When fetching
+    /// fails, the last fetched copy is used, or your keymap file if there is none.
+    #[arg(long, value_name = "URL", conflicts_with = "safe_mode")]
+    keymap_url: Option<String>,
+
+    /// Starts with the default settings and keymap, without extensions or user themes.
+    ///
+    /// Useful to check whether a problem is caused by your configuration.
+    #[arg(long, conflicts_with = "dev_server_token")]
+    safe_mode: bool,
+
+    /// Opens windows in zen mode, showing only the editors without docks, tabs, title bar
+    /// or status bar. Use `workspace: toggle zen mode` to bring them back.
+    #[arg(long)]
+    zen: bool,
+
+    /// Starts without bringing Zed to the foreground or focusing its windows.
+    ///
+    /// Zed comes forward once it's launched again, or when one of its windows is clicked.
+    #[arg(long)]
+    background: bool,
+
+    /// Starts without reopening the windows of the previous session, regardless of the
+    /// `restore_on_startup` setting. The setting itself is left unchanged.
+    #[arg(long)]
+    no_restore: bool,
+
+    /// Moves your settings file to a timestamped backup and starts with the default settings.
+    ///
+    /// Useful when a broken settings file keeps Zed from working.
+    #[arg(long, conflicts_with_all = ["print_config", "dev_server_token"])]
+    reset_settings: bool,
+
+    /// Prints the directories and files where Zed keeps its configuration and data, then exits.
+    #[arg(long)]
+    print_paths: bool,
+
+    /// Prints the version, commit, release channel and target triple of this build as JSON,
+    /// then exits. The commit and target are `null` if they weren't recorded at build time.
+    #[arg(long)]
+    print_version_json: bool,
+
+    /// Prints the end of Zed's log file and keeps printing new lines as they are logged,
+    /// like `tail -f`, instead of launching Zed. Stop it with Ctrl-C.
+    #[arg(long, conflicts_with_all = ["paths_or_urls", "dev_server_token"])]
+    tail_log: bool,
+
+    /// Prints the effective settings, i.e. the defaults merged with your settings file, as JSON, then exits.
+    #[arg(long, conflicts_with_all = ["safe_mode", "dev_server_token"])]
+    print_config: bool,
+
+    /// Prints the id and version of each installed extension, then exits.
+    #[arg(long, conflicts_with_all = ["safe_mode", "dev_server_token"])]
+    list_extensions: bool,
+
+    /// Installs the latest version of the extension with the given id, then exits. Fails if
+    /// Zed is already running.
+    #[arg(
+        long,
+        value_name = "ID",
+        conflicts_with_all = ["safe_mode", "dev_server_token", "list_extensions"]
+    )]
+    install_extension: Option<String>,
+
+    /// Sets the environment variables from a dotenv-style file of `KEY=VALUE` lines for this
+    /// session, e.g. for terminals, tasks and language servers. Ignored if Zed is already running.
+    #[arg(long, value_name = "PATH")]
+    env_file: Option<PathBuf>,
+
+    /// Shows git blame in the files that are opened, e.g. `zed --blame src/main.rs:42`.
+    #[arg(long, requires = "paths_or_urls")]
+    blame: bool,
+
+    /// Shows the changes in the opened files since the given git ref, e.g.
+    /// `zed --diff-git main src/main.rs`.
+    ///
+    /// The changes are expanded inline and stay against that ref until the file is closed.
+    #[arg(long, value_name = "REF", requires = "paths_or_urls")]
+    diff_git: Option<String>,
+
+    /// Opens the given files read-only, e.g. `zed --read-only /etc/nginx/nginx.conf`.
+    ///
+    /// Typing into such a file shows a notification with a button to unlock it for editing.
+    #[arg(long, requires = "paths_or_urls")]
+    read_only: bool,
+
+    /// Opens the given files in this encoding (e.g. `shift_jis`, `windows-1252`) instead of UTF-8.
+    ///
+    /// Files are saved back in the same encoding.
+    #[arg(long, value_name = "NAME", value_parser = parse_encoding)]
+    encoding: Option<&'static Encoding>,
+
+    /// Loads the environment of the login shell in the directory of the first path given,
+    /// instead of the home directory, so that tools like direnv set up the project's
+    /// environment for language servers and tasks.
+    #[arg(long, requires = "paths_or_urls")]
+    shell_env_from_project: bool,
+
+    /// Saves the given files with these line endings, instead of the ones they already use.
+    #[arg(long, value_name = "STYLE", value_enum, requires = "paths_or_urls")]
+    line_endings: Option<cli::LineEndings>,
+
+    /// Accepts invalid TLS certificates, e.g. self-signed ones, from the configured `server_url`,
+    /// for evaluating a self-hosted server.
+    ///
+    /// WARNING: this lets anyone on the network impersonate that server. Other hosts, and the
+    /// default `https://zed.dev`, are always verified.
+    #[arg(long)]
+    insecure: bool,
+
+    /// Used by the `RestartApp` action: waits for the process with this id to exit before starting.
+    #[arg(long, value_name = "PID", hide = true)]
+    relaunch_after: Option<u32>,
+}
+
+/// Blocks until the process with the given id has exited, giving up after a while so that a
+/// process that hangs while quitting doesn't keep the new instance from starting.
+fn wait_for_process_exit(pid: u32) {
+    const TIMEOUT: Duration = Duration::from_secs(30);
+    let pid = sysinfo::Pid::from_u32(pid);
+    let mut system = sysinfo::System::new();
+    let start = Instant::now();
+    while system.refresh_processes_specifics(
+        sysinfo::ProcessesToUpdate::Some(&[pid]),
+        sysinfo::ProcessRefreshKind::new(),
+    ) == 1
+    {
+        if start.elapsed() > TIMEOUT {
+            log::warn!("process {pid} did not exit within {TIMEOUT:?}, starting anyway");
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+fn parse_encoding(name: &str) -> Result<&'static Encoding> {
+    Encoding::for_label(name.trim().as_bytes())
+        .ok_or_else(|| anyhow!("unknown encoding {name:?}, expected a name like \"windows-1252\""))
+}
+
+/// Which graphics device to render with, see `--renderer`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Renderer {
+    /// Let the graphics driver pick the device.
+    Auto,
+    /// Prefer the discrete GPU on systems with hybrid graphics.
+    Gpu,
+    /// Render on the CPU with Mesa's lavapipe Vulkan driver.
+    Software,
+}
+
+/// Points the Vulkan loader at the requested device through environment variables,
+/// so this has to run before the first window is created.
+fn select_renderer(renderer: Renderer) {
+    // Inherited by restarts, and reported by `zed: copy system info`.
+    if let Some(name) = renderer.to_possible_value() {
+        env::set_var("ZED_RENDERER", name.get_name());
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+    match renderer {
+        Renderer::Auto => {}
+        Renderer::Gpu => {
+            // PRIME render offloading, for both Mesa and NVIDIA drivers.
+            env::set_var("DRI_PRIME", "1");
+            env::set_var("__NV_PRIME_RENDER_OFFLOAD", "1");
+            env::set_var("__VK_LAYER_NV_optimus", "NVIDIA_only");
+        }
+        Renderer::Software => {
+            const ICD_DIRS: [&str; 3] = [
+                "/usr/share/vulkan/icd.d",
+                "/usr/local/share/vulkan/icd.d",
+                "/etc/vulkan/icd.d",
+            ];
+            let lavapipe_icd = ICD_DIRS.iter().find_map(|dir| {
+                std::fs::read_dir(dir)
+                    .ok()?
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .find(|path| {
+                        path.file_name()
+                            .map_or(false, |name| name.to_string_lossy().starts_with("lvp_icd"))
+                    })
+            });
+            match lavapipe_icd {
+                Some(icd) => {
+                    log::info!("rendering with lavapipe ({})", icd.display());
+                    // `VK_ICD_FILENAMES` is the name used by older Vulkan loaders.
+                    env::set_var("VK_DRIVER_FILES", &icd);
+                    env::set_var("VK_ICD_FILENAMES", &icd);
+                }
+                None => log::error!(
+                    "software rendering needs Mesa's lavapipe Vulkan driver, which wasn't found in {ICD_DIRS:?}"
+                ),
+            }
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
+    if renderer != Renderer::Auto {
+        log::warn!("--renderer only has an effect on Linux and FreeBSD");
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -1218,9 +2823,11 @@ fn load_embedded_fonts(cx: &AppContext) {
         }
     }));
 
-    cx.text_system()
-        .add_fonts(embedded_fonts.into_inner())
-        .unwrap();
+    let text_system = cx.text_system();
+    text_system.add_fonts(embedded_fonts.into_inner()).unwrap();
+    // Fall back to the bundled fonts before any system font, as the latter may lack glyphs.
+    text_system.add_preferred_fallback_font(font("Zed Plex Mono"));
+    text_system.add_preferred_fallback_font(font("Zed Plex Sans"));
 }
 
 /// Spawns a background task to load the user themes from the themes directory.
@@ -1257,35 +2864,6 @@ fn load_user_themes_in_background(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
     .detach_and_log_err(cx);
 }
 
-/// Spawns a background task to watch the themes directory for changes.
-fn watch_themes(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
-    use std::time::Duration;
-    cx.spawn(|cx| async move {
-        let (mut events, _) = fs
-            .watch(paths::themes_dir(), Duration::from_millis(100))
-            .await;
-
-        while let Some(paths) = events.next().await {
-            for event in paths {
-                if fs.metadata(&event.path).await.ok().flatten().is_some() {
-                    if let Some(theme_registry) =
-                        cx.update(|cx| ThemeRegistry::global(cx).clone()).log_err()
-                    {
-                        if let Some(()) = theme_registry
-                            .load_user_theme(&event.path, fs.clone())
-                            .await
-                            .log_err()
-                        {
-                            cx.update(ThemeSettings::reload_current_theme).log_err();
-                        }
-                    }
-                }
-            }
-        }
-    })
-    .detach()
-}
-
 #[cfg(debug_assertions)]
 fn watch_languages(fs: Arc<dyn fs::Fs>, languages: Arc<LanguageRegistry>, cx: &mut AppContext) {
     use std::time::Duration;
@@ -1299,15 +2877,17 @@ fn watch_languages(fs: Arc<dyn fs::Fs>, languages: Arc<LanguageRegistry>, cx: &m
     };
 
     cx.spawn(|_| async move {
-        let (mut events, _) = fs.watch(path.as_path(), Duration::from_millis(100)).await;
+        let mut events = settings::watch_unless_paused(fs, path, Duration::from_millis(100)).await;
         while let Some(event) = events.next().await {
-            let has_language_file = event.iter().any(|event| {
-                event
-                    .path
-                    .extension()
-                    .map(|ext| ext.to_string_lossy().as_ref() == "scm")
-                    .unwrap_or(false)
-            });
+            // An empty batch means that the file watchers were resumed.
+            let has_language_file = event.is_empty()
+                || event.iter().any(|event| {
+                    event
+                        .path
+                        .extension()
+                        .map(|ext| ext.to_string_lossy().as_ref() == "scm")
+                        .unwrap_or(false)
+                });
             if has_language_file {
                 languages.reload();
             }
@@ -1335,7 +2915,7 @@ fn watch_file_types(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
     };
 
     cx.spawn(|cx| async move {
-        let (mut events, _) = fs.watch(path.as_path(), Duration::from_millis(100)).await;
+        let mut events = settings::watch_unless_paused(fs, path, Duration::from_millis(100)).await;
         while (events.next().await).is_some() {
             cx.update(|cx| {
                 FileIcons::update_global(cx, |file_types, _cx| {
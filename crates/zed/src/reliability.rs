@@ -3,7 +3,7 @@ use backtrace::{self, Backtrace};
 use chrono::Utc;
 use client::telemetry;
 use db::kvp::KEY_VALUE_STORE;
-use gpui::{AppContext, SemanticVersion};
+use gpui::{AppContext, DismissEvent, Global, SemanticVersion, ViewContext, VisualContext};
 use http_client::{HttpRequestExt, Method};
 
 use http_client::{self, HttpClient, HttpClientWithUrl};
@@ -15,6 +15,7 @@ use smol::stream::StreamExt;
 use std::{
     env,
     ffi::OsStr,
+    path::PathBuf,
     sync::{atomic::Ordering, Arc},
 };
 use std::{io::Write, panic, sync::atomic::AtomicU32, thread};
@@ -22,10 +23,25 @@ use telemetry_events::LocationData;
 use telemetry_events::Panic;
 use telemetry_events::PanicRequest;
 use util::ResultExt;
+use workspace::{
+    notifications::{simple_message_notification::MessageNotification, NotificationId},
+    OpenLog, Workspace,
+};
 
-use crate::stdout_is_a_pty;
+use crate::{flush_logger, stdout_is_a_pty};
 static PANIC_COUNT: AtomicU32 = AtomicU32::new(0);
 
+/// Written next to the panic file, and kept until the crash is reported to the
+/// user on a later launch. Panic files themselves are deleted once uploaded.
+fn panic_marker_path() -> PathBuf {
+    paths::logs_dir().join("last-session-panicked")
+}
+
+/// The payload of the panic that ended the previous session, if any.
+struct PreviousPanic(Option<String>);
+
+impl Global for PreviousPanic {}
+
 pub fn init_panic_hook(
     app_version: SemanticVersion,
     system_id: Option<String>,
@@ -65,6 +81,7 @@ pub fn init_panic_hook(
                 location.column(),
                 backtrace,
             );
+            flush_logger();
             std::process::exit(-1);
         }
 
@@ -124,9 +141,11 @@ pub fn init_panic_hook(
                     writeln!(&mut panic_file, "{panic_data_json}").log_err();
                     panic_file.flush().log_err();
                 }
+                std::fs::write(panic_marker_path(), &panic_data.payload).log_err();
             }
         }
 
+        flush_logger();
         std::process::abort();
     }));
 }
@@ -136,12 +155,53 @@ pub fn init(
     installation_id: Option<String>,
     cx: &mut AppContext,
 ) {
+    cx.set_global(PreviousPanic(
+        std::fs::read_to_string(panic_marker_path()).ok(),
+    ));
+
     #[cfg(target_os = "macos")]
     monitor_main_thread_hangs(http_client.clone(), installation_id.clone(), cx);
 
     upload_panics_and_crashes(http_client, installation_id, cx)
 }
 
+/// Shows a notification in the first workspace opened after a crash, offering
+/// to open the log or file an issue about it.
+pub fn notify_of_previous_panic(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    if !cx.has_global::<PreviousPanic>() {
+        return;
+    }
+    let Some(payload) = cx.global_mut::<PreviousPanic>().0.take() else {
+        return;
+    };
+    // Only show the notice once, even if it's dismissed without clicking it.
+    std::fs::remove_file(panic_marker_path()).log_err();
+
+    struct PreviousPanicNotification;
+    workspace.show_notification(
+        NotificationId::unique::<PreviousPanicNotification>(),
+        cx,
+        |cx| {
+            cx.new_view(|_| {
+                MessageNotification::new(format!("Zed crashed during the last session: {payload}"))
+                    .with_click_message("Open log")
+                    .on_click(|cx| {
+                        cx.dispatch_action(Box::new(OpenLog));
+                        cx.emit(DismissEvent);
+                    })
+                    .with_secondary_click_message("File an issue")
+                    .on_secondary_click(|cx| {
+                        // Issue forms can't be pre-filled with attachments, so reveal
+                        // the log for the user to drag into the report.
+                        cx.reveal_path(paths::log_file());
+                        cx.dispatch_action(Box::new(feedback::FileBugReport));
+                        cx.emit(DismissEvent);
+                    })
+            })
+        },
+    );
+}
+
 #[cfg(target_os = "macos")]
 pub fn monitor_main_thread_hangs(
     http_client: Arc<HttpClientWithUrl>,
@@ -372,6 +432,7 @@ async fn upload_previous_panics(
     let mut children = smol::fs::read_dir(paths::logs_dir()).await?;
 
     let mut most_recent_panic = None;
+    let mut last_panic_json = None;
 
     while let Some(child) = children.next().await {
         let child = child?;
@@ -409,7 +470,13 @@ async fn upload_previous_panics(
                 });
 
             if let Some(panic) = panic {
-                most_recent_panic = Some((panic.panicked_on, panic.payload.clone()));
+                if most_recent_panic
+                    .as_ref()
+                    .map_or(true, |(panicked_on, _)| panic.panicked_on > *panicked_on)
+                {
+                    last_panic_json = serde_json::to_string(&panic).log_err();
+                    most_recent_panic = Some((panic.panicked_on, panic.payload.clone()));
+                }
 
                 let json_bytes = serde_json::to_vec(&PanicRequest { panic }).unwrap();
 
@@ -438,6 +505,14 @@ async fn upload_previous_panics(
             .context("error removing panic")
             .log_err();
     }
+
+    // Kept so that the panic can be referenced with `feedback: report crash`.
+    if let Some(last_panic_json) = last_panic_json {
+        smol::fs::write(paths::last_panic_file(), last_panic_json)
+            .await
+            .context("error writing last panic")
+            .log_err();
+    }
     Ok::<_, anyhow::Error>(most_recent_panic)
 }
 
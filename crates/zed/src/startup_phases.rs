@@ -0,0 +1,81 @@
+//! Warns about startup phases that take longer than expected, so that regressions and slow
+//! environments (such as a slow login shell) show up in the logs without manual profiling.
+
+use std::{
+    env,
+    future::Future,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use client::telemetry::Telemetry;
+
+const DEFAULT_THRESHOLD: Duration = Duration::from_secs(1);
+
+/// Overrides the threshold of every phase, in milliseconds. A single phase can be tuned by
+/// appending its uppercased name, e.g. `ZED_SLOW_STARTUP_PHASE_MS_INIT_UI=500`.
+const THRESHOLD_ENV_VAR: &str = "ZED_SLOW_STARTUP_PHASE_MS";
+
+static TELEMETRY: OnceLock<Arc<Telemetry>> = OnceLock::new();
+/// Slow phases that finished before telemetry was started.
+static UNREPORTED_SLOW_PHASES: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+/// Runs a startup phase, warning if it took longer than its threshold.
+pub fn measure<T>(phase: &'static str, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+    phase_finished(phase, start.elapsed());
+    result
+}
+
+/// Awaits a startup phase, warning if it took longer than its threshold.
+pub async fn measure_async<T>(phase: &'static str, future: impl Future<Output = T>) -> T {
+    let start = Instant::now();
+    let result = future.await;
+    phase_finished(phase, start.elapsed());
+    result
+}
+
+/// Reports slow phases as telemetry events from now on, including the ones that already finished.
+pub fn report_to_telemetry(telemetry: Arc<Telemetry>) {
+    let mut unreported = UNREPORTED_SLOW_PHASES.lock().unwrap();
+    for phase in unreported.drain(..) {
+        report_slow_phase(&telemetry, phase);
+    }
+    TELEMETRY.set(telemetry).ok();
+}
+
+fn phase_finished(phase: &'static str, elapsed: Duration) {
+    let threshold = threshold(phase);
+    if elapsed <= threshold {
+        return;
+    }
+
+    log::warn!(
+        "startup phase {phase} took {elapsed:?}, longer than the threshold of {threshold:?}"
+    );
+    let mut unreported = UNREPORTED_SLOW_PHASES.lock().unwrap();
+    match TELEMETRY.get() {
+        Some(telemetry) => report_slow_phase(telemetry, phase),
+        None => unreported.push(phase),
+    }
+}
+
+fn report_slow_phase(telemetry: &Arc<Telemetry>, phase: &str) {
+    telemetry.report_app_event(format!("slow startup phase: {phase}"));
+}
+
+fn threshold(phase: &str) -> Duration {
+    let phase_env_var = format!("{THRESHOLD_ENV_VAR}_{}", phase.to_uppercase());
+    [phase_env_var.as_str(), THRESHOLD_ENV_VAR]
+        .into_iter()
+        .find_map(|name| {
+            let value = env::var(name).ok()?;
+            value
+                .parse()
+                .map(Duration::from_millis)
+                .map_err(|error| log::error!("invalid {name} {value:?}: {error}"))
+                .ok()
+        })
+        .unwrap_or(DEFAULT_THRESHOLD)
+}
@@ -5,6 +5,9 @@ pub(crate) mod linux_prompts;
 #[cfg(target_os = "macos")]
 pub(crate) mod mac_only_instance;
 mod open_listener;
+pub(crate) mod remote_keymap;
+pub(crate) mod splash;
+pub(crate) mod unsaved_changes;
 #[cfg(target_os = "windows")]
 pub(crate) mod windows_only_instance;
 
@@ -12,14 +15,16 @@ pub use app_menus::*;
 use assistant::PromptBuilder;
 use breadcrumbs::Breadcrumbs;
 use client::ZED_URL_SCHEME;
-use collections::VecDeque;
+use collections::{HashSet, VecDeque};
 use command_palette_hooks::CommandPaletteFilter;
 use editor::ProposedChangesEditorToolbar;
 use editor::{scroll::Autoscroll, Editor, MultiBuffer};
 use feature_flags::FeatureFlagAppExt;
+use fs::{Fs, RemoveOptions, RenameOptions};
 use gpui::{
-    actions, point, px, AppContext, AsyncAppContext, Context, FocusableView, MenuItem, PromptLevel,
-    ReadGlobal, TitlebarOptions, View, ViewContext, VisualContext, WindowKind, WindowOptions,
+    actions, point, px, AppContext, AsyncAppContext, ClipboardItem, Context, DismissEvent,
+    FocusableView, MenuItem, PathPromptOptions, PromptLevel, ReadGlobal, SharedString, Task,
+    TitlebarOptions, View, ViewContext, VisualContext, WindowHandle, WindowKind, WindowOptions,
 };
 pub use open_listener::*;
 
@@ -27,20 +32,29 @@ use anyhow::Context as _;
 use assets::Assets;
 use futures::{channel::mpsc, select_biased, StreamExt};
 use outline_panel::OutlinePanel;
-use project::TaskSourceKind;
+use project::{DirectoryLister, TaskSourceKind};
 use project_panel::ProjectPanel;
 use quick_action_bar::QuickActionBar;
 use release_channel::{AppCommitSha, ReleaseChannel};
 use rope::Rope;
 use search::project_search::ProjectSearchBar;
 use settings::{
-    initial_local_settings_content, initial_tasks_content, watch_config_file, KeymapFile, Settings,
-    SettingsStore, DEFAULT_KEYMAP_PATH,
+    initial_local_settings_content, initial_tasks_content, update_settings_file, watch_config_file,
+    KeymapConflict, KeymapFile, Settings, SettingsStore, DEFAULT_KEYMAP_PATH,
 };
 use std::any::TypeId;
-use std::{borrow::Cow, ops::Deref, path::Path, sync::Arc};
+use std::{
+    borrow::Cow,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{self, AtomicBool},
+        Arc, OnceLock,
+    },
+    time::Duration,
+};
 use task::static_source::{StaticSource, TrackedFile};
-use theme::ActiveTheme;
+use theme::{ActiveTheme, ThemeRegistry, ThemeSettings};
 use workspace::notifications::NotificationId;
 use workspace::CloseIntent;
 
@@ -48,11 +62,13 @@ use paths::{local_settings_file_relative_path, local_tasks_file_relative_path};
 use terminal_view::terminal_panel::{self, TerminalPanel};
 use util::{asset_str, ResultExt};
 use uuid::Uuid;
-use vim::VimModeSetting;
+use vim::{ToggleVimMode, VimModeSetting};
 use welcome::{BaseKeymap, MultibufferHint};
 use workspace::{
-    create_and_open_local_file, notifications::simple_message_notification::MessageNotification,
-    open_new, AppState, NewFile, NewWindow, OpenLog, Toast, Workspace, WorkspaceSettings,
+    create_and_open_local_file, item::ItemHandle,
+    notifications::simple_message_notification::MessageNotification, open_new, AppState, NewFile,
+    NewWindow, OpenLog, OpenVisible, QuitWithRunningTasks, SplitDirection, Toast, Workspace,
+    WorkspaceSettings,
 };
 use workspace::{notifications::DetachAndPromptErr, Pane};
 use zed_actions::{OpenAccountSettings, OpenBrowser, OpenSettings, OpenZedUrl, Quit};
@@ -60,17 +76,28 @@ use zed_actions::{OpenAccountSettings, OpenBrowser, OpenSettings, OpenZedUrl, Qu
 actions!(
     zed,
     [
+        CopySystemInfo,
         DebugElements,
+        ExportKeymap,
+        ExportLayout,
         Hide,
         HideOthers,
+        ImportLayout,
         Minimize,
+        MoveToDisplay,
         OpenDefaultKeymap,
+        OpenConfigFiles,
         OpenDefaultSettings,
         OpenLocalSettings,
         OpenLocalTasks,
         OpenTasks,
+        ReloadAllBuffers,
         ResetDatabase,
+        ResetKeymap,
+        RestartApp,
         ShowAll,
+        ShowReleaseChannelInfo,
+        ToggleFileWatchers,
         ToggleFullScreen,
         Zoom,
         TestPanic,
@@ -85,12 +112,53 @@ pub fn init(cx: &mut AppContext) {
     #[cfg(target_os = "macos")]
     cx.on_action(|_: &ShowAll, cx| cx.unhide_other_apps());
     cx.on_action(quit);
+    cx.on_action(restart_app);
+    cx.on_action(toggle_vim_mode);
+    cx.on_action(copy_system_info);
 
     if ReleaseChannel::global(cx) == ReleaseChannel::Dev {
         cx.on_action(test_panic);
     }
 }
 
+/// The window decorations requested with `ZED_WINDOW_DECORATIONS`, or the invalid value.
+fn requested_window_decorations() -> &'static Result<gpui::WindowDecorations, String> {
+    static DECORATIONS: OnceLock<Result<gpui::WindowDecorations, String>> = OnceLock::new();
+    DECORATIONS.get_or_init(|| match std::env::var("ZED_WINDOW_DECORATIONS") {
+        Ok(val) if val == "server" => Ok(gpui::WindowDecorations::Server),
+        Ok(val) if val == "client" || val == "auto" => Ok(gpui::WindowDecorations::Client),
+        Err(_) => Ok(gpui::WindowDecorations::Client),
+        Ok(val) => {
+            log::warn!(
+                "invalid ZED_WINDOW_DECORATIONS value {val:?}, expected \"server\", \"client\" or \"auto\". Using client decorations"
+            );
+            Err(val)
+        }
+    })
+}
+
+fn notify_of_invalid_window_decorations(
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+) {
+    static NOTIFIED: AtomicBool = AtomicBool::new(false);
+    let Err(value) = requested_window_decorations() else {
+        return;
+    };
+    if NOTIFIED.swap(true, atomic::Ordering::Relaxed) {
+        return;
+    }
+
+    struct InvalidWindowDecorations;
+    workspace.show_notification(NotificationId::unique::<InvalidWindowDecorations>(), cx, |cx| {
+        cx.new_view(|_| {
+            MessageNotification::new(format!(
+                "ZED_WINDOW_DECORATIONS is set to {value:?}, which isn't one of \"server\", \"client\" or \"auto\". Using client decorations."
+            ))
+        })
+    });
+}
+
 pub fn build_window_options(display_uuid: Option<Uuid>, cx: &mut AppContext) -> WindowOptions {
     let display = display_uuid.and_then(|uuid| {
         cx.displays()
@@ -98,11 +166,10 @@ pub fn build_window_options(display_uuid: Option<Uuid>, cx: &mut AppContext) ->
             .find(|display| display.uuid().ok() == Some(uuid))
     });
     let app_id = ReleaseChannel::global(cx).app_id();
-    let window_decorations = match std::env::var("ZED_WINDOW_DECORATIONS") {
-        Ok(val) if val == "server" => gpui::WindowDecorations::Server,
-        Ok(val) if val == "client" => gpui::WindowDecorations::Client,
-        _ => gpui::WindowDecorations::Client,
-    };
+    let window_decorations = requested_window_decorations()
+        .as_ref()
+        .copied()
+        .unwrap_or(gpui::WindowDecorations::Client);
 
     WindowOptions {
         titlebar: Some(TitlebarOptions {
@@ -144,7 +211,8 @@ pub fn initialize_workspace(
                     text,
                     title,
                     language,
-                } => open_bundled_file(workspace, text.clone(), title, language, cx),
+                } => open_bundled_file(workspace, text.clone(), title, language, cx)
+                    .detach_and_log_err(cx),
                 _ => {}
             }
         })
@@ -217,6 +285,9 @@ pub fn initialize_workspace(
         });
 
         auto_update::notify_of_any_new_update(cx);
+        crate::reliability::notify_of_previous_panic(workspace, cx);
+        unsaved_changes::offer_to_recover_changes(workspace, cx);
+        notify_of_invalid_window_decorations(workspace, cx);
 
         let handle = cx.view().downgrade();
         cx.on_window_should_close(move |cx| {
@@ -300,6 +371,7 @@ pub fn initialize_workspace(
 
         workspace
             .register_action(about)
+            .register_action(show_release_channel_info)
             .register_action(|_, _: &Minimize, cx| {
                 cx.minimize_window();
             })
@@ -309,6 +381,13 @@ pub fn initialize_workspace(
             .register_action(|_, _: &ToggleFullScreen, cx| {
                 cx.toggle_fullscreen();
             })
+            .register_action(move_to_display)
+            .register_action(export_keymap)
+            .register_action(export_layout)
+            .register_action(import_layout)
+            .register_action(reset_keymap)
+            .register_action(reload_all_buffers)
+            .register_action(toggle_file_watchers)
             .register_action(|_, action: &OpenZedUrl, cx| {
                 OpenListener::global(cx).open_urls(vec![action.url.clone()])
             })
@@ -411,7 +490,8 @@ pub fn initialize_workspace(
                     "Open Source License Attribution",
                     "Markdown",
                     cx,
-                );
+                )
+                .detach_and_log_err(cx);
             })
             .register_action(
                 move |workspace: &mut Workspace,
@@ -424,7 +504,7 @@ pub fn initialize_workspace(
                 move |_: &mut Workspace,
                       _: &zed_actions::OpenKeymap,
                       cx: &mut ViewContext<Workspace>| {
-                    open_settings_file(paths::keymap_file(), || settings::initial_keymap_content().as_ref().into(), cx);
+                    open_settings_file(paths::keymap_file(), || settings::initial_keymap_content().as_ref().into(), cx).detach_and_log_err(cx);
                 },
             )
             .register_action(
@@ -433,7 +513,8 @@ pub fn initialize_workspace(
                         paths::settings_file(),
                         || settings::initial_user_settings_content().as_ref().into(),
                         cx,
-                    );
+                    )
+                    .detach_and_log_err(cx);
                 },
             )
             .register_action(
@@ -448,7 +529,8 @@ pub fn initialize_workspace(
                         paths::tasks_file(),
                         || settings::initial_tasks_content().as_ref().into(),
                         cx,
-                    );
+                    )
+                    .detach_and_log_err(cx);
                 },
             )
             .register_action(open_local_settings_file)
@@ -463,7 +545,8 @@ pub fn initialize_workspace(
                         "Default Key Bindings",
                         "JSON",
                         cx,
-                    );
+                    )
+                    .detach_and_log_err(cx);
                 },
             )
             .register_action(
@@ -476,9 +559,11 @@ pub fn initialize_workspace(
                         "Default Settings",
                         "JSON",
                         cx,
-                    );
+                    )
+                    .detach_and_log_err(cx);
                 },
             )
+            .register_action(open_config_files)
             .register_action(
                 |workspace: &mut Workspace,
                  _: &project_panel::ToggleFocus,
@@ -615,19 +700,480 @@ fn about(_: &mut Workspace, _: &zed_actions::About, cx: &mut gpui::ViewContext<W
         .detach();
 }
 
+/// Shows which release channel is running and where it keeps its configuration and data, to
+/// help diagnose channels overwriting each other's state.
+fn show_release_channel_info(
+    workspace: &mut Workspace,
+    _: &ShowReleaseChannelInfo,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let release_channel = ReleaseChannel::global(cx);
+    let fs = workspace.app_state().fs.clone();
+    cx.spawn(|workspace, mut cx| async move {
+        let channel_db_dir = format!("0-{}", release_channel.dev_name());
+        let mut info = format!(
+            "App ID: {}\nConfig: {}\nSettings: {}\nKeymap: {}\nSupport: {}\nDatabase: {}",
+            release_channel.app_id(),
+            paths::config_dir().display(),
+            paths::settings_file().display(),
+            paths::keymap_file().display(),
+            paths::support_dir().display(),
+            paths::database_dir().join(&channel_db_dir).display(),
+        );
+
+        // Each channel has its own database, but everything else is shared, so another
+        // channel's database is a sign that this channel shares its settings and data.
+        let mut other_channels = Vec::new();
+        if let Ok(mut entries) = fs.read_dir(paths::database_dir()).await {
+            while let Some(entry) = entries.next().await {
+                let Some(name) = entry
+                    .ok()
+                    .and_then(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+                else {
+                    continue;
+                };
+                if let Some(channel) = name.strip_prefix("0-") {
+                    if name != channel_db_dir && channel != "global" {
+                        other_channels.push(channel.to_string());
+                    }
+                }
+            }
+        }
+        other_channels.sort();
+
+        let level = if other_channels.is_empty() {
+            PromptLevel::Info
+        } else {
+            info.push_str(&format!(
+                "\n\nWarning: the {} channel(s) also use these directories, and share their \
+                settings, keymap and extensions with this one.",
+                other_channels.join(", ")
+            ));
+            PromptLevel::Warning
+        };
+        let message = format!(
+            "{} ({} channel)",
+            release_channel.display_name(),
+            release_channel.dev_name()
+        );
+        let answer = workspace.update(&mut cx, |_, cx| {
+            cx.prompt(level, &message, Some(&info), &["Copy", "OK"])
+        })?;
+        if answer.await.ok() == Some(0) {
+            cx.update(|cx| cx.write_to_clipboard(ClipboardItem::new_string(info)))?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+fn copy_system_info(_: &CopySystemInfo, cx: &mut AppContext) {
+    let Some(window) = cx.active_window() else {
+        return;
+    };
+    window
+        .update(cx, |_, cx| {
+            let specs = feedback::SystemSpecs::new(cx);
+            let decorations = match cx.window_decorations() {
+                gpui::Decorations::Server => "server",
+                gpui::Decorations::Client { .. } => "client",
+            };
+            cx.spawn(|mut cx| async move {
+                let mut info = specs.await.to_string();
+                let renderer = std::env::var("ZED_RENDERER").unwrap_or_else(|_| "auto".into());
+                info.push_str(&format!(
+                    "\nWindow decorations: {decorations}\nRenderer: {renderer}\nPaths:"
+                ));
+                // Paths are shown relative to the home directory, to leave out the user name.
+                for (name, path) in paths::named_paths() {
+                    let path = match path.strip_prefix(paths::home_dir()) {
+                        Ok(relative_path) => Path::new("~").join(relative_path),
+                        Err(_) => path.clone(),
+                    };
+                    info.push_str(&format!("\n  {name}: {}", path.display()));
+                }
+
+                cx.update(|cx| cx.write_to_clipboard(ClipboardItem::new_string(info.clone())))
+                    .log_err();
+                cx.prompt(
+                    PromptLevel::Info,
+                    "Copied system info to the clipboard",
+                    Some(&info),
+                    &["OK"],
+                )
+                .await
+                .ok();
+            })
+            .detach();
+        })
+        .log_err();
+}
+
+fn export_keymap(workspace: &mut Workspace, _: &ExportKeymap, cx: &mut ViewContext<Workspace>) {
+    let keymap = KeymapFile::from_bindings(cx.key_bindings().borrow().bindings());
+    let content = serde_json::to_string_pretty(&keymap);
+    let fs = workspace.app_state().fs.clone();
+    cx.spawn(|workspace, mut cx| async move {
+        let path = paths::effective_keymap_file();
+        fs.create_dir(paths::temp_dir()).await?;
+        fs.atomic_write(path.clone(), content?).await?;
+        workspace
+            .update(&mut cx, |workspace, cx| {
+                workspace.with_local_workspace(cx, |workspace, cx| {
+                    workspace.open_paths(vec![path.clone()], OpenVisible::None, None, cx)
+                })
+            })?
+            .await?
+            .await;
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Failed to export key bindings", cx, |_, _| None);
+}
+
+/// Writes the pane layout of this window, with the files open in each pane, to a JSON file
+/// that `zed: import layout` can reopen. Paths in the file are relative to where it's saved.
+fn export_layout(workspace: &mut Workspace, _: &ExportLayout, cx: &mut ViewContext<Workspace>) {
+    let fs = workspace.app_state().fs.clone();
+    let directory = workspace
+        .visible_worktrees(cx)
+        .next()
+        .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+        .unwrap_or_else(|| util::paths::home_dir().clone());
+    let prompt = cx.prompt_for_new_path(&directory);
+    cx.spawn(|workspace, mut cx| async move {
+        let Some(path) = prompt.await?? else {
+            return Ok(());
+        };
+        let base_dir = path.parent().unwrap_or(&path).to_path_buf();
+        let layout =
+            workspace.update(&mut cx, |workspace, cx| workspace.layout(&base_dir, cx))??;
+        fs.atomic_write(path.clone(), serde_json::to_string_pretty(&layout)?)
+            .await?;
+        workspace
+            .update(&mut cx, |workspace, cx| {
+                workspace.open_paths(vec![path.clone()], OpenVisible::None, None, cx)
+            })?
+            .await;
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Failed to export layout", cx, |_, _| None);
+}
+
+/// Opens a new window with the layout described by a file written by `zed: export layout`.
+fn import_layout(workspace: &mut Workspace, _: &ImportLayout, cx: &mut ViewContext<Workspace>) {
+    let fs = workspace.app_state().fs.clone();
+    let app_state = workspace.app_state().clone();
+    let prompt = workspace.prompt_for_open_path(
+        PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+        },
+        DirectoryLister::Local(fs.clone()),
+        cx,
+    );
+    cx.spawn(|_, mut cx| async move {
+        let Some(path) = prompt
+            .await
+            .ok()
+            .flatten()
+            .and_then(|mut paths| paths.pop())
+        else {
+            return Ok(());
+        };
+        let content = fs.load(&path).await?;
+        let layout = serde_json::from_str::<workspace::layout::WindowLayout>(&content)
+            .with_context(|| format!("invalid layout file {path:?}"))?;
+        let base_dir = path.parent().unwrap_or(&path).to_path_buf();
+        cx.update(|cx| workspace::layout::open_layout(layout, &base_dir, app_state, cx))?
+            .await?;
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Failed to import layout", cx, |_, _| None);
+}
+
+/// Moves the user keymap aside and replaces it with an empty one, so that only the
+/// default bindings apply. The notification shown afterwards can restore the backup.
+fn reset_keymap(workspace: &mut Workspace, _: &ResetKeymap, cx: &mut ViewContext<Workspace>) {
+    struct ResetKeymapNotification;
+
+    let fs = workspace.app_state().fs.clone();
+    cx.spawn(|workspace, mut cx| async move {
+        let keymap_path = paths::keymap_file();
+        let backup_path = if fs.is_file(keymap_path).await {
+            let backup_path = keymap_backup_path(fs.as_ref(), keymap_path).await;
+            fs.rename(keymap_path, &backup_path, RenameOptions::default())
+                .await?;
+            Some(backup_path)
+        } else {
+            None
+        };
+        // The keymap file watcher picks up the new content and reloads the key bindings.
+        fs.atomic_write(
+            keymap_path.clone(),
+            settings::initial_keymap_content().into_owned(),
+        )
+        .await?;
+
+        workspace.update(&mut cx, |workspace, cx| {
+            workspace.show_notification(
+                NotificationId::unique::<ResetKeymapNotification>(),
+                cx,
+                |cx| {
+                    let message = match &backup_path {
+                        Some(backup_path) => format!(
+                            "Key bindings were reset to the defaults. Your keymap was backed up to {}",
+                            backup_path.display()
+                        ),
+                        None => "Key bindings were reset to the defaults.".to_string(),
+                    };
+                    cx.new_view(|_| {
+                        MessageNotification::new(message)
+                            .with_click_message("Undo")
+                            .on_click(move |cx| {
+                                let fs = fs.clone();
+                                let backup_path = backup_path.clone();
+                                cx.background_executor()
+                                    .spawn(async move {
+                                        // Without a backup, there was no keymap before the reset.
+                                        match backup_path {
+                                            Some(backup_path) => {
+                                                fs.rename(
+                                                    &backup_path,
+                                                    paths::keymap_file(),
+                                                    RenameOptions {
+                                                        overwrite: true,
+                                                        ..Default::default()
+                                                    },
+                                                )
+                                                .await
+                                            }
+                                            None => {
+                                                fs.remove_file(
+                                                    paths::keymap_file(),
+                                                    RemoveOptions {
+                                                        ignore_if_not_exists: true,
+                                                        ..Default::default()
+                                                    },
+                                                )
+                                                .await
+                                            }
+                                        }
+                                    })
+                                    .detach_and_log_err(cx);
+                                cx.emit(DismissEvent);
+                            })
+                    })
+                },
+            );
+        })?;
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Failed to reset the keymap", cx, |_, _| None);
+}
+
+/// Picks a backup path next to the keymap that isn't taken yet, so that resetting the keymap
+/// again never overwrites an earlier backup: `keymap.json.bak`, then `keymap.json.1.bak`, etc.
+async fn keymap_backup_path(fs: &dyn Fs, keymap_path: &Path) -> PathBuf {
+    let mut backup_path = keymap_path.with_extension("json.bak");
+    let mut n = 0;
+    while fs.metadata(&backup_path).await.ok().flatten().is_some() {
+        n += 1;
+        backup_path = keymap_path.with_extension(format!("json.{n}.bak"));
+    }
+    backup_path
+}
+
+/// Reloads every open buffer whose file still exists on disk. Buffers with unsaved
+/// changes are only reloaded if the user agrees to discard those changes.
+fn reload_all_buffers(
+    workspace: &mut Workspace,
+    _: &ReloadAllBuffers,
+    cx: &mut ViewContext<Workspace>,
+) {
+    struct ReloadedBuffers;
+
+    let project = workspace.project().clone();
+    let mut clean_buffers = HashSet::default();
+    let mut dirty_buffers = HashSet::default();
+    let mut deleted_count = 0;
+    for buffer in project.read(cx).opened_buffers(cx) {
+        let snapshot = buffer.read(cx);
+        let Some(file) = snapshot.file() else {
+            continue;
+        };
+        if file.is_deleted() {
+            deleted_count += 1;
+        } else if snapshot.is_dirty() || snapshot.has_conflict() {
+            dirty_buffers.insert(buffer);
+        } else {
+            clean_buffers.insert(buffer);
+        }
+    }
+
+    let discard_prompt = (!dirty_buffers.is_empty()).then(|| {
+        let detail = format!(
+            "{} open {} unsaved changes that will be lost if {} reloaded.",
+            dirty_buffers.len(),
+            if dirty_buffers.len() == 1 {
+                "file has"
+            } else {
+                "files have"
+            },
+            if dirty_buffers.len() == 1 {
+                "it is"
+            } else {
+                "they are"
+            },
+        );
+        cx.prompt(
+            PromptLevel::Warning,
+            "Discard unsaved changes?",
+            Some(&detail),
+            &["Reload All", "Skip Unsaved Files"],
+        )
+    });
+
+    cx.spawn(|workspace, mut cx| async move {
+        if let Some(discard_prompt) = discard_prompt {
+            if discard_prompt.await? == 0 {
+                clean_buffers.extend(dirty_buffers);
+            }
+        }
+
+        let reloaded_count = clean_buffers.len();
+        project
+            .update(&mut cx, |project, cx| {
+                project.reload_buffers(clean_buffers, true, cx)
+            })?
+            .await?;
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let mut message = format!(
+                "Reloaded {reloaded_count} {} from disk.",
+                if reloaded_count == 1 { "file" } else { "files" }
+            );
+            if deleted_count > 0 {
+                message.push_str(&format!(
+                    " Skipped {deleted_count} deleted {}.",
+                    if deleted_count == 1 { "file" } else { "files" }
+                ));
+            }
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<ReloadedBuffers>(), message).autohide(),
+                cx,
+            );
+        })?;
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Failed to reload buffers", cx, |_, _| None);
+}
+
+fn toggle_file_watchers(
+    workspace: &mut Workspace,
+    _: &ToggleFileWatchers,
+    cx: &mut ViewContext<Workspace>,
+) {
+    struct FileWatchersToggled;
+
+    let paused = !settings::file_watchers_paused();
+    settings::set_file_watchers_paused(paused);
+    let message = if paused {
+        "Stopped watching the settings, keymap, tasks and theme files for changes."
+    } else {
+        "Resumed watching the settings, keymap, tasks and theme files for changes."
+    };
+    workspace.show_toast(
+        Toast::new(NotificationId::unique::<FileWatchersToggled>(), message).autohide(),
+        cx,
+    );
+}
+
+fn move_to_display(_: &mut Workspace, _: &MoveToDisplay, cx: &mut ViewContext<Workspace>) {
+    let primary_display = cx.primary_display().map(|display| display.id());
+    let current_display = cx.display().map(|display| display.id());
+    let (display_uuids, mut labels): (Vec<_>, Vec<_>) = cx
+        .displays()
+        .into_iter()
+        .filter_map(|display| Some((display.uuid().ok()?, display)))
+        .enumerate()
+        .map(|(ix, (uuid, display))| {
+            let size = display.bounds().size;
+            let mut label = format!(
+                "Display {} ({}×{})",
+                ix + 1,
+                u32::from(size.width),
+                u32::from(size.height)
+            );
+            if Some(display.id()) == primary_display {
+                label.push_str(", primary");
+            }
+            if Some(display.id()) == current_display {
+                label.push_str(", current");
+            }
+            (uuid, label)
+        })
+        .unzip();
+
+    if display_uuids.len() < 2 {
+        let prompt = cx.prompt(
+            PromptLevel::Info,
+            "No other displays are connected",
+            None,
+            &["OK"],
+        );
+        cx.background_executor().spawn(prompt).detach();
+        return;
+    }
+
+    labels.push("Cancel".to_string());
+    let answers = labels.iter().map(String::as_str).collect::<Vec<_>>();
+    let prompt = cx.prompt(PromptLevel::Info, "Move window to display", None, &answers);
+    cx.spawn(|workspace, mut cx| async move {
+        let Some(display_uuid) = display_uuids.get(prompt.await?).copied() else {
+            return Ok(());
+        };
+        workspace
+            .update(&mut cx, |workspace, cx| {
+                workspace.move_to_display(display_uuid, cx)
+            })?
+            .await
+    })
+    .detach_and_prompt_err("Failed to move window", cx, |_, _| None);
+}
+
+/// Flips `vim_mode` in the user's settings file. The settings observer in
+/// `handle_keymap_file_changes` picks up the change and reloads the keymap.
+fn toggle_vim_mode(_: &ToggleVimMode, cx: &mut AppContext) {
+    let fs = <dyn Fs>::global(cx);
+    let currently_enabled = VimModeSetting::get_global(cx).0;
+    update_settings_file::<VimModeSetting>(fs, cx, move |setting, _| {
+        *setting = Some(!currently_enabled)
+    });
+}
+
 fn test_panic(_: &TestPanic, _: &mut AppContext) {
     panic!("Ran the TestPanic action")
 }
 
 fn quit(_: &Quit, cx: &mut AppContext) {
-    let should_confirm = WorkspaceSettings::get_global(cx).confirm_quit;
+    quit_or_restart(false, cx);
+}
+
+/// Quits the same way [`Quit`] does, then starts this binary again with the same arguments.
+/// The session is restored by the new instance like after any other quit.
+fn restart_app(_: &RestartApp, cx: &mut AppContext) {
+    quit_or_restart(true, cx);
+}
+
+fn quit_or_restart(restart: bool, cx: &mut AppContext) {
+    let workspace_settings = WorkspaceSettings::get_global(cx);
+    let should_confirm = workspace_settings.confirm_quit;
+    let confirm_without_unsaved_files = workspace_settings.confirm_quit_without_unsaved_files;
+    let confirm_running_tasks_once =
+        workspace_settings.quit_with_running_tasks == QuitWithRunningTasks::ConfirmOnce;
     cx.spawn(|mut cx| async move {
-        let mut workspace_windows = cx.update(|cx| {
-            cx.windows()
-                .into_iter()
-                .filter_map(|window| window.downcast::<Workspace>())
-                .collect::<Vec<_>>()
-        })?;
+        let mut workspace_windows = cx.update(workspace::workspace_windows)?;
 
         // If multiple windows have unsaved changes, and need a save prompt,
         // prompt in the active window before switching to a different window.
@@ -636,14 +1182,50 @@ fn quit(_: &Quit, cx: &mut AppContext) {
         })
         .log_err();
 
+        let unsaved_files = cx
+            .update(|cx| unsaved_file_count(&workspace_windows, cx))
+            .unwrap_or(0);
+        let running_tasks = if confirm_running_tasks_once {
+            cx.update(|cx| running_task_count(&workspace_windows, cx))
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        let should_confirm = running_tasks > 0
+            || should_confirm && (unsaved_files > 0 || confirm_without_unsaved_files);
+
         if let (true, Some(workspace)) = (should_confirm, workspace_windows.first().copied()) {
+            let unsaved_files_detail = match unsaved_files {
+                0 => None,
+                1 => Some("You have 1 unsaved file.".to_string()),
+                count => Some(format!("You have {count} unsaved files.")),
+            };
+            let running_tasks_detail = match running_tasks {
+                0 => None,
+                1 => Some("1 task is still running.".to_string()),
+                count => Some(format!("{count} tasks are still running.")),
+            };
+            let detail = [running_tasks_detail, unsaved_files_detail]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            let detail = (!detail.is_empty()).then(|| detail.join(" "));
+            let (message, quit_label) = match (restart, running_tasks > 0) {
+                (false, false) => ("Are you sure you want to quit?", "Quit"),
+                (false, true) => ("Are you sure you want to quit?", "Stop Tasks and Quit"),
+                (true, false) => ("Are you sure you want to restart?", "Restart"),
+                (true, true) => (
+                    "Are you sure you want to restart?",
+                    "Stop Tasks and Restart",
+                ),
+            };
             let answer = workspace
                 .update(&mut cx, |_, cx| {
                     cx.prompt(
                         PromptLevel::Info,
-                        "Are you sure you want to quit?",
-                        None,
-                        &["Quit", "Cancel"],
+                        message,
+                        detail.as_deref(),
+                        &[quit_label, "Cancel"],
                     )
                 })
                 .log_err();
@@ -669,12 +1251,94 @@ fn quit(_: &Quit, cx: &mut AppContext) {
                 }
             }
         }
+
+        // Give subsystems a chance to persist their state, or to veto the quit.
+        if !workspace::run_quit_hooks(&mut cx).await? {
+            return Ok(());
+        }
+        if restart {
+            // The new instance waits for this one to exit before taking the single-instance
+            // lock, so it's fine to start it before quitting.
+            relaunch_current_process().context("failed to restart")?;
+        }
         cx.update(|cx| cx.quit())?;
         anyhow::Ok(())
     })
     .detach_and_log_err(cx);
 }
 
+fn relaunch_current_process() -> anyhow::Result<()> {
+    let binary_path = std::env::current_exe()?;
+    let args = relaunch_args(std::env::args_os().skip(1), std::process::id());
+    std::process::Command::new(binary_path).args(args).spawn()?;
+    Ok(())
+}
+
+/// The flags that configure the whole session, and are kept when relaunching. Everything else,
+/// like paths to open or one-shot flags such as `--reset-settings`, is dropped, so that the
+/// relaunched process restores the session instead.
+const PERSISTENT_FLAGS: [&str; 2] = ["--safe-mode", "--insecure"];
+/// Like [`PERSISTENT_FLAGS`], for the flags that take a value.
+const PERSISTENT_FLAGS_WITH_VALUE: [&str; 5] = [
+    "--renderer",
+    "--theme",
+    "--keymap",
+    "--env-file",
+    "--dev-server-token",
+];
+
+/// Returns the arguments for relaunching, given the ones of the current process: the
+/// [`PERSISTENT_FLAGS`] among them, and a `--relaunch-after` for the current process.
+fn relaunch_args(
+    args: impl IntoIterator<Item = std::ffi::OsString>,
+    pid: u32,
+) -> Vec<std::ffi::OsString> {
+    let mut result = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let Some(flag) = arg.to_str().map(|arg| arg.split('=').next().unwrap_or(arg)) else {
+            continue;
+        };
+        if PERSISTENT_FLAGS.contains(&flag) {
+            result.push(arg);
+        } else if PERSISTENT_FLAGS_WITH_VALUE.contains(&flag) {
+            let has_value = flag.len() < arg.len();
+            result.push(arg);
+            if !has_value {
+                result.extend(args.next());
+            }
+        }
+    }
+    result.push("--relaunch-after".into());
+    result.push(pid.to_string().into());
+    result
+}
+
+/// Counts the tasks running in terminals across the given workspace windows.
+fn running_task_count(workspace_windows: &[WindowHandle<Workspace>], cx: &AppContext) -> usize {
+    workspace_windows
+        .iter()
+        .filter_map(|window| window.read(cx).log_err())
+        .map(|workspace| workspace.project().read(cx).running_task_count(cx))
+        .sum()
+}
+
+/// Counts the distinct dirty buffers across the given workspace windows.
+fn unsaved_file_count(workspace_windows: &[WindowHandle<Workspace>], cx: &AppContext) -> usize {
+    let mut dirty_buffers = HashSet::default();
+    for window in workspace_windows {
+        let Some(workspace) = window.read(cx).log_err() else {
+            continue;
+        };
+        for buffer in workspace.project().read(cx).opened_buffers(cx) {
+            if buffer.read(cx).is_dirty() {
+                dirty_buffers.insert(buffer.entity_id());
+            }
+        }
+    }
+    dirty_buffers.len()
+}
+
 fn open_log_file(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
     const MAX_LINES: usize = 1000;
     workspace
@@ -765,7 +1429,7 @@ fn open_log_file(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
 pub fn handle_keymap_file_changes(
     mut user_keymap_file_rx: mpsc::UnboundedReceiver<String>,
     cx: &mut AppContext,
-    keymap_changed: impl Fn(Option<anyhow::Error>, &mut AppContext) + 'static,
+    keymap_changed: impl Fn(Option<anyhow::Error>, Vec<KeymapConflict>, &mut AppContext) + 'static,
 ) {
     BaseKeymap::register(cx);
     VimModeSetting::register(cx);
@@ -796,11 +1460,13 @@ pub fn handle_keymap_file_changes(
                     if let Some(user_keymap_content) = user_keymap_content {
                         match KeymapFile::parse(&user_keymap_content) {
                             Ok(keymap_content) => {
-                                cx.update(|cx| keymap_changed(None, cx)).log_err();
+                                let conflicts = keymap_content.conflicts();
+                                cx.update(|cx| keymap_changed(None, conflicts, cx)).log_err();
                                 user_keymap = keymap_content;
                             }
                             Err(error) => {
-                                cx.update(|cx| keymap_changed(Some(error), cx)).log_err();
+                                cx.update(|cx| keymap_changed(Some(error), Vec::new(), cx))
+                                    .log_err();
                             }
                         }
                     }
@@ -812,6 +1478,67 @@ pub fn handle_keymap_file_changes(
     .detach();
 }
 
+/// Spawns a background task to watch the themes directory for changes.
+///
+/// Changed themes are reloaded into the [`ThemeRegistry`]. Windows are only repainted
+/// when the active theme is among them. All themes are reloaded after the file watchers
+/// are resumed.
+pub fn watch_themes(fs: Arc<dyn Fs>, themes_dir: &'static Path, cx: &mut AppContext) {
+    cx.spawn(|cx| async move {
+        let mut events = settings::watch_unless_paused(
+            fs.clone(),
+            themes_dir.to_path_buf(),
+            Duration::from_millis(100),
+        )
+        .await;
+
+        while let Some(events) = events.next().await {
+            if events.is_empty() {
+                let Some(theme_registry) =
+                    cx.update(|cx| ThemeRegistry::global(cx).clone()).log_err()
+                else {
+                    break;
+                };
+                theme_registry
+                    .load_user_themes(themes_dir, fs.clone())
+                    .await
+                    .log_err();
+                cx.update(ThemeSettings::reload_current_theme).log_err();
+                continue;
+            }
+            let mut changed_paths = HashSet::default();
+            for event in events {
+                if !changed_paths.insert(event.path.clone()) {
+                    continue;
+                }
+                if fs.metadata(&event.path).await.ok().flatten().is_none() {
+                    continue;
+                }
+                let Some(theme_family) = ThemeRegistry::read_user_theme(&event.path, fs.clone())
+                    .await
+                    .log_err()
+                else {
+                    continue;
+                };
+                let theme_names = theme_family
+                    .themes
+                    .iter()
+                    .map(|theme| SharedString::from(theme.name.clone()))
+                    .collect::<Vec<_>>();
+                cx.update(|cx| {
+                    ThemeRegistry::global(cx).insert_user_themes(theme_family.themes);
+                    // The theme selected in the settings may only just have been installed.
+                    if !ThemeSettings::reload_active_theme_if_changed(&theme_names, cx) {
+                        ThemeSettings::reload_current_theme(cx);
+                    }
+                })
+                .log_err();
+            }
+        }
+    })
+    .detach()
+}
+
 fn reload_keymaps(cx: &mut AppContext, keymap_content: &KeymapFile) {
     cx.clear_key_bindings();
     load_default_keymap(cx);
@@ -986,7 +1713,7 @@ fn open_bundled_file(
     title: &'static str,
     language: &'static str,
     cx: &mut ViewContext<Workspace>,
-) {
+) -> Task<anyhow::Result<View<Editor>>> {
     let language = workspace.app_state().languages.language_for_name(language);
     cx.spawn(|workspace, mut cx| async move {
         let language = language.await.log_err();
@@ -1000,30 +1727,26 @@ fn open_bundled_file(
                     let buffer = cx.new_model(|cx| {
                         MultiBuffer::singleton(buffer, cx).with_title(title.into())
                     });
-                    workspace.add_item_to_active_pane(
-                        Box::new(cx.new_view(|cx| {
-                            let mut editor =
-                                Editor::for_multibuffer(buffer, Some(project.clone()), true, cx);
-                            editor.set_read_only(true);
-                            editor.set_breadcrumb_header(title.into());
-                            editor
-                        })),
-                        None,
-                        true,
-                        cx,
-                    );
+                    let editor = cx.new_view(|cx| {
+                        let mut editor =
+                            Editor::for_multibuffer(buffer, Some(project.clone()), true, cx);
+                        editor.set_read_only(true);
+                        editor.set_breadcrumb_header(title.into());
+                        editor
+                    });
+                    workspace.add_item_to_active_pane(Box::new(editor.clone()), None, true, cx);
+                    editor
                 })
             })?
             .await
     })
-    .detach_and_log_err(cx);
 }
 
 fn open_settings_file(
     abs_path: &'static Path,
     default_content: impl FnOnce() -> Rope + Send + 'static,
     cx: &mut ViewContext<Workspace>,
-) {
+) -> Task<anyhow::Result<Box<dyn ItemHandle>>> {
     cx.spawn(|workspace, mut cx| async move {
         let (worktree_creation_task, settings_open_task) =
             workspace.update(&mut cx, |workspace, cx| {
@@ -1037,10 +1760,65 @@ fn open_settings_file(
             })?;
 
         let _ = worktree_creation_task.await?;
-        let _ = settings_open_task.await?;
+        settings_open_task.await
+    })
+}
+
+/// Opens the user settings, the keymap and the default settings next to each other.
+fn open_config_files(_: &mut Workspace, _: &OpenConfigFiles, cx: &mut ViewContext<Workspace>) {
+    cx.spawn(|workspace, mut cx| async move {
+        let settings = workspace
+            .update(&mut cx, |_, cx| {
+                open_settings_file(
+                    paths::settings_file(),
+                    || settings::initial_user_settings_content().as_ref().into(),
+                    cx,
+                )
+            })?
+            .await?;
+        let keymap = workspace
+            .update(&mut cx, |_, cx| {
+                open_settings_file(
+                    paths::keymap_file(),
+                    || settings::initial_keymap_content().as_ref().into(),
+                    cx,
+                )
+            })?
+            .await?;
+        let default_settings = workspace
+            .update(&mut cx, |workspace, cx| {
+                open_bundled_file(
+                    workspace,
+                    settings::default_settings(),
+                    "Default Settings",
+                    "JSON",
+                    cx,
+                )
+            })?
+            .await?;
+
+        // All three were opened in the active pane, so move the others into splits to the right.
+        workspace.update(&mut cx, |workspace, cx| {
+            let mut left = settings;
+            for item in [keymap, Box::new(default_settings) as Box<dyn ItemHandle>] {
+                let (Some(left_pane), Some(item_pane)) =
+                    (workspace.pane_for(&*left), workspace.pane_for(&*item))
+                else {
+                    continue;
+                };
+                workspace.split_pane_with_item(
+                    left_pane.downgrade(),
+                    SplitDirection::Right,
+                    item_pane.downgrade(),
+                    item.item_id(),
+                    cx,
+                );
+                left = item;
+            }
+        })?;
         anyhow::Ok(())
     })
-    .detach_and_log_err(cx);
+    .detach_and_prompt_err("Failed to open the configuration files", cx, |_, _| None);
 }
 
 async fn register_zed_scheme(cx: &AsyncAppContext) -> anyhow::Result<()> {
@@ -1347,15 +2125,58 @@ mod tests {
     }
 
     #[gpui::test]
-    async fn test_window_edit_state_restoring_disabled(cx: &mut TestAppContext) {
-        let executor = cx.executor();
+    async fn test_unsaved_file_count(cx: &mut TestAppContext) {
         let app_state = init_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree("/root", json!({"a": "hey", "b": "there"}))
+            .await;
 
         cx.update(|cx| {
-            SettingsStore::update_global(cx, |store, cx| {
-                store.update_user_settings::<ProjectSettings>(cx, |settings| {
-                    settings.session.restore_unsaved_buffers = false
-                });
+            open_paths(
+                &[PathBuf::from("/root/a"), PathBuf::from("/root/b")],
+                app_state.clone(),
+                workspace::OpenOptions::default(),
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+        let window = cx.update(|cx| cx.windows()[0].downcast::<Workspace>().unwrap());
+        let unsaved_files =
+            |cx: &mut TestAppContext| cx.update(|cx| unsaved_file_count(&[window], cx));
+        assert_eq!(unsaved_files(cx), 0);
+
+        let editors = window
+            .read_with(cx, |workspace, cx| {
+                workspace.items_of_type::<Editor>(cx).collect::<Vec<_>>()
+            })
+            .unwrap();
+        assert_eq!(editors.len(), 2);
+        for editor in &editors {
+            window
+                .update(cx, |_, cx| {
+                    editor.update(cx, |editor, cx| editor.insert("EDIT", cx));
+                })
+                .unwrap();
+        }
+        assert_eq!(unsaved_files(cx), 2);
+
+        // Passing the same window twice doesn't double count its buffers.
+        assert_eq!(cx.update(|cx| unsaved_file_count(&[window, window], cx)), 2);
+    }
+
+    #[gpui::test]
+    async fn test_window_edit_state_restoring_disabled(cx: &mut TestAppContext) {
+        let executor = cx.executor();
+        let app_state = init_test(cx);
+
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<ProjectSettings>(cx, |settings| {
+                    settings.session.restore_unsaved_buffers = false
+                });
             });
         });
 
@@ -1992,6 +2813,143 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_open_paths_in_given_order(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                "/root",
+                json!({
+                    "a.txt": "a",
+                    "b.txt": "b",
+                    "c.txt": "c",
+                    "d.txt": "d",
+                }),
+            )
+            .await;
+
+        let file_names = |items: &[Box<dyn ItemHandle>], cx: &AppContext| {
+            items
+                .iter()
+                .map(|item| item.project_path(cx).unwrap().path.to_path_buf())
+                .collect::<Vec<_>>()
+        };
+
+        // Opening in a new window.
+        let (window, opened_items) = cx
+            .update(|cx| {
+                open_paths(
+                    &[
+                        PathBuf::from("/root/c.txt"),
+                        PathBuf::from("/root/a.txt"),
+                        PathBuf::from("/root/b.txt"),
+                    ],
+                    app_state.clone(),
+                    workspace::OpenOptions::default(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        let opened_items = opened_items
+            .into_iter()
+            .map(|item| item.unwrap().unwrap())
+            .collect::<Vec<_>>();
+        let expected = ["c.txt", "a.txt", "b.txt"].map(PathBuf::from);
+        window
+            .read_with(cx, |workspace, cx| {
+                assert_eq!(file_names(&opened_items, cx), expected);
+                let pane_items = workspace
+                    .active_pane()
+                    .read(cx)
+                    .items()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                assert_eq!(file_names(&pane_items, cx), expected);
+            })
+            .unwrap();
+
+        // Opening in an existing window appends the new tabs in the given order.
+        let opened_items = window
+            .update(cx, |workspace, cx| {
+                workspace.open_paths(
+                    vec!["/root/d.txt".into(), "/root/a.txt".into()],
+                    OpenVisible::All,
+                    None,
+                    cx,
+                )
+            })
+            .unwrap()
+            .await
+            .into_iter()
+            .map(|item| item.unwrap().unwrap())
+            .collect::<Vec<_>>();
+        window
+            .read_with(cx, |workspace, cx| {
+                assert_eq!(
+                    file_names(&opened_items, cx),
+                    ["d.txt", "a.txt"].map(PathBuf::from)
+                );
+                let pane_items = workspace
+                    .active_pane()
+                    .read(cx)
+                    .items()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    file_names(&pane_items, cx),
+                    ["c.txt", "a.txt", "b.txt", "d.txt"].map(PathBuf::from)
+                );
+            })
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_open_duplicate_paths(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree("/root", json!({ "a.txt": "a", "b.txt": "b" }))
+            .await;
+        app_state
+            .fs
+            .as_fake()
+            .insert_symlink("/root/link.txt", "/root/a.txt".into())
+            .await;
+
+        let (window, opened_items) = cx
+            .update(|cx| {
+                open_paths(
+                    &[
+                        PathBuf::from("/root/a.txt"),
+                        PathBuf::from("/root/b.txt"),
+                        PathBuf::from("/root/a.txt"),
+                        PathBuf::from("/root/link.txt"),
+                    ],
+                    app_state.clone(),
+                    workspace::OpenOptions::default(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        let opened_items = opened_items
+            .into_iter()
+            .map(|item| item.unwrap().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(opened_items.len(), 4);
+        assert_eq!(opened_items[0].item_id(), opened_items[2].item_id());
+        assert_eq!(opened_items[0].item_id(), opened_items[3].item_id());
+        window
+            .read_with(cx, |workspace, cx| {
+                assert_eq!(workspace.active_pane().read(cx).items_len(), 2);
+            })
+            .unwrap();
+    }
+
     #[gpui::test]
     async fn test_opening_excluded_paths(cx: &mut TestAppContext) {
         let app_state = init_test(cx);
@@ -3050,6 +4008,163 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    async fn test_reopening_closed_items_across_panes(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                "/root",
+                json!({
+                    "a": {
+                        "file1": "",
+                        "file2": "",
+                        "file3": "",
+                        "file4": "",
+                    },
+                }),
+            )
+            .await;
+
+        let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project, cx));
+        let left_pane = workspace
+            .read_with(cx, |workspace, _| workspace.active_pane().clone())
+            .unwrap();
+
+        let entries = cx.update(|cx| workspace.root(cx).unwrap().file_project_paths(cx));
+        let file1 = entries[0].clone();
+        let file2 = entries[1].clone();
+        let file3 = entries[2].clone();
+        let file4 = entries[3].clone();
+
+        let file1_item_id = workspace
+            .update(cx, |w, cx| w.open_path(file1.clone(), None, true, cx))
+            .unwrap()
+            .await
+            .unwrap()
+            .item_id();
+        workspace
+            .update(cx, |w, cx| w.open_path(file2.clone(), None, true, cx))
+            .unwrap()
+            .await
+            .unwrap();
+        let right_pane = workspace
+            .update(cx, |w, cx| {
+                w.split_and_clone(w.active_pane().clone(), SplitDirection::Right, cx)
+            })
+            .unwrap()
+            .unwrap();
+        let file3_item_id = workspace
+            .update(cx, |w, cx| w.open_path(file3.clone(), None, true, cx))
+            .unwrap()
+            .await
+            .unwrap()
+            .item_id();
+        let file4_item_id = workspace
+            .update(cx, |w, cx| w.open_path(file4.clone(), None, true, cx))
+            .unwrap()
+            .await
+            .unwrap()
+            .item_id();
+
+        // Close an item in the right pane, then one in the left pane.
+        workspace
+            .update(cx, |_, cx| {
+                right_pane.update(cx, |pane, cx| {
+                    pane.close_item_by_id(file3_item_id, SaveIntent::Close, cx)
+                })
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        workspace
+            .update(cx, |_, cx| {
+                left_pane.update(cx, |pane, cx| {
+                    pane.close_item_by_id(file1_item_id, SaveIntent::Close, cx)
+                })
+            })
+            .unwrap()
+            .await
+            .unwrap();
+
+        // The most recently closed item is reopened, even though its pane isn't active.
+        workspace
+            .update(cx, Workspace::reopen_closed_item)
+            .unwrap()
+            .await
+            .unwrap();
+        workspace
+            .read_with(cx, |workspace, _| {
+                assert_eq!(workspace.active_pane(), &left_pane);
+            })
+            .unwrap();
+        assert_eq!(
+            workspace
+                .read_with(cx, |w, cx| w.active_item(cx)?.project_path(cx))
+                .unwrap(),
+            Some(file1.clone())
+        );
+
+        // Files that were deleted after being closed are skipped, and the next most recently
+        // closed item is reopened, even if it's in another pane.
+        let file2_item_id = workspace
+            .read_with(cx, |_, cx| {
+                left_pane
+                    .read(cx)
+                    .items()
+                    .find(|item| item.project_path(cx) == Some(file2.clone()))
+                    .unwrap()
+                    .item_id()
+            })
+            .unwrap();
+        workspace
+            .update(cx, |_, cx| {
+                left_pane.update(cx, |pane, cx| {
+                    pane.close_item_by_id(file2_item_id, SaveIntent::Close, cx)
+                })
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        workspace
+            .update(cx, |_, cx| {
+                right_pane.update(cx, |pane, cx| {
+                    pane.close_item_by_id(file4_item_id, SaveIntent::Close, cx)
+                })
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        app_state
+            .fs
+            .remove_file("/root/a/file4".as_ref(), Default::default())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        workspace
+            .update(cx, Workspace::reopen_closed_item)
+            .unwrap()
+            .await
+            .unwrap();
+        workspace
+            .read_with(cx, |workspace, cx| {
+                assert_eq!(workspace.active_pane(), &left_pane);
+                assert_eq!(
+                    workspace.active_item(cx).unwrap().project_path(cx),
+                    Some(file2.clone())
+                );
+                assert_eq!(
+                    workspace.notification_messages(cx),
+                    [SharedString::from(
+                        "Skipped a/file4, which was deleted after it was closed."
+                    )]
+                );
+            })
+            .unwrap();
+    }
+
     fn init_keymap_test(cx: &mut TestAppContext) -> Arc<AppState> {
         cx.update(|cx| {
             let app_state = AppState::test(cx);
@@ -3123,7 +4238,7 @@ mod tests {
                 PathBuf::from("/keymap.json"),
             );
             handle_settings_file_changes(settings_rx, cx, |_, _| {});
-            handle_keymap_file_changes(keymap_rx, cx, |_, _| {});
+            handle_keymap_file_changes(keymap_rx, cx, |_, _, _| {});
         });
         workspace
             .update(cx, |workspace, cx| {
@@ -3263,7 +4378,7 @@ mod tests {
             );
 
             handle_settings_file_changes(settings_rx, cx, |_, _| {});
-            handle_keymap_file_changes(keymap_rx, cx, |_, _| {});
+            handle_keymap_file_changes(keymap_rx, cx, |_, _, _| {});
         });
 
         cx.background_executor.run_until_parked();
@@ -3361,6 +4476,248 @@ mod tests {
         assert!(has_default_theme);
     }
 
+    #[gpui::test]
+    async fn test_reloading_edited_themes(cx: &mut TestAppContext) {
+        fn theme_family(name: &str, background: &str) -> String {
+            json!({
+                "name": name,
+                "author": "Zed",
+                "themes": [{
+                    "name": name,
+                    "appearance": "dark",
+                    "style": { "background": background },
+                }],
+            })
+            .to_string()
+        }
+
+        let app_state = init_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                "/themes",
+                json!({
+                    "active.json": theme_family("Active", "#ff0000ff"),
+                    "other.json": theme_family("Other", "#00ff00ff"),
+                }),
+            )
+            .await;
+        let registry = cx.update(|cx| ThemeRegistry::global(cx).clone());
+        registry
+            .load_user_themes(Path::new("/themes"), app_state.fs.clone())
+            .await
+            .unwrap();
+        cx.update(|cx| {
+            select_theme("Active", cx);
+            watch_themes(app_state.fs.clone(), Path::new("/themes"), cx);
+        });
+        cx.run_until_parked();
+
+        // Editing the active theme repaints with the new colors, even after several quick saves.
+        for background in ["#0000ffff", "#ffff00ff", "#ff00ffff"] {
+            app_state
+                .fs
+                .save(
+                    "/themes/active.json".as_ref(),
+                    &theme_family("Active", background).into(),
+                    Default::default(),
+                )
+                .await
+                .unwrap();
+        }
+        cx.run_until_parked();
+        cx.update(|cx| {
+            assert_eq!(cx.theme().name, "Active");
+            assert_eq!(
+                cx.theme().colors().background,
+                gpui::rgba(0xff00ffff).into()
+            );
+        });
+
+        // Editing another theme only updates the registry.
+        app_state
+            .fs
+            .save(
+                "/themes/other.json".as_ref(),
+                &theme_family("Other", "#000000ff").into(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        cx.update(|cx| {
+            assert_eq!(cx.theme().name, "Active");
+            assert_eq!(
+                cx.theme().colors().background,
+                gpui::rgba(0xff00ffff).into()
+            );
+        });
+        assert_eq!(
+            registry.get("Other").unwrap().colors().background,
+            gpui::rgba(0x000000ff).into()
+        );
+
+        // A theme that is selected before it's installed becomes active once its file appears.
+        cx.update(|cx| select_theme("New", cx));
+        cx.run_until_parked();
+        cx.update(|cx| assert_ne!(cx.theme().name, "New"));
+        app_state
+            .fs
+            .save(
+                "/themes/new.json".as_ref(),
+                &theme_family("New", "#123456ff").into(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        cx.update(|cx| {
+            assert_eq!(cx.theme().name, "New");
+            assert_eq!(
+                cx.theme().colors().background,
+                gpui::rgba(0x123456ff).into()
+            );
+        });
+
+        fn select_theme(name: &str, cx: &mut AppContext) {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<ThemeSettings>(cx, |settings| {
+                    settings.theme = Some(theme::ThemeSelection::Static(name.to_string()));
+                });
+            });
+            ThemeSettings::reload_current_theme(cx);
+        }
+    }
+
+    #[test]
+    fn test_relaunch_args() {
+        let args = |args: &[&str]| {
+            args.iter()
+                .map(std::ffi::OsString::from)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            relaunch_args(args(&["--safe-mode", "--theme", "One Dark", "a.rs"]), 42),
+            args(&[
+                "--safe-mode",
+                "--theme",
+                "One Dark",
+                "--relaunch-after",
+                "42"
+            ])
+        );
+        assert_eq!(
+            relaunch_args(args(&["--renderer=gpu", "--reset-settings"]), 42),
+            args(&["--renderer=gpu", "--relaunch-after", "42"])
+        );
+        assert_eq!(
+            relaunch_args(args(&["--relaunch-after", "7", "a.rs"]), 42),
+            args(&["--relaunch-after", "42"])
+        );
+        assert_eq!(
+            relaunch_args(args(&["--relaunch-after=7", "--insecure"]), 42),
+            args(&["--insecure", "--relaunch-after", "42"])
+        );
+    }
+
+    #[gpui::test]
+    async fn test_keymap_backup_path(cx: &mut TestAppContext) {
+        let fs = fs::FakeFs::new(cx.executor());
+        let keymap_path = Path::new("/config/keymap.json");
+        fs.insert_tree("/config", json!({ "keymap.json": "[]" }))
+            .await;
+        assert_eq!(
+            keymap_backup_path(fs.as_ref(), keymap_path).await,
+            Path::new("/config/keymap.json.bak")
+        );
+
+        fs.insert_file("/config/keymap.json.bak", Vec::new()).await;
+        fs.insert_file("/config/keymap.json.1.bak", Vec::new())
+            .await;
+        assert_eq!(
+            keymap_backup_path(fs.as_ref(), keymap_path).await,
+            Path::new("/config/keymap.json.2.bak")
+        );
+    }
+
+    #[gpui::test]
+    async fn test_layout_round_trip(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                "/code",
+                json!({
+                    "zed": { "src": { "main.rs": "", "lib.rs": "" } },
+                    "docs": { "intro.md": "" },
+                }),
+            )
+            .await;
+
+        cx.update(|cx| {
+            open_paths(
+                &[PathBuf::from("/code/zed"), PathBuf::from("/code/docs")],
+                app_state.clone(),
+                workspace::OpenOptions::default(),
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+        let window = cx.update(|cx| cx.windows()[0].downcast::<Workspace>().unwrap());
+        window
+            .update(cx, |workspace, cx| {
+                workspace.open_paths(
+                    vec!["/code/zed/src/main.rs".into()],
+                    OpenVisible::None,
+                    None,
+                    cx,
+                )
+            })
+            .unwrap()
+            .await;
+        window
+            .update(cx, |workspace, cx| {
+                workspace.split_pane(workspace.active_pane().clone(), SplitDirection::Right, cx);
+                workspace.open_paths(
+                    vec!["/code/docs/intro.md".into()],
+                    OpenVisible::None,
+                    None,
+                    cx,
+                )
+            })
+            .unwrap()
+            .await;
+        cx.run_until_parked();
+
+        let base_dir = Path::new("/code/zed");
+        let layout = window
+            .read_with(cx, |workspace, cx| workspace.layout(base_dir, cx))
+            .unwrap()
+            .unwrap();
+        let mut roots = layout.roots.clone();
+        roots.sort();
+        assert_eq!(roots, [PathBuf::from("."), PathBuf::from("../docs")]);
+
+        let json = serde_json::to_string(&layout).unwrap();
+        let imported = serde_json::from_str(&json).unwrap();
+        let new_window = cx
+            .update(|cx| workspace::layout::open_layout(imported, base_dir, app_state, cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(cx.update(|cx| cx.windows().len()), 2);
+        assert_eq!(
+            new_window
+                .read_with(cx, |workspace, cx| workspace.layout(base_dir, cx))
+                .unwrap()
+                .unwrap(),
+            layout
+        );
+    }
+
     #[gpui::test]
     async fn test_bundled_languages(cx: &mut TestAppContext) {
         env_logger::builder().is_test(true).try_init().ok();
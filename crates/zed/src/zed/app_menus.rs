@@ -18,6 +18,7 @@ pub fn app_menus() -> Vec<Menu> {
                         MenuItem::action("Open Settings", super::OpenSettings),
                         MenuItem::action("Open Key Bindings", zed_actions::OpenKeymap),
                         MenuItem::action("Open Default Settings", super::OpenDefaultSettings),
+                        MenuItem::action("Open Configuration Files", super::OpenConfigFiles),
                         MenuItem::action("Open Default Key Bindings", super::OpenDefaultKeymap),
                         MenuItem::action("Open Local Settings", super::OpenLocalSettings),
                         MenuItem::action("Select Theme...", theme_selector::Toggle::default()),
@@ -37,6 +38,7 @@ pub fn app_menus() -> Vec<Menu> {
             items: vec![
                 MenuItem::action("New", workspace::NewFile),
                 MenuItem::action("New Window", workspace::NewWindow),
+                MenuItem::action("Duplicate Window", workspace::DuplicateWindow),
                 MenuItem::separator(),
                 MenuItem::action("Open…", workspace::Open),
                 MenuItem::action(
@@ -112,6 +114,7 @@ pub fn app_menus() -> Vec<Menu> {
                 MenuItem::action("Toggle Right Dock", workspace::ToggleRightDock),
                 MenuItem::action("Toggle Bottom Dock", workspace::ToggleBottomDock),
                 MenuItem::action("Close All Docks", workspace::CloseAllDocks),
+                MenuItem::action("Toggle Zen Mode", workspace::ToggleZenMode),
                 MenuItem::submenu(Menu {
                     name: "Editor Layout".into(),
                     items: vec![
@@ -158,6 +161,7 @@ pub fn app_menus() -> Vec<Menu> {
             items: vec![
                 MenuItem::action("Minimize", super::Minimize),
                 MenuItem::action("Zoom", super::Zoom),
+                MenuItem::action("Move to Display…", super::MoveToDisplay),
                 MenuItem::separator(),
             ],
         },
@@ -168,6 +172,7 @@ pub fn app_menus() -> Vec<Menu> {
                 MenuItem::action("View Dependency Licenses", zed_actions::OpenLicenses),
                 MenuItem::action("Show Welcome", workspace::Welcome),
                 MenuItem::action("Give Feedback...", feedback::GiveFeedback),
+                MenuItem::action("Report a Crash...", feedback::ReportCrash),
                 MenuItem::separator(),
                 MenuItem::action(
                     "Documentation",
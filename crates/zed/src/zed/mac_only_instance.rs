@@ -5,14 +5,18 @@ use std::{
     time::Duration,
 };
 
+use anyhow::{Context as _, Result};
 use sysinfo::System;
 
 use release_channel::ReleaseChannel;
 
+use super::{OpenListener, PING_URL_PREFIX};
+
 const LOCALHOST: Ipv4Addr = Ipv4Addr::new(127, 0, 0, 1);
 const CONNECT_TIMEOUT: Duration = Duration::from_millis(10);
 const RECEIVE_TIMEOUT: Duration = Duration::from_millis(35);
 const SEND_TIMEOUT: Duration = Duration::from_millis(20);
+const PING_TIMEOUT: Duration = Duration::from_millis(500);
 const USER_BLOCK: u16 = 100;
 
 fn address() -> SocketAddr {
@@ -86,7 +90,11 @@ pub enum IsOnlyInstance {
     No,
 }
 
-pub fn ensure_only_instance() -> IsOnlyInstance {
+/// Claims the single-instance port, unless another instance answers on it.
+///
+/// After the handshake, `zed --health-check` sends a ping, which is passed to `opener` so that
+/// it's answered from the main thread.
+pub fn ensure_only_instance(opener: OpenListener) -> IsOnlyInstance {
     if *db::ZED_STATELESS || *release_channel::RELEASE_CHANNEL == ReleaseChannel::Dev {
         return IsOnlyInstance::Yes;
     }
@@ -122,12 +130,46 @@ pub fn ensure_only_instance() -> IsOnlyInstance {
             _ = stream.set_nodelay(true);
             _ = stream.set_read_timeout(Some(SEND_TIMEOUT));
             _ = stream.write_all(instance_handshake().as_bytes());
+
+            // Other instances close the connection after the handshake, so this returns
+            // right away unless a ping follows.
+            _ = stream.set_read_timeout(Some(PING_TIMEOUT));
+            let mut buf = [0u8; 1024];
+            if let Ok(len) = stream.read(&mut buf) {
+                let message = String::from_utf8_lossy(&buf[..len]);
+                if message.starts_with(PING_URL_PREFIX) {
+                    opener.open_urls(vec![message.into_owned()]);
+                }
+            }
         }
     });
 
     IsOnlyInstance::Yes
 }
 
+/// Whether another instance answers the single-instance handshake.
+pub fn is_instance_running() -> bool {
+    check_got_handshake()
+}
+
+/// Sends a ping to the running instance once it answered the handshake, see
+/// [`ensure_only_instance`].
+pub fn send_ping(ping: &str) -> Result<()> {
+    let mut stream =
+        TcpStream::connect_timeout(&address(), CONNECT_TIMEOUT).context("no running instance")?;
+    let mut buf = vec![0u8; instance_handshake().len()];
+    stream.set_read_timeout(Some(RECEIVE_TIMEOUT))?;
+    stream
+        .read_exact(&mut buf)
+        .context("running instance did not answer the handshake")?;
+    anyhow::ensure!(
+        buf == instance_handshake().as_bytes(),
+        "unexpected handshake from running instance"
+    );
+    stream.write_all(ping.as_bytes())?;
+    Ok(())
+}
+
 fn check_got_handshake() -> bool {
     match TcpStream::connect_timeout(&address(), CONNECT_TIMEOUT) {
         Ok(mut stream) => {
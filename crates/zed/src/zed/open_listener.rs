@@ -1,36 +1,185 @@
 use crate::restorable_workspace_locations;
-use crate::{handle_open_request, init_headless, init_ui};
+use crate::{apply_welcome_choices, handle_open_request, init_headless, init_ui};
 use anyhow::{anyhow, Context, Result};
-use assistant::PromptBuilder;
+use assistant::{AssistantPanel, PromptBuilder};
 use cli::{ipc, IpcHandshake};
-use cli::{ipc::IpcSender, CliRequest, CliResponse};
+use cli::{ipc::IpcSender, CliRequest, CliResponse, PipedBuffer};
 use client::parse_zed_link;
+use collab_ui::{chat_panel::ChatPanel, notification_panel::NotificationPanel, CollabPanel};
 use collections::HashMap;
-use db::kvp::KEY_VALUE_STORE;
+use editor::actions::ExpandAllHunkDiffs;
 use editor::scroll::Autoscroll;
-use editor::Editor;
+use editor::{Editor, EditorEvent};
+use fs::{Encoding, Fs};
 use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
 use futures::channel::{mpsc, oneshot};
 use futures::{FutureExt, SinkExt, StreamExt};
-use gpui::{AppContext, AsyncAppContext, Global, WindowHandle};
-use language::{Bias, Point};
+use gpui::{
+    px, AppContext, AsyncAppContext, Global, Pixels, PromptLevel, View, ViewContext,
+    VisualContext as _, WindowHandle,
+};
+use language::{Bias, Capability, LineEnding, Point};
+use outline_panel::OutlinePanel;
+use project_panel::ProjectPanel;
 use remote::SshConnectionOptions;
+use settings::Settings;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use std::{process, thread};
-use util::paths::PathWithPosition;
-use util::ResultExt;
-use welcome::{show_welcome_view, FIRST_OPEN};
+use terminal_view::terminal_panel::TerminalPanel;
+use util::paths::{FileTarget, PathWithPosition};
+use util::{maybe, ResultExt};
+use welcome::{should_show_welcome_view, show_welcome_view};
+use workspace::dock::DockPosition;
 use workspace::item::ItemHandle;
-use workspace::{AppState, OpenOptions, Workspace};
+use workspace::notifications::{simple_message_notification::MessageNotification, NotificationId};
+use workspace::{AppState, OpenOptions, SplitDirection, Workspace, WorkspaceSettings};
+
+/// Sent over the single-instance socket by `zed --health-check`, followed by the
+/// path of the socket to reply on.
+pub const PING_URL_PREFIX: &str = "zed-ping://";
+const PING_RESPONSE: &[u8] = b"OK";
+/// Sent over the single-instance socket when Zed is launched again without any
+/// paths, to show a window of the running instance.
+pub const REOPEN_URL: &str = "zed-reopen://";
+/// Sent over the single-instance socket by `zed --quit`, to quit the running instance, e.g. one
+/// that keeps running without windows.
+pub const QUIT_URL: &str = "zed-quit://";
 
 #[derive(Default, Debug)]
 pub struct OpenRequest {
     pub cli_connection: Option<(mpsc::Receiver<CliRequest>, IpcSender<CliResponse>)>,
     pub open_paths: Vec<PathWithPosition>,
+    /// Where to open those of `open_paths` that were given with a pattern or byte offset.
+    pub open_path_targets: HashMap<PathBuf, FileTarget>,
     pub open_channel_notes: Vec<(u64, Option<String>)>,
     pub join_channel: Option<u64>,
     pub ssh_connection: Option<SshConnectionOptions>,
+    pub ping_reply_path: Option<PathBuf>,
+    pub reopen: bool,
+    pub quit: bool,
+    pub focus_panel: Option<StartupPanel>,
+    pub run_action: Option<String>,
+    /// Whether to show git blame in the opened files, see `zed --blame`.
+    pub show_blame: bool,
+    /// A git ref to diff the opened files against, see `zed --diff-git`.
+    pub diff_git_ref: Option<String>,
+    /// Whether to open the files read-only, see `zed --read-only`.
+    pub read_only: bool,
+    /// How to arrange the opened files in splits, see `zed --split`.
+    pub split: Option<StartupSplit>,
+    /// Pane and dock sizes to apply once the workspace has opened.
+    pub layout: StartupLayout,
+}
+
+/// A panel to focus once the workspace has opened, see `zed --panel`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum StartupPanel {
+    Project,
+    Outline,
+    Terminal,
+    Assistant,
+    Collab,
+    Chat,
+    Notifications,
+}
+
+impl StartupPanel {
+    /// Focuses and reveals the panel. Returns false if it hasn't been added to the workspace yet,
+    /// as panels are loaded asynchronously after the workspace opens.
+    pub fn focus(self, workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> bool {
+        match self {
+            Self::Project => workspace.focus_panel::<ProjectPanel>(cx).is_some(),
+            Self::Outline => workspace.focus_panel::<OutlinePanel>(cx).is_some(),
+            Self::Terminal => workspace.focus_panel::<TerminalPanel>(cx).is_some(),
+            Self::Assistant => workspace.focus_panel::<AssistantPanel>(cx).is_some(),
+            Self::Collab => workspace.focus_panel::<CollabPanel>(cx).is_some(),
+            Self::Chat => workspace.focus_panel::<ChatPanel>(cx).is_some(),
+            Self::Notifications => workspace.focus_panel::<NotificationPanel>(cx).is_some(),
+        }
+    }
+}
+
+/// How to arrange the files opened from the command line, see `zed --split`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum StartupSplit {
+    /// Side by side.
+    Vertical,
+    /// Stacked on top of each other.
+    Horizontal,
+}
+
+/// Pane and dock sizes to apply once the workspace has opened, see `zed --split-ratios` and
+/// `zed --dock-size`.
+#[derive(Clone, Debug, Default)]
+pub struct StartupLayout {
+    pub split_ratios: Vec<f32>,
+    pub dock_sizes: Vec<(DockPosition, Pixels)>,
+}
+
+impl StartupLayout {
+    pub fn is_empty(&self) -> bool {
+        self.split_ratios.is_empty() && self.dock_sizes.is_empty()
+    }
+}
+
+/// Parses a dock size given as `<DOCK>=<PIXELS>`, e.g. `left=320`.
+pub fn parse_dock_size(value: &str) -> Result<(DockPosition, Pixels)> {
+    let (dock, size) = value
+        .split_once('=')
+        .ok_or_else(|| anyhow!("expected <DOCK>=<PIXELS>, e.g. left=320"))?;
+    let position = match dock.trim() {
+        "left" => DockPosition::Left,
+        "right" => DockPosition::Right,
+        "bottom" => DockPosition::Bottom,
+        other => {
+            return Err(anyhow!(
+                "unknown dock {other:?}, expected left, right or bottom"
+            ))
+        }
+    };
+    let size = size.trim().parse::<f32>()?;
+    Ok((position, px(size)))
+}
+
+impl From<cli::SplitLayout> for StartupSplit {
+    fn from(layout: cli::SplitLayout) -> Self {
+        match layout {
+            cli::SplitLayout::Vertical => Self::Vertical,
+            cli::SplitLayout::Horizontal => Self::Horizontal,
+        }
+    }
+}
+
+impl StartupSplit {
+    /// Moves each item after the first into its own pane, split off from the previous item's pane.
+    pub fn arrange(
+        self,
+        items: &[Box<dyn ItemHandle>],
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let direction = match self {
+            Self::Vertical => SplitDirection::Right,
+            Self::Horizontal => SplitDirection::Down,
+        };
+        let mut items = items.iter();
+        let Some(mut previous_pane) = items
+            .next()
+            .and_then(|item| workspace.pane_for(item.as_ref()))
+        else {
+            return;
+        };
+        for item in items {
+            let Some(pane) = workspace.pane_for(item.as_ref()) else {
+                continue;
+            };
+            let new_pane = workspace.split_pane(previous_pane, direction, cx);
+            workspace::move_item(&pane, &new_pane, item.item_id(), 0, cx);
+            previous_pane = new_pane;
+        }
+    }
 }
 
 impl OpenRequest {
@@ -39,6 +188,12 @@ impl OpenRequest {
         for url in urls {
             if let Some(server_name) = url.strip_prefix("zed-cli://") {
                 this.cli_connection = Some(connect_to_cli(server_name)?);
+            } else if let Some(reply_path) = url.strip_prefix(PING_URL_PREFIX) {
+                this.ping_reply_path = Some(PathBuf::from(reply_path));
+            } else if url == REOPEN_URL {
+                this.reopen = true;
+            } else if url == QUIT_URL {
+                this.quit = true;
             } else if let Some(file) = url.strip_prefix("file://") {
                 this.parse_file_path(file)
             } else if let Some(file) = url.strip_prefix("zed://file") {
@@ -57,7 +212,10 @@ impl OpenRequest {
 
     fn parse_file_path(&mut self, file: &str) {
         if let Some(decoded) = urlencoding::decode(file).log_err() {
-            let path_buf = PathWithPosition::parse_str(&decoded);
+            let (path_buf, target) = parse_path_with_target(strip_slash_before_drive(&decoded));
+            if let Some(target) = target {
+                self.open_path_targets.insert(path_buf.path.clone(), target);
+            }
             self.open_paths.push(path_buf)
         }
     }
@@ -137,12 +295,48 @@ impl OpenListener {
     }
 }
 
+/// Whether an instance of any release channel is listening on its socket in the given support
+/// directory.
 #[cfg(target_os = "linux")]
-pub fn listen_for_cli_connections(opener: OpenListener) -> Result<()> {
+pub fn is_instance_listening_in(support_dir: &Path) -> bool {
+    use std::os::unix::net::UnixDatagram;
+
+    let Ok(entries) = std::fs::read_dir(support_dir) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        file_name.starts_with("zed-")
+            && file_name.ends_with(".sock")
+            && UnixDatagram::unbound()
+                .and_then(|socket| socket.connect(entry.path()))
+                .is_ok()
+    })
+}
+
+/// Whether an instance of this release channel is listening on its socket.
+#[cfg(target_os = "linux")]
+pub fn is_instance_running() -> bool {
+    use std::os::unix::net::UnixDatagram;
+
+    UnixDatagram::unbound()
+        .and_then(|socket| socket.connect(instance_socket_path()))
+        .is_ok()
+}
+
+#[cfg(target_os = "linux")]
+fn instance_socket_path() -> PathBuf {
     use release_channel::RELEASE_CHANNEL_NAME;
+
+    paths::support_dir().join(format!("zed-{}.sock", *RELEASE_CHANNEL_NAME))
+}
+
+#[cfg(target_os = "linux")]
+pub fn listen_for_cli_connections(opener: OpenListener) -> Result<()> {
     use std::os::unix::net::UnixDatagram;
 
-    let sock_path = paths::support_dir().join(format!("zed-{}.sock", *RELEASE_CHANNEL_NAME));
+    let sock_path = instance_socket_path();
     // remove the socket if the process listening on it has died
     if let Err(e) = UnixDatagram::unbound()?.connect(&sock_path) {
         if e.kind() == std::io::ErrorKind::ConnectionRefused {
@@ -159,6 +353,70 @@ pub fn listen_for_cli_connections(opener: OpenListener) -> Result<()> {
     Ok(())
 }
 
+/// Asks the running instance to answer a ping. The ping is handled on the main
+/// thread, so an instance whose main thread is hung fails to respond in time.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub fn ping_running_instance(timeout: Duration) -> Result<()> {
+    use std::os::unix::net::UnixDatagram;
+
+    let reply_path = paths::temp_dir().join(format!("zed-ping-{}.sock", process::id()));
+    std::fs::remove_file(&reply_path).ok();
+    let socket = UnixDatagram::bind(&reply_path)?;
+    let result = maybe!({
+        socket.set_read_timeout(Some(timeout))?;
+        let ping = format!("{PING_URL_PREFIX}{}", reply_path.display());
+        #[cfg(target_os = "linux")]
+        socket
+            .send_to(ping.as_bytes(), instance_socket_path())
+            .context("no running instance")?;
+        #[cfg(target_os = "macos")]
+        super::mac_only_instance::send_ping(&ping)?;
+        let mut buf = [0u8; 16];
+        let len = socket
+            .recv(&mut buf)
+            .context("running instance did not respond")?;
+        anyhow::ensure!(
+            &buf[..len] == PING_RESPONSE,
+            "unexpected response from running instance"
+        );
+        Ok(())
+    });
+    std::fs::remove_file(&reply_path).ok();
+    result
+}
+
+/// Asks the running instance to show a window, restoring the last session if it has none open.
+#[cfg(target_os = "linux")]
+pub fn reopen_running_instance() -> Result<()> {
+    use std::os::unix::net::UnixDatagram;
+
+    UnixDatagram::unbound()?
+        .send_to(REOPEN_URL.as_bytes(), instance_socket_path())
+        .context("no running instance")?;
+    Ok(())
+}
+
+/// Asks the running instance to quit, the same way the `zed: quit` action does.
+#[cfg(target_os = "linux")]
+pub fn quit_running_instance() -> Result<()> {
+    use std::os::unix::net::UnixDatagram;
+
+    UnixDatagram::unbound()?
+        .send_to(QUIT_URL.as_bytes(), instance_socket_path())
+        .context("no running instance")?;
+    Ok(())
+}
+
+#[cfg(unix)]
+pub fn reply_to_ping(reply_path: &std::path::Path) {
+    use std::os::unix::net::UnixDatagram;
+
+    UnixDatagram::unbound()
+        .and_then(|socket| socket.send_to(PING_RESPONSE, reply_path))
+        .context("error replying to health check")
+        .log_err();
+}
+
 fn connect_to_cli(
     server_name: &str,
 ) -> Result<(mpsc::Receiver<CliRequest>, IpcSender<CliResponse>)> {
@@ -188,8 +446,26 @@ fn connect_to_cli(
     Ok((async_request_rx, response_tx))
 }
 
+/// Turns the path of a URL to a file on Windows, like `/C:/dir/file.rs`, back into a file path.
+fn strip_slash_before_drive(path: &str) -> &str {
+    match path.as_bytes() {
+        [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => &path[1..],
+        _ => path,
+    }
+}
+
+/// Parses a path that may end with a `:row:column` position, or with a `:/pattern/` or
+/// `@offset` target. Paths that exist are never split.
+fn parse_path_with_target(s: &str) -> (PathWithPosition, Option<FileTarget>) {
+    match FileTarget::parse_str(s).filter(|_| !Path::new(s).exists()) {
+        Some((path, target)) => (PathWithPosition::from_path(path), Some(target)),
+        None => (PathWithPosition::parse_str(s), None),
+    }
+}
+
 pub async fn open_paths_with_positions(
     path_positions: &[PathWithPosition],
+    targets: &HashMap<PathBuf, FileTarget>,
     app_state: Arc<AppState>,
     open_options: workspace::OpenOptions,
     cx: &mut AsyncAppContext,
@@ -198,6 +474,7 @@ pub async fn open_paths_with_positions(
     Vec<Option<Result<Box<dyn ItemHandle>>>>,
 )> {
     let mut caret_positions = HashMap::default();
+    let read_only = open_options.read_only;
 
     let paths = path_positions
         .iter()
@@ -222,6 +499,37 @@ pub async fn open_paths_with_positions(
         let Some(Ok(item)) = item else {
             continue;
         };
+        if let Some(target) = targets.get(path) {
+            if let Some(active_editor) = item.downcast::<Editor>() {
+                workspace
+                    .update(cx, |_, cx| {
+                        active_editor.update(cx, |editor, cx| {
+                            let snapshot = editor.snapshot(cx).display_snapshot;
+                            let crlf =
+                                editor
+                                    .buffer()
+                                    .read(cx)
+                                    .as_singleton()
+                                    .is_some_and(|buffer| {
+                                        buffer.read(cx).line_ending() == LineEnding::Windows
+                                    });
+                            // Fall back to the top of the file, where it opens by default.
+                            let Some(offset) = target
+                                .resolve(&snapshot.buffer_snapshot.text(), crlf)
+                                .with_context(|| format!("can't open {path:?} at {target:?}"))
+                                .log_err()
+                            else {
+                                return;
+                            };
+                            editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                                s.select_ranges([offset..offset])
+                            });
+                        });
+                    })
+                    .log_err();
+            }
+            continue;
+        }
         let Some(point) = caret_positions.remove(path) else {
             continue;
         };
@@ -240,9 +548,91 @@ pub async fn open_paths_with_positions(
         }
     }
 
+    if read_only {
+        workspace
+            .update(cx, |_, cx| {
+                for item in items.iter().flatten().flatten() {
+                    if let Some(editor) = item.downcast::<Editor>() {
+                        offer_to_unlock_on_edit(editor, cx);
+                    }
+                }
+            })
+            .log_err();
+    }
+
     Ok((workspace, items))
 }
 
+/// Replaces the diff base of the editor's buffer with its contents at `git_ref`, and expands the
+/// resulting hunks so the changes can be reviewed inline.
+pub(crate) async fn diff_against_git_ref(
+    window: WindowHandle<Workspace>,
+    editor: View<Editor>,
+    git_ref: String,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    let buffer = editor
+        .update(cx, |editor, cx| editor.buffer().read(cx).as_singleton())?
+        .context("only single files can be diffed against a git ref")?;
+    window
+        .update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.set_diff_base_to_git_ref(buffer, git_ref, cx)
+            })
+        })?
+        .await?;
+    editor.update(cx, |editor, cx| {
+        editor.expand_all_hunk_diffs(&ExpandAllHunkDiffs, cx)
+    })
+}
+
+/// Shows a notification with a way to unlock a file opened with `zed --read-only`, once the
+/// user tries to type into it.
+fn offer_to_unlock_on_edit(editor: View<Editor>, cx: &mut ViewContext<Workspace>) {
+    struct ReadOnlyFileNotification;
+
+    cx.subscribe(&editor, |workspace, editor, event, cx| {
+        if !matches!(event, EditorEvent::EditRejected) {
+            return;
+        }
+        let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+            return;
+        };
+        // Files that are read-only for another reason, e.g. in a disconnected remote project,
+        // can't be unlocked.
+        if buffer.read(cx).capability() != Capability::ReadOnly
+            || workspace.project().read(cx).is_read_only()
+        {
+            return;
+        }
+        let file_name = buffer
+            .read(cx)
+            .file()
+            .map(|file| file.file_name(cx).to_string_lossy().into_owned())
+            .unwrap_or_else(|| "This file".to_string());
+        workspace.show_notification(
+            NotificationId::identified::<ReadOnlyFileNotification>(
+                editor.entity_id().as_u64() as usize
+            ),
+            cx,
+            |cx| {
+                cx.new_view(|_| {
+                    MessageNotification::new(format!(
+                        "{file_name} was opened read-only, and can't be edited."
+                    ))
+                    .with_click_message("Unlock")
+                    .on_click(move |cx| {
+                        buffer.update(cx, |buffer, cx| {
+                            buffer.set_capability(Capability::ReadWrite, cx)
+                        });
+                    })
+                })
+            },
+        );
+    })
+    .detach();
+}
+
 pub async fn handle_cli_connection(
     (mut requests, responses): (mpsc::Receiver<CliRequest>, IpcSender<CliResponse>),
     app_state: Arc<AppState>,
@@ -256,8 +646,16 @@ pub async fn handle_cli_connection(
                 paths,
                 wait,
                 open_new_workspace,
+                window_per_path,
                 dev_server_token,
                 env,
+                piped_buffer,
+                assume_yes,
+                diff_git_ref,
+                read_only,
+                split,
+                encoding,
+                line_endings,
             } => {
                 if let Some(dev_server_token) = dev_server_token {
                     match cx
@@ -327,16 +725,45 @@ pub async fn handle_cli_connection(
                     return;
                 }
 
-                let open_workspace_result = open_workspaces(
-                    paths,
-                    open_new_workspace,
-                    &responses,
-                    wait,
-                    app_state.clone(),
-                    env,
-                    &mut cx,
-                )
-                .await;
+                let encoding = match encoding {
+                    Some(name) => match Encoding::for_label(name.as_bytes()) {
+                        Some(encoding) => Some(encoding),
+                        None => {
+                            responses
+                                .send(CliResponse::Stderr {
+                                    message: format!("unknown encoding {name:?}"),
+                                })
+                                .log_err();
+                            responses.send(CliResponse::Exit { status: 1 }).log_err();
+                            return;
+                        }
+                    },
+                    None => None,
+                };
+                set_file_format_overrides(app_state.fs.as_ref(), &paths, encoding, line_endings);
+
+                let open_workspace_result = if let Some(piped_buffer) = piped_buffer {
+                    open_piped_buffer(piped_buffer, &responses, app_state.clone(), &mut cx).await
+                } else {
+                    open_workspaces(
+                        paths.clone(),
+                        open_new_workspace,
+                        window_per_path,
+                        assume_yes,
+                        diff_git_ref,
+                        read_only,
+                        split.map(StartupSplit::from),
+                        &responses,
+                        wait,
+                        app_state.clone(),
+                        env,
+                        &mut cx,
+                    )
+                    .await
+                };
+                if encoding.is_some() || line_endings.is_some() {
+                    clear_file_format_overrides(app_state.fs.as_ref(), &paths).await;
+                }
 
                 let status = if open_workspace_result.is_err() { 1 } else { 0 };
                 responses.send(CliResponse::Exit { status }).log_err();
@@ -345,15 +772,100 @@ pub async fn handle_cli_connection(
     }
 }
 
+/// Makes the given files open and save in the encoding and line endings asked for with
+/// `zed --encoding` and `zed --line-endings`, rather than the ones from their settings.
+/// This only applies to the buffers opened for them next, see [`Fs::take_file_format_override`].
+pub fn set_file_format_overrides(
+    fs: &dyn Fs,
+    paths: &[String],
+    encoding: Option<&'static Encoding>,
+    line_endings: Option<cli::LineEndings>,
+) {
+    let line_ending = line_endings.map(|line_endings| match line_endings {
+        cli::LineEndings::Lf => LineEnding::Unix,
+        cli::LineEndings::Crlf => LineEnding::Windows,
+    });
+    for path in paths {
+        let path = PathWithPosition::parse_str(path).path;
+        if path.is_dir() {
+            continue;
+        }
+        if let Some(encoding) = encoding {
+            fs.set_encoding(&path, encoding);
+        }
+        if let Some(line_ending) = line_ending {
+            fs.set_line_ending(&path, line_ending);
+        }
+    }
+}
+
+/// Drops the overrides of [`set_file_format_overrides`] that no buffer took, because the file
+/// was already open, so that they don't apply when the file is opened again later.
+async fn clear_file_format_overrides(fs: &dyn Fs, paths: &[String]) {
+    for path in paths {
+        let path = PathWithPosition::parse_str(path).path;
+        fs.take_file_format_override(&path).await;
+    }
+}
+
+/// Opens text piped into the CLI as an unsaved, untitled buffer in the active workspace.
+async fn open_piped_buffer(
+    piped_buffer: PipedBuffer,
+    responses: &IpcSender<CliResponse>,
+    app_state: Arc<AppState>,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    let language = match app_state
+        .languages
+        .language_for_name_or_extension(&piped_buffer.language)
+        .await
+    {
+        Ok(language) => Some(language),
+        Err(_) => {
+            responses
+                .send(CliResponse::Stderr {
+                    message: format!(
+                        "Unknown language {:?}, opening as plain text",
+                        piped_buffer.language
+                    ),
+                })
+                .log_err();
+            None
+        }
+    };
+
+    let workspace_window = workspace::get_any_active_workspace(app_state, cx.clone()).await?;
+    workspace_window
+        .update(cx, |workspace, cx| {
+            Editor::new_scratch_buffer_in_workspace(
+                workspace,
+                None,
+                piped_buffer.text,
+                language,
+                cx,
+            )
+        })?
+        .await?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn open_workspaces(
     paths: Vec<String>,
-    open_new_workspace: Option<bool>,
+    mut open_new_workspace: Option<bool>,
+    window_per_path: bool,
+    assume_yes: bool,
+    diff_git_ref: Option<String>,
+    read_only: bool,
+    split: Option<StartupSplit>,
     responses: &IpcSender<CliResponse>,
     wait: bool,
     app_state: Arc<AppState>,
     env: Option<collections::HashMap<String, String>>,
     cx: &mut AsyncAppContext,
 ) -> Result<()> {
+    let paths_given = !paths.is_empty();
+    let mut targets = HashMap::default();
     let grouped_paths = if paths.is_empty() {
         // If no paths are provided, restore from previous workspaces unless a new workspace is requested with -n
         if open_new_workspace == Some(true) {
@@ -382,20 +894,46 @@ async fn open_workspaces(
         }
     } else {
         // If paths are provided, parse them (they include positions)
-        let paths_with_position = paths
-            .into_iter()
-            .map(|path_with_position_string| {
-                PathWithPosition::parse_str(&path_with_position_string)
-            })
-            .collect();
-        vec![paths_with_position]
+        let paths_with_position = paths.into_iter().map(|path_with_position_string| {
+            let (path, target) = parse_path_with_target(&path_with_position_string);
+            if let Some(target) = target {
+                targets.insert(path.path.clone(), target);
+            }
+            path
+        });
+        if window_per_path {
+            open_new_workspace.get_or_insert(true);
+            paths_with_position.map(|path| vec![path]).collect()
+        } else {
+            vec![paths_with_position.collect()]
+        }
     };
 
+    if paths_given && !assume_yes {
+        let mut file_count = 0;
+        for path in grouped_paths.iter().flatten() {
+            if !app_state.fs.is_dir(&path.path).await {
+                file_count += 1;
+            }
+        }
+        if let Err(error) = confirm_opening_many_files(file_count, &app_state, cx).await {
+            responses
+                .send(CliResponse::Stderr {
+                    message: format!("{error}"),
+                })
+                .log_err();
+            return Err(error);
+        }
+    }
+
     if grouped_paths.is_empty() {
         // If we have no paths to open, show the welcome screen if this is the first launch
-        if matches!(KEY_VALUE_STORE.read_kvp(FIRST_OPEN), Ok(None)) {
-            cx.update(|cx| show_welcome_view(app_state, cx).detach())
-                .log_err();
+        if cx.update(should_show_welcome_view).unwrap_or(false) {
+            cx.spawn(|mut cx| async move {
+                let choices = cx.update(|cx| show_welcome_view(app_state, cx))?.await?;
+                cx.update(|cx| apply_welcome_choices(choices, cx))
+            })
+            .detach();
         }
         // If not the first launch, show an empty window with empty editor
         else {
@@ -418,7 +956,11 @@ async fn open_workspaces(
         for workspace_paths in grouped_paths {
             let workspace_failed_to_open = open_workspace(
                 workspace_paths,
+                &targets,
                 open_new_workspace,
+                diff_git_ref.as_deref(),
+                read_only,
+                split,
                 wait,
                 responses,
                 env.as_ref(),
@@ -440,9 +982,49 @@ async fn open_workspaces(
     Ok(())
 }
 
+/// Guards against opening more files than intended, e.g. when a glob matched a whole directory,
+/// see the `confirm_opening_many_files` setting.
+async fn confirm_opening_many_files(
+    file_count: usize,
+    app_state: &Arc<AppState>,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    let settings = cx.update(|cx| WorkspaceSettings::get_global(cx).confirm_opening_many_files)?;
+    let Some(threshold) = settings.threshold else {
+        return Ok(());
+    };
+    if file_count <= threshold {
+        return Ok(());
+    }
+    if !settings.prompt {
+        return Err(anyhow!(
+            "not opening {file_count} files without confirmation, use --yes to open them"
+        ));
+    }
+
+    let window = workspace::get_any_active_workspace(app_state.clone(), cx.clone()).await?;
+    let answer = window.update(cx, |_, cx| {
+        cx.prompt(
+            PromptLevel::Warning,
+            &format!("Open {file_count} files?"),
+            Some("This many files can take a while to open."),
+            &["Open", "Cancel"],
+        )
+    })?;
+    if answer.await? != 0 {
+        return Err(anyhow!("cancelled opening {file_count} files"));
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn open_workspace(
     workspace_paths: Vec<PathWithPosition>,
+    targets: &HashMap<PathBuf, FileTarget>,
     open_new_workspace: Option<bool>,
+    diff_git_ref: Option<&str>,
+    read_only: bool,
+    split: Option<StartupSplit>,
     wait: bool,
     responses: &IpcSender<CliResponse>,
     env: Option<&HashMap<String, String>>,
@@ -453,10 +1035,12 @@ async fn open_workspace(
 
     match open_paths_with_positions(
         &workspace_paths,
+        targets,
         app_state.clone(),
         workspace::OpenOptions {
             open_new_workspace,
             env: env.cloned(),
+            read_only,
             ..Default::default()
         },
         cx,
@@ -464,6 +1048,39 @@ async fn open_workspace(
     .await
     {
         Ok((workspace, items)) => {
+            if let Some(git_ref) = diff_git_ref {
+                for (item, path) in items.iter().zip(&workspace_paths) {
+                    let Some(editor) = item
+                        .as_ref()
+                        .and_then(|item| item.as_ref().ok()?.downcast::<Editor>())
+                    else {
+                        continue;
+                    };
+                    if let Err(err) =
+                        diff_against_git_ref(workspace, editor, git_ref.to_string(), cx).await
+                    {
+                        responses
+                            .send(CliResponse::Stderr {
+                                message: format!("error diffing {path:?} against {git_ref}: {err}"),
+                            })
+                            .log_err();
+                    }
+                }
+            }
+
+            if let Some(split) = split {
+                let opened_items = items
+                    .iter()
+                    .filter_map(|item| item.as_ref()?.as_ref().ok())
+                    .map(|item| item.boxed_clone())
+                    .collect::<Vec<_>>();
+                workspace
+                    .update(cx, |workspace, cx| {
+                        split.arrange(&opened_items, workspace, cx)
+                    })
+                    .log_err();
+            }
+
             let mut item_release_futures = Vec::new();
 
             for (item, path) in items.into_iter().zip(&workspace_paths) {
@@ -548,13 +1165,101 @@ mod tests {
         ipc::{self},
         CliResponse,
     };
+    use collections::HashMap;
     use editor::Editor;
-    use gpui::TestAppContext;
+    use gpui::{px, Task, TestAppContext};
     use serde_json::json;
+    use settings::SettingsStore;
     use util::paths::PathWithPosition;
-    use workspace::{AppState, Workspace};
+    use workspace::{dock::DockPosition, AppState, Workspace};
 
-    use crate::zed::{open_listener::open_workspace, tests::init_test};
+    use crate::zed::{
+        open_listener::{open_workspace, open_workspaces, parse_dock_size, OpenRequest},
+        tests::init_test,
+    };
+
+    #[gpui::test]
+    fn test_parse_ssh_url(cx: &mut TestAppContext) {
+        let request = cx
+            .update(|cx| {
+                OpenRequest::parse(
+                    vec!["ssh://me@example.com:2222/home/me/project%20x".into()],
+                    cx,
+                )
+            })
+            .unwrap();
+        let connection = request.ssh_connection.unwrap();
+        assert_eq!(connection.username.as_deref(), Some("me"));
+        assert_eq!(connection.host, "example.com");
+        assert_eq!(connection.port, Some(2222));
+        assert_eq!(
+            request.open_paths,
+            vec![PathWithPosition::from_path(PathBuf::from(
+                "/home/me/project x"
+            ))]
+        );
+
+        let error = cx.update(|cx| {
+            OpenRequest::parse(
+                vec!["file:///tmp".into(), "ssh://example.com/home".into()],
+                cx,
+            )
+        });
+        assert!(error.is_err());
+    }
+
+    #[test]
+    fn test_parse_dock_size() {
+        assert_eq!(
+            parse_dock_size("left=320").unwrap(),
+            (DockPosition::Left, px(320.))
+        );
+        assert_eq!(
+            parse_dock_size("bottom = 200.5").unwrap(),
+            (DockPosition::Bottom, px(200.5))
+        );
+        assert!(parse_dock_size("top=100").is_err());
+        assert!(parse_dock_size("right").is_err());
+        assert!(parse_dock_size("right=wide").is_err());
+    }
+
+    #[gpui::test]
+    fn test_parse_zed_file_link(cx: &mut TestAppContext) {
+        // As copied by `editor: copy zed link to line`.
+        let request = cx
+            .update(|cx| {
+                OpenRequest::parse(
+                    vec!["zed://file/home/me/my%20project/main.rs:3:5".into()],
+                    cx,
+                )
+            })
+            .unwrap();
+        assert_eq!(
+            request.open_paths,
+            vec![PathWithPosition {
+                path: PathBuf::from("/home/me/my project/main.rs"),
+                row: Some(3),
+                column: Some(5),
+            }]
+        );
+
+        let request = cx
+            .update(|cx| {
+                OpenRequest::parse(
+                    vec!["zed://file/C:/Users/me/my%20project/main.rs:3:5".into()],
+                    cx,
+                )
+            })
+            .unwrap();
+        assert_eq!(
+            request.open_paths,
+            vec![PathWithPosition {
+                path: PathBuf::from("C:/Users/me/my project/main.rs"),
+                row: Some(3),
+                column: Some(5),
+            }]
+        );
+    }
 
     #[gpui::test]
     async fn test_open_workspace_with_directory(cx: &mut TestAppContext) {
@@ -657,6 +1362,105 @@ mod tests {
             .unwrap();
     }
 
+    #[gpui::test]
+    async fn test_confirm_opening_many_files(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                "/root",
+                json!({ "a.txt": "", "b.txt": "", "c.txt": "", "dir": {} }),
+            )
+            .await;
+        set_confirm_opening_many_files(r#"{ "threshold": 2, "prompt": false }"#, cx);
+        let three_files = &["/root/a.txt", "/root/b.txt", "/root/c.txt"];
+
+        // Directories don't count towards the threshold.
+        let two_files_and_a_dir = &["/root/a.txt", "/root/b.txt", "/root/dir"];
+        let (result, stderr) = open_files(two_files_and_a_dir, false, &app_state, cx).await;
+        assert!(result.is_ok());
+        assert!(stderr.is_empty());
+
+        // Above the threshold, the files aren't opened without a prompt...
+        let (result, stderr) = open_files(three_files, false, &app_state, cx).await;
+        assert!(result.is_err());
+        assert_eq!(
+            stderr,
+            ["not opening 3 files without confirmation, use --yes to open them"]
+        );
+
+        // ...unless `--yes` is passed.
+        let (result, stderr) = open_files(three_files, true, &app_state, cx).await;
+        assert!(result.is_ok());
+        assert!(stderr.is_empty());
+
+        // With the prompt enabled, cancelling it doesn't open the files.
+        set_confirm_opening_many_files(r#"{ "threshold": 2, "prompt": true }"#, cx);
+        let task = open_files(three_files, false, &app_state, cx);
+        cx.run_until_parked();
+        assert!(cx.has_pending_prompt());
+        cx.simulate_prompt_answer(1);
+        let (result, stderr) = task.await;
+        assert!(result.is_err());
+        assert_eq!(stderr, ["cancelled opening 3 files"]);
+
+        // `--yes` skips the prompt too.
+        let (result, _) = open_files(three_files, true, &app_state, cx).await;
+        assert!(result.is_ok());
+        assert!(!cx.has_pending_prompt());
+    }
+
+    fn set_confirm_opening_many_files(settings: &str, cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store
+                    .set_user_settings(
+                        &format!(r#"{{ "confirm_opening_many_files": {settings} }}"#),
+                        cx,
+                    )
+                    .unwrap();
+            })
+        });
+    }
+
+    /// Opens the files like the CLI does, resolving to the result and the messages sent to
+    /// stderr.
+    fn open_files(
+        paths: &[&str],
+        assume_yes: bool,
+        app_state: &Arc<AppState>,
+        cx: &mut TestAppContext,
+    ) -> Task<(anyhow::Result<()>, Vec<String>)> {
+        let paths = paths.iter().map(|path| path.to_string()).collect();
+        let app_state = app_state.clone();
+        cx.spawn(|mut cx| async move {
+            let (response_tx, response_rx) = ipc::channel::<CliResponse>().unwrap();
+            let result = open_workspaces(
+                paths,
+                None,
+                false,
+                assume_yes,
+                None,
+                false,
+                None,
+                &response_tx,
+                false,
+                app_state,
+                None,
+                &mut cx,
+            )
+            .await;
+            let mut stderr = Vec::new();
+            while let Ok(response) = response_rx.try_recv() {
+                if let CliResponse::Stderr { message } = response {
+                    stderr.push(message);
+                }
+            }
+            (result, stderr)
+        })
+    }
+
     async fn open_workspace_file(
         path: &str,
         open_new_workspace: Option<bool>,
@@ -676,7 +1480,11 @@ mod tests {
             .spawn(|mut cx| async move {
                 open_workspace(
                     workspace_paths,
+                    &HashMap::default(),
                     open_new_workspace,
+                    None,
+                    false,
+                    None,
                     false,
                     &response_tx,
                     None,
@@ -0,0 +1,116 @@
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::{anyhow, Context as _, Result};
+use fs::Fs;
+use futures::{channel::mpsc, select_biased, AsyncReadExt as _, FutureExt as _, StreamExt as _};
+use gpui::{actions, AppContext, Global};
+use http_client::{AsyncBody, HttpClient};
+use settings::KeymapFile;
+use util::ResultExt as _;
+
+actions!(zed, [RefreshRemoteKeymap]);
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+struct RefreshRemoteKeymapSender(mpsc::UnboundedSender<()>);
+
+impl Global for RefreshRemoteKeymapSender {}
+
+/// The user keymap, fetched from the URL given with `--keymap-url` rather than read from
+/// the keymap file, so that a team can share one keymap.
+pub struct RemoteKeymap {
+    url: String,
+    keymap_tx: mpsc::UnboundedSender<String>,
+}
+
+impl RemoteKeymap {
+    /// Returns the keymap along with the receiver for its contents, which takes the place of
+    /// the watched keymap file.
+    pub fn new(url: String) -> (Self, mpsc::UnboundedReceiver<String>) {
+        let (keymap_tx, keymap_rx) = mpsc::unbounded();
+        (Self { url, keymap_tx }, keymap_rx)
+    }
+
+    /// Starts fetching the keymap. Call this once the http client has been configured.
+    ///
+    /// The keymap is fetched again every hour, and whenever `zed: refresh remote keymap`
+    /// runs. Until the first fetch succeeds, and whenever one fails, the copy cached by the
+    /// last successful fetch is used, or the local keymap file if there is none.
+    pub fn start(self, cx: &mut AppContext) {
+        let (refresh_tx, mut refresh_rx) = mpsc::unbounded();
+        cx.set_global(RefreshRemoteKeymapSender(refresh_tx));
+        cx.on_action(|_: &RefreshRemoteKeymap, cx| {
+            if let Some(sender) = cx.try_global::<RefreshRemoteKeymapSender>() {
+                sender.0.unbounded_send(()).ok();
+            }
+        });
+
+        let fs = <dyn Fs>::global(cx);
+        let http_client = cx.http_client();
+        let executor = cx.background_executor().clone();
+        executor
+            .clone()
+            .spawn(async move {
+                let cache_path = cache_path();
+                let mut contents = match fs.load(&cache_path).await {
+                    Ok(cached) => cached,
+                    Err(_) => fs.load(paths::keymap_file()).await.unwrap_or_default(),
+                };
+                if self.keymap_tx.unbounded_send(contents.clone()).is_err() {
+                    return;
+                }
+
+                loop {
+                    match fetch_keymap(http_client.as_ref(), &self.url).await {
+                        Ok(fetched) if fetched != contents => {
+                            if let Some(parent) = cache_path.parent() {
+                                fs.create_dir(parent).await.log_err();
+                            }
+                            fs.atomic_write(cache_path.clone(), fetched.clone())
+                                .await
+                                .log_err();
+                            if self.keymap_tx.unbounded_send(fetched.clone()).is_err() {
+                                return;
+                            }
+                            contents = fetched;
+                        }
+                        Ok(_) => {}
+                        Err(error) => log::warn!(
+                            "failed to fetch the keymap from {}, keeping the current one: {error:#}",
+                            self.url
+                        ),
+                    }
+
+                    select_biased! {
+                        refresh = refresh_rx.next() => {
+                            if refresh.is_none() {
+                                return;
+                            }
+                        }
+                        _ = executor.timer(REFRESH_INTERVAL).fuse() => {}
+                    }
+                }
+            })
+            .detach();
+    }
+}
+
+fn cache_path() -> PathBuf {
+    paths::support_dir().join("remote_keymap.json")
+}
+
+async fn fetch_keymap(http_client: &dyn HttpClient, url: &str) -> Result<String> {
+    let mut response = http_client.get(url, AsyncBody::empty(), true).await?;
+    if !response.status().is_success() {
+        return Err(anyhow!("status {}", response.status().as_u16()));
+    }
+    let mut contents = String::new();
+    response
+        .body_mut()
+        .read_to_string(&mut contents)
+        .await
+        .context("error reading the response")?;
+    // Don't replace a working keymap with one that can't be loaded.
+    KeymapFile::parse(&contents).context("invalid keymap")?;
+    Ok(contents)
+}
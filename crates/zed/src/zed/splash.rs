@@ -0,0 +1,102 @@
+use gpui::{
+    px, size, svg, AppContext, Bounds, Global, IntoElement, ParentElement, Render, SharedString,
+    Styled, ViewContext, VisualContext, WindowBounds, WindowHandle, WindowKind, WindowOptions,
+};
+use release_channel::ReleaseChannel;
+use settings::Settings;
+use theme::ActiveTheme;
+use ui::{v_flex, Color, Label, LabelCommon, LabelSize};
+use workspace::{Workspace, WorkspaceSettings};
+
+/// A small window shown while Zed is starting up, see the `show_startup_splash` setting.
+pub struct SplashScreen {
+    status: SharedString,
+}
+
+impl Render for SplashScreen {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .justify_center()
+            .items_center()
+            .gap_4()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                svg()
+                    .path("icons/logo_96.svg")
+                    .text_color(cx.theme().colors().icon_disabled)
+                    .w(px(64.))
+                    .h(px(64.)),
+            )
+            .child(
+                Label::new(self.status.clone())
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+    }
+}
+
+struct SplashWindow(WindowHandle<SplashScreen>);
+
+impl Global for SplashWindow {}
+
+/// Opens the splash window if it is enabled in the settings.
+///
+/// The window closes itself as soon as the first workspace is created.
+pub fn open_splash_window(cx: &mut AppContext) {
+    if !WorkspaceSettings::get_global(cx).show_startup_splash || cx.has_global::<SplashWindow>() {
+        return;
+    }
+
+    let options = WindowOptions {
+        window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+            None,
+            size(px(320.), px(200.)),
+            cx,
+        ))),
+        titlebar: None,
+        focus: false,
+        show: true,
+        kind: WindowKind::PopUp,
+        is_movable: false,
+        app_id: Some(ReleaseChannel::global(cx).app_id().to_owned()),
+        ..Default::default()
+    };
+    let window = cx.open_window(options, |cx| {
+        cx.new_view(|_| SplashScreen {
+            status: "Loading…".into(),
+        })
+    });
+    match window {
+        Ok(window) => {
+            cx.set_global(SplashWindow(window));
+            cx.observe_new_views(|_: &mut Workspace, cx| close_splash_window(cx))
+                .detach();
+        }
+        Err(error) => log::warn!("failed to open the splash window: {error:?}"),
+    }
+}
+
+/// Updates the progress message shown in the splash window, if it is open.
+pub fn set_splash_status(status: impl Into<SharedString>, cx: &mut AppContext) {
+    let Some(SplashWindow(window)) = cx.try_global::<SplashWindow>() else {
+        return;
+    };
+    let window = *window;
+    let status = status.into();
+    window
+        .update(cx, |splash, cx| {
+            splash.status = status;
+            cx.notify();
+        })
+        .ok();
+}
+
+/// Closes the splash window, if it is open.
+pub fn close_splash_window(cx: &mut AppContext) {
+    if !cx.has_global::<SplashWindow>() {
+        return;
+    }
+    let SplashWindow(window) = cx.remove_global::<SplashWindow>();
+    window.update(cx, |_, cx| cx.remove_window()).ok();
+}
@@ -0,0 +1,319 @@
+//! Periodic snapshots of unsaved changes, so that they can be recovered after Zed crashes or is
+//! killed. Each session writes its snapshots to its own directory, which is removed when the
+//! session ends cleanly, so a directory left behind by a session whose process is gone means it
+//! didn't. The directories are scoped to the release channel, and named after the process id of
+//! their session, so that instances running side by side never recover each other's changes.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use collections::HashMap;
+use editor::Editor;
+use fs::{Fs, RemoveOptions};
+use gpui::{AppContext, AsyncAppContext, DismissEvent, Global, ViewContext, VisualContext as _};
+use release_channel::ReleaseChannel;
+use serde::{Deserialize, Serialize};
+use util::ResultExt as _;
+use uuid::Uuid;
+use workspace::{
+    notifications::{
+        simple_message_notification::MessageNotification, DetachAndPromptErr, NotificationId,
+    },
+    Workspace,
+};
+
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    path: PathBuf,
+    contents: String,
+}
+
+struct RecoveredChanges {
+    snapshot_path: PathBuf,
+    snapshot: Snapshot,
+}
+
+struct RecoveredChangesQueue(Vec<RecoveredChanges>);
+
+impl Global for RecoveredChangesQueue {}
+
+/// Looks for changes left behind by previous sessions, and starts snapshotting the unsaved
+/// changes of this one.
+pub fn init(fs: Arc<dyn Fs>, cx: &mut AppContext) {
+    let channel_dir = paths::unsaved_changes_dir().join(ReleaseChannel::global(cx).dev_name());
+    let session_dir = channel_dir.join(format!("{}-{}", std::process::id(), Uuid::new_v4()));
+    cx.set_global(RecoveredChangesQueue(load_recovered_changes(
+        &channel_dir,
+        is_process_running,
+    )));
+
+    cx.on_app_quit({
+        let session_dir = session_dir.clone();
+        move |_| {
+            std::fs::remove_dir_all(&session_dir).ok();
+            async {}
+        }
+    })
+    .detach();
+    cx.spawn(|cx| snapshot_unsaved_changes(fs, session_dir, cx))
+        .detach();
+}
+
+/// Offers to restore each file with changes recovered from a previous session, in the first
+/// local workspace that opens.
+pub fn offer_to_recover_changes(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    if !workspace.project().read(cx).is_local() || !cx.has_global::<RecoveredChangesQueue>() {
+        return;
+    }
+    let recovered_changes = std::mem::take(&mut cx.global_mut::<RecoveredChangesQueue>().0);
+
+    struct RecoveredChangesNotification;
+    for recovered in recovered_changes {
+        let workspace_handle = cx.view().downgrade();
+        let path = recovered.snapshot.path.clone();
+        let recovered = Arc::new(parking_lot::Mutex::new(Some(recovered)));
+        workspace.show_notification(
+            NotificationId::identified::<RecoveredChangesNotification>(
+                path.to_string_lossy().into_owned(),
+            ),
+            cx,
+            |cx| {
+                let restored = recovered.clone();
+                let discarded = recovered.clone();
+                cx.new_view(|_| {
+                    MessageNotification::new(format!(
+                        "Recovered unsaved changes to {} from a session that didn't exit cleanly",
+                        path.display()
+                    ))
+                    .with_click_message("Restore")
+                    .on_click(move |cx| {
+                        if let Some(recovered) = restored.lock().take() {
+                            workspace_handle
+                                .update(cx, |workspace, cx| {
+                                    restore_changes(workspace, recovered, cx)
+                                })
+                                .log_err();
+                        }
+                        cx.emit(DismissEvent);
+                    })
+                    .with_secondary_click_message("Discard")
+                    .on_secondary_click(move |cx| {
+                        if let Some(recovered) = discarded.lock().take() {
+                            remove_snapshot(&recovered.snapshot_path);
+                        }
+                        cx.emit(DismissEvent);
+                    })
+                })
+            },
+        );
+    }
+}
+
+fn restore_changes(
+    workspace: &mut Workspace,
+    recovered: RecoveredChanges,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let open = workspace.open_abs_path(recovered.snapshot.path.clone(), true, cx);
+    cx.spawn(|_, mut cx| async move {
+        let editor = open
+            .await?
+            .downcast::<Editor>()
+            .context("the file isn't open in an editor")?;
+        editor.update(&mut cx, |editor, cx| {
+            editor.set_text(recovered.snapshot.contents, cx)
+        })?;
+        remove_snapshot(&recovered.snapshot_path);
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Failed to restore unsaved changes", cx, |_, _| None);
+}
+
+fn remove_snapshot(snapshot_path: &Path) {
+    std::fs::remove_file(snapshot_path).log_err();
+    // Fails unless this was the last snapshot left by its session.
+    if let Some(session_dir) = snapshot_path.parent() {
+        std::fs::remove_dir(session_dir).ok();
+    }
+}
+
+/// Reads the snapshots left by sessions that didn't exit cleanly, skipping the directories of
+/// sessions that are still running. Snapshots that are older than their file were saved over in
+/// the meantime, so they're deleted instead.
+fn load_recovered_changes(
+    channel_dir: &Path,
+    is_process_running: impl Fn(u32) -> bool,
+) -> Vec<RecoveredChanges> {
+    let Ok(session_dirs) = std::fs::read_dir(channel_dir) else {
+        return Vec::new();
+    };
+
+    let mut recovered_changes = Vec::new();
+    for session_dir in session_dirs.filter_map(|entry| Some(entry.ok()?.path())) {
+        let session_pid = session_dir
+            .file_name()
+            .and_then(|name| name.to_str()?.split_once('-')?.0.parse::<u32>().ok());
+        if session_pid.map_or(false, |pid| {
+            pid != std::process::id() && is_process_running(pid)
+        }) {
+            continue;
+        }
+        let Ok(snapshot_paths) = std::fs::read_dir(&session_dir) else {
+            continue;
+        };
+        for snapshot_path in snapshot_paths.filter_map(|entry| Some(entry.ok()?.path())) {
+            let snapshot = std::fs::read_to_string(&snapshot_path)
+                .ok()
+                .and_then(|json| serde_json::from_str::<Snapshot>(&json).log_err());
+            let Some(snapshot) = snapshot else {
+                std::fs::remove_file(&snapshot_path).log_err();
+                continue;
+            };
+            let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+            if modified(&snapshot.path) > modified(&snapshot_path) {
+                std::fs::remove_file(&snapshot_path).log_err();
+                continue;
+            }
+            recovered_changes.push(RecoveredChanges {
+                snapshot_path,
+                snapshot,
+            });
+        }
+        std::fs::remove_dir(&session_dir).ok();
+    }
+    recovered_changes
+}
+
+fn is_process_running(pid: u32) -> bool {
+    let mut system = sysinfo::System::new();
+    system.refresh_processes_specifics(
+        sysinfo::ProcessesToUpdate::Some(&[sysinfo::Pid::from_u32(pid)]),
+        sysinfo::ProcessRefreshKind::new(),
+    ) == 1
+}
+
+/// Writes the contents of every dirty buffer to the session's directory, and removes the
+/// snapshots of buffers that have since been saved, reverted or closed.
+async fn snapshot_unsaved_changes(fs: Arc<dyn Fs>, session_dir: PathBuf, cx: AsyncAppContext) {
+    let mut written: HashMap<String, _> = HashMap::default();
+    loop {
+        cx.background_executor().timer(SNAPSHOT_INTERVAL).await;
+
+        let Ok((current, changed)) = cx.update(|cx| {
+            let mut current = HashMap::default();
+            let mut changed = Vec::new();
+            for workspace in workspace::workspace_windows(cx) {
+                let Ok(workspace) = workspace.read(cx) else {
+                    continue;
+                };
+                for buffer in workspace.project().read(cx).opened_buffers(cx) {
+                    let file_name = format!("{}.json", buffer.entity_id().as_u64());
+                    let buffer = buffer.read(cx);
+                    if !buffer.is_dirty() {
+                        continue;
+                    }
+                    let Some(file) = buffer.file().and_then(|file| file.as_local()) else {
+                        continue;
+                    };
+                    let version = buffer.version();
+                    if written.get(&file_name) != Some(&version) {
+                        changed.push((
+                            file_name.clone(),
+                            Snapshot {
+                                path: file.abs_path(cx),
+                                contents: buffer.text(),
+                            },
+                        ));
+                    }
+                    current.insert(file_name, version);
+                }
+            }
+            (current, changed)
+        }) else {
+            return;
+        };
+
+        if !changed.is_empty() {
+            fs.create_dir(&session_dir).await.log_err();
+        }
+        for (file_name, snapshot) in changed {
+            if let Some(json) = serde_json::to_string(&snapshot).log_err() {
+                fs.atomic_write(session_dir.join(file_name), json)
+                    .await
+                    .log_err();
+            }
+        }
+        for file_name in written.keys() {
+            if !current.contains_key(file_name) {
+                fs.remove_file(
+                    &session_dir.join(file_name),
+                    RemoveOptions {
+                        ignore_if_not_exists: true,
+                        ..Default::default()
+                    },
+                )
+                .await
+                .log_err();
+            }
+        }
+        written = current;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    fn write_snapshot(snapshot_path: &Path, path: &Path, contents: &str) {
+        std::fs::create_dir_all(snapshot_path.parent().unwrap()).unwrap();
+        let snapshot = Snapshot {
+            path: path.to_path_buf(),
+            contents: contents.to_string(),
+        };
+        std::fs::write(snapshot_path, serde_json::to_string(&snapshot).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_recovering_changes_skips_running_sessions() {
+        let channel_dir = tempfile::tempdir().unwrap();
+        let running_snapshot = channel_dir.path().join("111-running/1.json");
+        let crashed_snapshot = channel_dir.path().join("222-crashed/2.json");
+        write_snapshot(&running_snapshot, Path::new("/a.txt"), "running");
+        write_snapshot(&crashed_snapshot, Path::new("/b.txt"), "crashed");
+
+        let recovered = load_recovered_changes(channel_dir.path(), |pid| pid == 111);
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].snapshot_path, crashed_snapshot);
+        assert_eq!(recovered[0].snapshot.contents, "crashed");
+
+        remove_snapshot(&recovered[0].snapshot_path);
+        assert!(!crashed_snapshot.parent().unwrap().exists());
+        assert!(running_snapshot.exists());
+    }
+
+    #[test]
+    fn test_recovering_changes_discards_saved_over_snapshots() {
+        let channel_dir = tempfile::tempdir().unwrap();
+        let file_path = channel_dir.path().join("file.txt");
+        std::fs::write(&file_path, "saved").unwrap();
+        let snapshot_path = channel_dir.path().join("333-crashed/1.json");
+        write_snapshot(&snapshot_path, &file_path, "unsaved");
+        std::fs::File::options()
+            .write(true)
+            .open(&snapshot_path)
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH)
+            .unwrap();
+
+        let recovered = load_recovered_changes(channel_dir.path(), |_| false);
+        assert!(recovered.is_empty());
+        assert!(!snapshot_path.exists());
+    }
+}
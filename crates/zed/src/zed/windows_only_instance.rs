@@ -2,8 +2,8 @@ use release_channel::ReleaseChannel;
 use windows::{
     core::HSTRING,
     Win32::{
-        Foundation::{GetLastError, ERROR_ALREADY_EXISTS},
-        System::Threading::CreateEventW,
+        Foundation::{CloseHandle, GetLastError, ERROR_ALREADY_EXISTS},
+        System::Threading::{CreateEventW, OpenEventW, SYNCHRONIZATION_SYNCHRONIZE},
     },
 };
 
@@ -37,3 +37,16 @@ fn check_single_instance_event() -> bool {
     let last_err = unsafe { GetLastError() };
     last_err != ERROR_ALREADY_EXISTS
 }
+
+/// Whether another instance is running, without becoming the single instance like
+/// [`check_single_instance`] does.
+pub fn is_instance_running() -> bool {
+    let identifier = HSTRING::from(retrieve_app_instance_event_identifier());
+    match unsafe { OpenEventW(SYNCHRONIZATION_SYNCHRONIZE, false, &identifier) } {
+        Ok(event) => {
+            unsafe { CloseHandle(event) }.ok();
+            true
+        }
+        Err(_) => false,
+    }
+}